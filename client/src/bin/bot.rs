@@ -0,0 +1,209 @@
+use bevy::app::{App, AppExit, PluginGroup, ScheduleRunnerPlugin};
+use bevy::asset::{AssetApp, AssetPlugin};
+use bevy::camera::visibility::VisibilityPlugin;
+use bevy::log::{error, info, warn, LogPlugin};
+use bevy::mesh::Mesh;
+use bevy::prelude::{ColorMaterial, TransformPlugin};
+use bevy::utils::default;
+use bevy::MinimalPlugins;
+use bevy_renet::RenetClientPlugin;
+use client::bot::{BotBehavior, BotLeave, BotScript, LoadReport};
+use client::plugin::bot_plugin::BotPlugin;
+use client::plugin::client_plugin::ClientPlugin;
+use client::plugin::game_plugin::GamePlugin;
+use client::resource::{BotState, CurrentClientId, PlayerProfile};
+use game_core::network::get_current_time;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Durée d'une frame de la boucle des bots (60 Hz).
+const FRAME: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+/// Intervalle entre deux bilans intermédiaires.
+const REPORT_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Délai accordé aux bots pour faire acquitter leur départ par le serveur.
+const LEAVE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Intervalle entre deux changements de direction d'un bot aléatoire, en secondes.
+const RANDOM_CHANGE_EVERY: f32 = 1.0;
+
+/// Bots de test de charge : lance `BOT_COUNT` clients simulés dans ce processus,
+/// chacun dans sa propre application Bevy sans rendu, et affiche régulièrement
+/// le taux de connexion, le temps aller-retour et la bande passante.
+///
+/// Variables d'environnement :
+/// - `BOT_COUNT` : nombre de bots (10 par défaut).
+/// - `BOT_RAMP_MS` : délai entre le lancement de deux bots (20 ms par défaut).
+/// - `BOT_DURATION` : durée du test en secondes ; jusqu'à Ctrl+C si absente.
+/// - `BOT_SCRIPT` : script RON des déplacements (voir `BotScript`) ; déplacements
+///   aléatoires si absent.
+/// - `BOT_CLIENT_ID` : identifiant du premier bot, incrémenté pour les suivants.
+/// - `PLAYER_PASSWORD` : mot de passe du serveur.
+///
+/// À l'arrêt, chaque bot quitte le serveur avec `ClientMessages::Leave` avant de
+/// se déconnecter, pour que le `ServerLobby` soit libéré sans attendre le délai
+/// de reconnexion.
+fn main() {
+    init_logging();
+
+    let count: usize = env_parse("BOT_COUNT").unwrap_or(10);
+    let ramp = Duration::from_millis(env_parse("BOT_RAMP_MS").unwrap_or(20));
+    let duration = env_parse("BOT_DURATION").map(Duration::from_secs_f32);
+    let first_client_id =
+        env_parse("BOT_CLIENT_ID").unwrap_or_else(|| get_current_time().as_millis() as u64);
+    let password = std::env::var("PLAYER_PASSWORD")
+        .ok()
+        .filter(|password| !password.is_empty());
+    let behavior = match std::env::var_os("BOT_SCRIPT").filter(|path| !path.is_empty()) {
+        Some(path) => match BotScript::load(&PathBuf::from(path)) {
+            Ok(script) => BotBehavior::Script(script),
+            Err(err) => {
+                error!("{err}");
+                std::process::exit(1);
+            }
+        },
+        None => BotBehavior::Random {
+            change_every: RANDOM_CHANGE_EVERY,
+        },
+    };
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let signal = stop.clone();
+    if let Err(e) = ctrlc::set_handler(move || signal.store(true, Ordering::SeqCst)) {
+        warn!("Failed to install the Ctrl+C handler: {e}");
+    }
+
+    let started_at = Instant::now();
+    let mut bots: Vec<App> = Vec::with_capacity(count);
+    let mut next_report = started_at + REPORT_INTERVAL;
+    loop {
+        let frame_start = Instant::now();
+        if stop.load(Ordering::SeqCst) || duration.is_some_and(|d| started_at.elapsed() >= d) {
+            break;
+        }
+
+        while bots.len() < count && started_at + ramp * bots.len() as u32 <= frame_start {
+            let index = bots.len();
+            bots.push(build_bot(
+                index,
+                first_client_id + index as u64,
+                password.clone(),
+                behavior.clone(),
+            ));
+        }
+        for bot in &mut bots {
+            bot.update();
+        }
+
+        if frame_start >= next_report {
+            next_report += REPORT_INTERVAL;
+            info!("{}", load_report(&bots));
+        }
+        std::thread::sleep(FRAME.saturating_sub(frame_start.elapsed()));
+    }
+
+    let report = load_report(&bots);
+    leave(&mut bots);
+    info!(
+        "Final report after {:.1}s: {report}",
+        started_at.elapsed().as_secs_f32()
+    );
+}
+
+/// Installe la journalisation, globale au processus, avant le lancement des bots.
+fn init_logging() {
+    App::new().add_plugins(LogPlugin {
+        filter: "warn,bot=info".to_string(),
+        ..default()
+    });
+}
+
+/// Crée l'application d'un bot : réseau et état du jeu du client, sans rendu.
+///
+/// Le profil du bot ne reprend de l'environnement que le mot de passe du serveur :
+/// `PLAYER_NAME`, `PLAYER_COLOR` et `PLAYER_SPECTATOR` concernent le client graphique.
+fn build_bot(index: usize, client_id: u64, password: Option<String>, behavior: BotBehavior) -> App {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins.build().disable::<ScheduleRunnerPlugin>());
+    // Ressources et composants des entités du jeu, sans leur rendu.
+    app.add_plugins((AssetPlugin::default(), TransformPlugin, VisibilityPlugin));
+    app.init_asset::<Mesh>();
+    app.init_asset::<ColorMaterial>();
+
+    let profile = PlayerProfile {
+        name: format!("Bot {index}"),
+        password,
+        ..default()
+    };
+    app.insert_resource(CurrentClientId(client_id));
+    app.insert_resource(profile);
+
+    app.add_plugins(RenetClientPlugin);
+    app.add_plugins(GamePlugin);
+    app.add_plugins(ClientPlugin);
+    app.add_plugins(BotPlugin {
+        behavior,
+        phase: fastrand::f32() * 10.0,
+    });
+
+    app.finish();
+    app.cleanup();
+    app
+}
+
+/// Fait quitter le serveur à tous les bots, puis les déconnecte.
+fn leave(bots: &mut [App]) {
+    for bot in bots.iter_mut() {
+        let mut state = bot.world_mut().resource_mut::<BotState>();
+        if state.leave == BotLeave::Playing {
+            state.leave = BotLeave::Requested;
+        }
+    }
+
+    let deadline = Instant::now() + LEAVE_TIMEOUT;
+    loop {
+        let frame_start = Instant::now();
+        for bot in bots.iter_mut() {
+            bot.update();
+        }
+        let pending = bots
+            .iter()
+            .filter(|bot| bot.world().resource::<BotState>().leave != BotLeave::Done)
+            .count();
+        if pending == 0 {
+            break;
+        }
+        if frame_start >= deadline {
+            warn!("{pending} bots did not leave in time, the server will keep their slot until they time out");
+            break;
+        }
+        std::thread::sleep(FRAME.saturating_sub(frame_start.elapsed()));
+    }
+
+    for bot in bots.iter_mut() {
+        bot.world_mut().write_message(AppExit::Success);
+        bot.update();
+    }
+}
+
+fn load_report(bots: &[App]) -> LoadReport {
+    LoadReport::new(
+        bots.iter()
+            .map(|bot| &bot.world().resource::<BotState>().stats),
+    )
+}
+
+/// Lit une variable d'environnement numérique. Une valeur mal formée est ignorée.
+fn env_parse<T: std::str::FromStr>(key: &str) -> Option<T> {
+    let value = std::env::var(key).ok()?;
+    match value.trim().parse() {
+        Ok(value) => Some(value),
+        Err(_) => {
+            warn!("Ignoring invalid {key}={value}");
+            None
+        }
+    }
+}
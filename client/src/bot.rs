@@ -0,0 +1,367 @@
+use bevy::prelude::Vec2;
+use serde::Deserialize;
+use std::fmt;
+use std::path::Path;
+
+/// Étape d'un script de bot : direction maintenue pendant `secs` secondes.
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct BotStep {
+    pub direction: (f32, f32),
+    pub secs: f32,
+}
+
+/// Script de déplacement d'un bot, joué en boucle.
+///
+/// Chargé depuis un fichier RON, par exemple :
+///
+/// ```ron
+/// (steps: [
+///     (direction: (1.0, 0.0), secs: 2.0),
+///     (direction: (-1.0, 0.0), secs: 2.0),
+/// ])
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct BotScript {
+    pub steps: Vec<BotStep>,
+}
+
+impl BotScript {
+    /// Charge un script depuis un fichier RON.
+    ///
+    /// Retourne une erreur si le fichier ne peut pas être lu ou interprété, ou
+    /// s'il ne contient aucune étape de durée positive.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|err| format!("Failed to read {}: {err}", path.display()))?;
+        let script: Self = ron::from_str(&content)
+            .map_err(|err| format!("Invalid bot script {}: {err}", path.display()))?;
+        if script.duration() <= 0.0 {
+            return Err(format!("Bot script {} has no steps", path.display()));
+        }
+        Ok(script)
+    }
+
+    /// Durée d'un passage complet du script, en secondes.
+    pub fn duration(&self) -> f32 {
+        self.steps.iter().map(|step| step.secs.max(0.0)).sum()
+    }
+
+    /// Direction du script `elapsed_secs` secondes après son début.
+    pub fn direction_at(&self, elapsed_secs: f32) -> Vec2 {
+        let mut remaining = elapsed_secs.rem_euclid(self.duration());
+        for step in &self.steps {
+            let secs = step.secs.max(0.0);
+            if remaining < secs {
+                let (x, y) = step.direction;
+                return Vec2::new(x, y).normalize_or_zero();
+            }
+            remaining -= secs;
+        }
+        Vec2::ZERO
+    }
+}
+
+/// Comportement d'un bot.
+#[derive(Debug, Clone)]
+pub enum BotBehavior {
+    /// Change de direction au hasard toutes les `change_every` secondes (l'immobilité
+    /// fait partie des choix possibles).
+    Random { change_every: f32 },
+    /// Joue un script en boucle, décalé pour chaque bot afin de ne pas les synchroniser.
+    Script(BotScript),
+}
+
+/// Avancement du départ d'un bot.
+///
+/// Le bot annonce son départ (`ClientMessages::Leave`) et attend que le serveur
+/// l'ait acquitté avant de se déconnecter : le serveur libère alors sa place
+/// immédiatement, au lieu de la réserver pendant le délai de reconnexion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BotLeave {
+    /// Le bot joue.
+    Playing,
+    /// Le départ a été demandé ; le message sera envoyé à la prochaine frame.
+    Requested,
+    /// Le message de départ a été envoyé et attend son acquittement.
+    Sent,
+    /// Le bot peut se déconnecter.
+    Done,
+}
+
+/// Tire une direction au hasard : l'une des huit directions, ou l'immobilité.
+pub fn random_direction() -> Vec2 {
+    let index = fastrand::u8(0..9);
+    if index == 8 {
+        return Vec2::ZERO;
+    }
+    Vec2::from_angle(index as f32 * std::f32::consts::FRAC_PI_4)
+}
+
+/// Statistiques réseau d'un bot.
+///
+/// - `connected_after` : durée de la connexion au serveur, une fois établie.
+/// - `connected` : le bot est actuellement connecté.
+/// - `disconnected` : le transport du bot a été fermé (refus, expiration ou perte
+///   de connexion).
+/// - `rtt_sum` / `rtt_samples` / `rtt_max` : temps aller-retour observés, en secondes.
+/// - `bytes_sent_per_sec` / `bytes_received_per_sec` / `packet_loss` : dernières
+///   mesures de renet.
+#[derive(Debug, Clone, Default)]
+pub struct BotNetworkStats {
+    pub connected_after: Option<f32>,
+    pub connected: bool,
+    pub disconnected: bool,
+    pub rtt_sum: f64,
+    pub rtt_samples: u32,
+    pub rtt_max: f64,
+    pub bytes_sent_per_sec: f64,
+    pub bytes_received_per_sec: f64,
+    pub packet_loss: f64,
+}
+
+impl BotNetworkStats {
+    /// Temps aller-retour moyen, en secondes.
+    pub fn rtt_mean(&self) -> Option<f64> {
+        (self.rtt_samples > 0).then(|| self.rtt_sum / self.rtt_samples as f64)
+    }
+}
+
+/// Bilan de charge agrégé sur tous les bots.
+#[derive(Debug, Clone, Default)]
+pub struct LoadReport {
+    /// Nombre de bots lancés.
+    pub bots: usize,
+    /// Bots connectés au moins une fois.
+    pub succeeded: usize,
+    /// Bots actuellement connectés.
+    pub connected: usize,
+    /// Bots déconnectés sans jamais avoir été connectés.
+    pub failed: usize,
+    /// Bots déconnectés après avoir été connectés.
+    pub dropped: usize,
+    /// Durée moyenne de connexion, en secondes.
+    pub connect_time_mean: Option<f32>,
+    /// Temps aller-retour moyen et maximal des bots connectés, en secondes.
+    pub rtt_mean: Option<f64>,
+    pub rtt_max: f64,
+    /// Débits cumulés des bots connectés, en octets par seconde.
+    pub bytes_sent_per_sec: f64,
+    pub bytes_received_per_sec: f64,
+    /// Perte de paquets moyenne des bots connectés.
+    pub packet_loss_mean: f64,
+}
+
+impl LoadReport {
+    /// Agrège les statistiques des bots.
+    pub fn new<'a>(stats: impl IntoIterator<Item = &'a BotNetworkStats>) -> Self {
+        let mut report = LoadReport::default();
+        let mut connect_time_sum = 0.0;
+        let mut rtt_sum = 0.0;
+        let mut rtt_samples = 0;
+
+        for stats in stats {
+            report.bots += 1;
+            if let Some(connected_after) = stats.connected_after {
+                report.succeeded += 1;
+                connect_time_sum += connected_after;
+                if stats.disconnected {
+                    report.dropped += 1;
+                }
+            } else if stats.disconnected {
+                report.failed += 1;
+            }
+
+            if !stats.connected {
+                continue;
+            }
+            report.connected += 1;
+            if let Some(rtt) = stats.rtt_mean() {
+                rtt_sum += rtt;
+                rtt_samples += 1;
+            }
+            report.rtt_max = report.rtt_max.max(stats.rtt_max);
+            report.bytes_sent_per_sec += stats.bytes_sent_per_sec;
+            report.bytes_received_per_sec += stats.bytes_received_per_sec;
+            report.packet_loss_mean += stats.packet_loss;
+        }
+
+        if report.succeeded > 0 {
+            report.connect_time_mean = Some(connect_time_sum / report.succeeded as f32);
+        }
+        if rtt_samples > 0 {
+            report.rtt_mean = Some(rtt_sum / rtt_samples as f64);
+        }
+        if report.connected > 0 {
+            report.packet_loss_mean /= report.connected as f64;
+        }
+        report
+    }
+
+    /// Part des bots qui ont réussi à se connecter, entre 0 et 1.
+    pub fn success_rate(&self) -> f32 {
+        if self.bots == 0 {
+            return 0.0;
+        }
+        self.succeeded as f32 / self.bots as f32
+    }
+}
+
+impl fmt::Display for LoadReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}/{} connected ({:.1}% success, {} failed, {} dropped)",
+            self.connected,
+            self.bots,
+            self.success_rate() * 100.0,
+            self.failed,
+            self.dropped,
+        )?;
+        if let Some(connect_time) = self.connect_time_mean {
+            write!(f, ", connect {:.0} ms", connect_time * 1000.0)?;
+        }
+        if let Some(rtt) = self.rtt_mean {
+            write!(
+                f,
+                ", rtt {:.1} ms (max {:.1} ms)",
+                rtt * 1000.0,
+                self.rtt_max * 1000.0
+            )?;
+        }
+        write!(
+            f,
+            ", up {:.1} KiB/s, down {:.1} KiB/s, loss {:.1}%",
+            self.bytes_sent_per_sec / 1024.0,
+            self.bytes_received_per_sec / 1024.0,
+            self.packet_loss_mean * 100.0,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn script() -> BotScript {
+        BotScript {
+            steps: vec![
+                BotStep {
+                    direction: (2.0, 0.0),
+                    secs: 1.0,
+                },
+                BotStep {
+                    direction: (0.0, -1.0),
+                    secs: 0.5,
+                },
+                BotStep {
+                    direction: (1.0, 1.0),
+                    secs: -3.0,
+                },
+            ],
+        }
+    }
+
+    fn load(name: &str, content: &str) -> Result<BotScript, String> {
+        let path = std::env::temp_dir().join(format!("{name}_{}.ron", std::process::id()));
+        std::fs::write(&path, content).unwrap();
+        let script = BotScript::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        script
+    }
+
+    #[test]
+    fn script_loops_over_its_steps() {
+        let script = script();
+
+        assert_eq!(script.duration(), 1.5);
+        assert_eq!(script.direction_at(0.2), Vec2::X);
+        assert_eq!(script.direction_at(1.2), Vec2::NEG_Y);
+        assert_eq!(script.direction_at(1.7), Vec2::X);
+        assert_eq!(script.direction_at(-0.2), Vec2::NEG_Y);
+    }
+
+    #[test]
+    fn script_is_loaded_from_ron() {
+        let script = load(
+            "bot_script",
+            "(steps: [(direction: (1.0, 0.0), secs: 2.0), (direction: (-1.0, 0.0), secs: 2.0)])",
+        )
+        .unwrap();
+
+        assert_eq!(script.steps.len(), 2);
+        assert_eq!(script.duration(), 4.0);
+    }
+
+    #[test]
+    fn invalid_scripts_are_rejected() {
+        assert!(load("bot_script_invalid", "(steps: [").is_err());
+        assert!(load("bot_script_empty", "(steps: [])").is_err());
+        assert!(load(
+            "bot_script_zero",
+            "(steps: [(direction: (1.0, 0.0), secs: 0.0)])"
+        )
+        .is_err());
+        assert!(BotScript::load(Path::new("/nonexistent/bot_script.ron")).is_err());
+    }
+
+    fn connected(rtt: f64, down: f64, loss: f64) -> BotNetworkStats {
+        BotNetworkStats {
+            connected_after: Some(0.2),
+            connected: true,
+            rtt_sum: rtt * 2.0,
+            rtt_samples: 2,
+            rtt_max: rtt * 2.0,
+            bytes_received_per_sec: down,
+            packet_loss: loss,
+            ..BotNetworkStats::default()
+        }
+    }
+
+    #[test]
+    fn load_report_aggregates_bots() {
+        let failed = BotNetworkStats {
+            disconnected: true,
+            ..BotNetworkStats::default()
+        };
+        let dropped = BotNetworkStats {
+            connected_after: Some(0.4),
+            disconnected: true,
+            ..BotNetworkStats::default()
+        };
+        let pending = BotNetworkStats::default();
+        let stats = [
+            connected(0.01, 1000.0, 0.0),
+            connected(0.03, 3000.0, 0.1),
+            failed,
+            dropped,
+            pending,
+        ];
+
+        let report = LoadReport::new(&stats);
+
+        assert_eq!(report.bots, 5);
+        assert_eq!(report.succeeded, 3);
+        assert_eq!(report.connected, 2);
+        assert_eq!(report.failed, 1);
+        assert_eq!(report.dropped, 1);
+        assert_eq!(report.success_rate(), 0.6);
+        assert!((report.connect_time_mean.unwrap() - 0.8 / 3.0).abs() < 1e-6);
+        assert!((report.rtt_mean.unwrap() - 0.02).abs() < 1e-9);
+        assert_eq!(report.rtt_max, 0.06);
+        assert_eq!(report.bytes_received_per_sec, 4000.0);
+        assert!((report.packet_loss_mean - 0.05).abs() < 1e-9);
+    }
+
+    #[test]
+    fn empty_load_report() {
+        let report = LoadReport::new(&[]);
+
+        assert_eq!(report.success_rate(), 0.0);
+        assert_eq!(report.connect_time_mean, None);
+        assert_eq!(report.rtt_mean, None);
+        assert_eq!(
+            report.to_string(),
+            "0/0 connected (0.0% success, 0 failed, 0 dropped), up 0.0 KiB/s, down 0.0 KiB/s, loss 0.0%"
+        );
+    }
+}
@@ -1,3 +1,4 @@
+pub mod bot;
 pub mod plugin;
 pub mod resource;
 pub mod system;
@@ -9,13 +9,23 @@ use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use bevy_renet::RenetClientPlugin;
 use client::plugin::client_plugin::ClientPlugin;
 use client::plugin::game_plugin::GamePlugin;
+use client::plugin::replay_plugin::ReplayPlugin;
 use client::system::camera::spawn_camera;
+use std::path::PathBuf;
 
 fn main() {
     let mut app = App::new();
 
+    // `CLIENT_REPLAY` : lit ce fichier de replay au lieu de se connecter au serveur.
+    let replay_path = std::env::var_os("CLIENT_REPLAY")
+        .filter(|path| !path.is_empty())
+        .map(PathBuf::from);
+
     let process_id = std::process::id();
-    let window_title = format!("Client - PID: {}", process_id);
+    let window_title = match &replay_path {
+        Some(path) => format!("Replay - {}", path.display()),
+        None => format!("Client - PID: {}", process_id),
+    };
 
     app.add_plugins(
         DefaultPlugins
@@ -36,9 +46,16 @@ fn main() {
 
     app.add_plugins(EguiPlugin::default());
     app.add_plugins(WorldInspectorPlugin::new());
-    app.add_plugins(RenetClientPlugin);
-    app.add_plugins(ClientPlugin);
     app.add_plugins(GamePlugin);
+    match replay_path {
+        Some(path) => {
+            app.add_plugins(ReplayPlugin { path });
+        }
+        None => {
+            app.add_plugins(RenetClientPlugin);
+            app.add_plugins(ClientPlugin);
+        }
+    }
 
     app.add_systems(Startup, spawn_camera);
 
@@ -1,2 +1,4 @@
+pub mod bot_plugin;
 pub mod client_plugin;
 pub mod game_plugin;
+pub mod replay_plugin;
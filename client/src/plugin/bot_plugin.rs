@@ -0,0 +1,29 @@
+use crate::bot::BotBehavior;
+use crate::resource::BotState;
+use crate::system::bot::{drive_bot, leave_server, sample_bot_stats};
+use crate::system::input::send_player_input;
+use bevy::app::{FixedUpdate, Update};
+use bevy::prelude::{App, IntoScheduleConfigs, Plugin};
+use bevy_renet::client_connected;
+
+/// Client simulé pour les tests de charge : remplace le clavier par un
+/// `BotBehavior` et relève les statistiques réseau dans `BotState`.
+///
+/// S'ajoute après `ClientPlugin`, dans une application sans fenêtre ni rendu.
+pub struct BotPlugin {
+    /// Comportement du bot.
+    pub behavior: BotBehavior,
+    /// Décalage du bot dans son comportement, en secondes.
+    pub phase: f32,
+}
+
+impl Plugin for BotPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(BotState::new(self.behavior.clone(), self.phase));
+        app.add_systems(
+            FixedUpdate,
+            drive_bot.before(send_player_input).run_if(client_connected),
+        );
+        app.add_systems(Update, (sample_bot_stats, leave_server));
+    }
+}
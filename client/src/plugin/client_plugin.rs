@@ -1,12 +1,37 @@
-use bevy::prelude::{App, MessageReader, Plugin, Update};
+use bevy::input::ButtonInput;
+use bevy::prelude::{
+    error, resource_exists, App, Commands, FixedUpdate, IntoScheduleConfigs, KeyCode,
+    MessageReader, MouseButton, Plugin, ResMut, Update,
+};
 use bevy_renet::netcode::{
-    ClientAuthentication, NetcodeClientPlugin, NetcodeClientTransport, NetcodeTransportError,
+    ClientAuthentication, NetcodeClientPlugin, NetcodeClientTransport, NetcodeError,
+    NetcodeTransportError,
 };
 use bevy_renet::renet::RenetClient;
+use bevy_renet::{client_connected, client_disconnected};
 
-use crate::resource::{ClientLobby, CurrentClientId, PlayerMapping};
+use crate::resource::{
+    CurrentClientId, InputDirection, InputFire, InputSequence, LastDisconnect, PlayerProfile,
+};
+use crate::system::camera::free_camera;
+use crate::system::client_event::{on_server_event, receive_server_messages};
+use crate::system::connection_screen::disconnected_screen;
+use crate::system::input::{keyboard_input_direction, mouse_input_fire, send_player_input};
+use crate::system::physics::extrapolate_bodies;
+use crate::system::projectile::{
+    blend_reconciled_projectiles, reconcile_predicted_projectiles, update_predicted_projectiles,
+};
+use crate::system::spectator::{follow_player, spectating_free_camera, spectator_window};
+use bevy_egui::EguiPrimaryContextPass;
 use game_core::network::{connection_config, get_current_time, get_socket, PROTOCOL_ID};
 
+/// Connexion au serveur : transport, réception des messages, envoi des entrées,
+/// mode spectateur et écran de déconnexion. L'état du jeu est géré par `GamePlugin`.
+///
+/// L'identifiant et le profil du client sont lus depuis l'environnement, sauf si
+/// `CurrentClientId` et `PlayerProfile` ont été insérés avant le plugin (bots).
+/// La direction envoyée au serveur est lue au clavier, et les tirs à la souris, si
+/// l'`InputPlugin` est présent ; les projectiles tirés sont prédits localement.
 pub struct ClientPlugin;
 
 impl Plugin for ClientPlugin {
@@ -15,12 +40,58 @@ impl Plugin for ClientPlugin {
 
         build_client_transport(app);
 
-        app.insert_resource(PlayerMapping::default());
-        app.insert_resource(ClientLobby::default());
+        app.insert_resource(InputSequence::default());
+        app.insert_resource(InputDirection::default());
+        app.insert_resource(InputFire::default());
+        app.add_systems(Update, receive_server_messages.before(on_server_event));
+        app.add_systems(
+            FixedUpdate,
+            (
+                keyboard_input_direction.run_if(resource_exists::<ButtonInput<KeyCode>>),
+                mouse_input_fire.run_if(resource_exists::<ButtonInput<MouseButton>>),
+                send_player_input,
+            )
+                .chain()
+                .run_if(client_connected),
+        );
+        app.add_systems(
+            Update,
+            (
+                free_camera.run_if(spectating_free_camera),
+                follow_player.after(on_server_event),
+            ),
+        );
+        app.add_systems(
+            Update,
+            (
+                update_predicted_projectiles,
+                reconcile_predicted_projectiles.after(on_server_event),
+                blend_reconciled_projectiles
+                    .after(update_predicted_projectiles)
+                    .after(reconcile_predicted_projectiles)
+                    .after(extrapolate_bodies),
+            )
+                .run_if(client_connected),
+        );
+        app.add_systems(
+            EguiPrimaryContextPass,
+            (
+                spectator_window.run_if(client_connected),
+                disconnected_screen.run_if(client_disconnected),
+            ),
+        );
     }
 }
 
-fn build_client_transport(app: &mut App) {
+/// Crée une connexion renet et son transport netcode vers le serveur.
+///
+/// - `client_id` : identifiant du client, réutilisé lors d'une reconnexion pour que
+///   le serveur rende au joueur son entité.
+/// - `profile` : nom et couleur du joueur, encodés dans les `user_data` de la connexion.
+pub fn new_client_transport(
+    client_id: u64,
+    profile: &PlayerProfile,
+) -> (RenetClient, NetcodeClientTransport) {
     let client = RenetClient::new(connection_config());
 
     let server_addr = "127.0.0.1:5000"
@@ -32,26 +103,58 @@ fn build_client_transport(app: &mut App) {
     let socket = get_socket(socket_addr);
 
     let current_time = get_current_time();
-    let client_id = current_time.as_millis() as u64;
     let authentication = ClientAuthentication::Unsecure {
         client_id,
         protocol_id: PROTOCOL_ID,
         server_addr,
-        user_data: None,
+        user_data: Some(profile.to_user_data(client_id).to_user_data()),
     };
 
     let transport = NetcodeClientTransport::new(current_time, authentication, socket).unwrap();
 
+    (client, transport)
+}
+
+fn build_client_transport(app: &mut App) {
+    let client_id = match app.world().get_resource::<CurrentClientId>() {
+        Some(current_client_id) => current_client_id.0,
+        None => std::env::var("CLIENT_ID")
+            .ok()
+            .and_then(|client_id| client_id.trim().parse().ok())
+            .unwrap_or_else(|| get_current_time().as_millis() as u64),
+    };
+    let profile = app
+        .world_mut()
+        .remove_resource::<PlayerProfile>()
+        .unwrap_or_else(PlayerProfile::from_env);
+    let (client, transport) = new_client_transport(client_id, &profile);
+
     app.insert_resource(client);
+    app.insert_resource(profile);
     app.insert_resource(transport);
     app.insert_resource(CurrentClientId(client_id));
 
-    #[allow(clippy::never_loop)]
-    fn panic_on_error_system(mut renet_error: MessageReader<NetcodeTransportError>) {
-        for e in renet_error.read() {
-            panic!("{}", e);
+    app.add_systems(Update, on_transport_error);
+}
+
+/// Enregistre la raison d'une déconnexion du transport et retire ce dernier,
+/// qui ne peut plus être utilisé. Les autres erreurs sont simplement journalisées.
+fn on_transport_error(
+    mut transport_errors: MessageReader<NetcodeTransportError>,
+    mut last_disconnect: ResMut<LastDisconnect>,
+    mut commands: Commands,
+) {
+    for e in transport_errors.read() {
+        match e {
+            NetcodeTransportError::Netcode(NetcodeError::Disconnected(reason)) => {
+                last_disconnect.record_netcode(reason);
+                commands.remove_resource::<NetcodeClientTransport>();
+            }
+            NetcodeTransportError::Renet(reason) => {
+                last_disconnect.record_transport(reason.to_string());
+                commands.remove_resource::<NetcodeClientTransport>();
+            }
+            e => error!("Transport error: {e}"),
         }
     }
-
-    app.add_systems(Update, panic_on_error_system);
 }
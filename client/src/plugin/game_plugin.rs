@@ -1,18 +1,61 @@
+use crate::resource::{
+    ChatLog, ClientLobby, LastDisconnect, LoadedMap, QueueStatus, SpectatorState,
+};
+use crate::system::chat::chat_window;
 use crate::system::client_event::on_server_event;
+use crate::system::connection_screen::queue_screen;
+use crate::system::physics::{extrapolate_bodies, spawn_body_visuals};
+use crate::system::player_visual::{
+    highlight_controlled_player, spawn_health_bars, spawn_nameplates, update_health_bars,
+};
+use crate::system::projectile::spawn_projectile_visuals;
 use bevy::app::Update;
 use bevy::prelude::{App, IntoScheduleConfigs, Plugin, SystemSet};
+use bevy_egui::EguiPrimaryContextPass;
 use bevy_renet::client_connected;
+use game_core::entity_map::NetworkEntityMap;
+use game_core::event::client_event::ServerMessageReceived;
 use game_core::event::game_event::GameEvent;
+use game_core::replication::ReplicationPlugin;
 #[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Connected;
+
+/// État du jeu côté client, alimenté par les `ServerMessageReceived` du réseau
+/// (`ClientPlugin`) ou d'un replay (`ReplayPlugin`).
 pub struct GamePlugin;
 
 impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
+        app.add_plugins(ReplicationPlugin);
+
+        app.insert_resource(NetworkEntityMap::default());
+        app.insert_resource(ClientLobby::default());
+        app.insert_resource(LastDisconnect::default());
+        app.insert_resource(ChatLog::default());
+        app.insert_resource(QueueStatus::default());
+        app.insert_resource(SpectatorState::default());
+        app.insert_resource(LoadedMap::default());
+
         app.add_message::<GameEvent>();
+        app.add_message::<ServerMessageReceived>();
         app.add_systems(Update, on_server_event);
-
-        app.add_systems(Update, on_server_event.in_set(Connected));
         app.configure_sets(Update, Connected.run_if(client_connected));
+
+        app.add_systems(
+            Update,
+            (
+                spawn_nameplates,
+                highlight_controlled_player,
+                spawn_health_bars,
+                update_health_bars,
+            ),
+        );
+        app.add_systems(Update, (spawn_body_visuals, spawn_projectile_visuals));
+        app.add_systems(Update, extrapolate_bodies.in_set(Connected));
+
+        app.add_systems(
+            EguiPrimaryContextPass,
+            (chat_window, queue_screen).run_if(client_connected),
+        );
     }
 }
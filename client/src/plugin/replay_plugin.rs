@@ -0,0 +1,60 @@
+use crate::resource::{ReplayLoadError, ReplayPlayback};
+use crate::system::camera::free_camera;
+use crate::system::chat::chat_window;
+use crate::system::client_event::on_server_event;
+use crate::system::replay::{play_replay, replay_controls, replay_error_screen};
+use bevy::app::Update;
+use bevy::log::{error, info, warn};
+use bevy::prelude::{App, IntoScheduleConfigs, Plugin};
+use bevy_egui::EguiPrimaryContextPass;
+use game_core::replay::{protocol_fingerprint, Replay};
+use game_core::replication::ReplicationRegistry;
+use std::path::PathBuf;
+
+/// Lecture d'un replay enregistré par le serveur, sans connexion réseau.
+///
+/// Les messages enregistrés alimentent `on_server_event` : ce plugin s'ajoute
+/// après `GamePlugin`, à la place de `ClientPlugin`. Le chat de la partie est
+/// affiché, sans zone de saisie.
+///
+/// Si le fichier n'est pas un replay lisible, ou s'il a été enregistré avec un autre
+/// protocole (voir `protocol_fingerprint`), l'erreur est affichée à la place de la
+/// lecture (voir `ReplayLoadError`).
+pub struct ReplayPlugin {
+    /// Chemin du fichier de replay.
+    pub path: PathBuf,
+}
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        let fingerprint = protocol_fingerprint(app.world().resource::<ReplicationRegistry>());
+        let replay = Replay::read(&self.path).and_then(|replay| {
+            replay
+                .header
+                .check(&fingerprint)
+                .map(|_| replay)
+                .map_err(|err| format!("Cannot play {}: {err}", self.path.display()))
+        });
+        let replay = match replay {
+            Ok(replay) => replay,
+            Err(err) => {
+                error!("{err}");
+                app.insert_resource(ReplayLoadError(err));
+                app.add_systems(EguiPrimaryContextPass, replay_error_screen);
+                return;
+            }
+        };
+        if replay.truncated {
+            warn!("The end of {} is truncated", self.path.display());
+        }
+        info!(
+            "Playing replay {} ({} records)",
+            self.path.display(),
+            replay.records.len()
+        );
+
+        app.insert_resource(ReplayPlayback::new(replay));
+        app.add_systems(Update, (play_replay.before(on_server_event), free_camera));
+        app.add_systems(EguiPrimaryContextPass, (replay_controls, chat_window));
+    }
+}
@@ -1,7 +1,14 @@
-use bevy::prelude::{Entity, Resource};
+use crate::bot::{BotBehavior, BotLeave, BotNetworkStats};
+use bevy::prelude::{Entity, Resource, Vec2};
+use bevy_renet::netcode::NetcodeDisconnectReason;
 use bevy_renet::renet::ClientId;
-use game_core::client::PlayerEntities;
-use std::collections::HashMap;
+use game_core::client::{hash_password, ConnectUserData, PlayerEntities, SessionToken};
+use game_core::map::GameMap;
+use game_core::network::deserialize_server_message;
+use game_core::projectile::FireCooldown;
+use game_core::replay::{Replay, ReplayRecipient, ReplayRecord};
+use game_core::server::{DisconnectReason, ServerMessages};
+use std::collections::{HashMap, VecDeque};
 
 /// Représente l'état du lobby côté client.
 ///
@@ -37,6 +44,101 @@ impl ClientLobby {
     pub fn get_player_entities(&self, client_id: &ClientId) -> Option<&PlayerEntities> {
         self.players.get(client_id)
     }
+
+    /// Itère sur les joueurs du lobby client.
+    pub fn iter(&self) -> impl Iterator<Item = (&ClientId, &PlayerEntities)> {
+        self.players.iter()
+    }
+
+    /// Vide le lobby client.
+    ///
+    /// - Retourne les `PlayerEntities` de tous les joueurs retirés.
+    pub fn remove_all_players(&mut self) -> Vec<PlayerEntities> {
+        self.players.drain().map(|(_, entities)| entities).collect()
+    }
+}
+
+/// Profil du joueur local, transmis au serveur à la connexion.
+///
+/// - `name` : nom affiché souhaité (lu depuis la variable d'environnement `PLAYER_NAME`).
+/// - `color` : couleur cosmétique souhaitée (lue depuis `PLAYER_COLOR`, au format `#rrggbb`).
+/// - `password` : mot de passe du serveur (lu depuis `PLAYER_PASSWORD` ou saisi
+///   dans l'écran de connexion).
+/// - `admin_password` : mot de passe d'administration du serveur (lu depuis
+///   `PLAYER_ADMIN_PASSWORD`), pour envoyer des commandes via le chat.
+/// - `spectator` : rejoint le serveur en spectateur (`PLAYER_SPECTATOR=1`).
+/// - `session_token` : jeton de session reçu du serveur (voir `ServerMessages::Session`),
+///   pour récupérer son joueur en se reconnectant après une coupure.
+#[derive(Debug, Clone, Default, Resource)]
+pub struct PlayerProfile {
+    /// Nom souhaité par le joueur.
+    pub name: String,
+    /// Couleur cosmétique `[r, g, b]` souhaitée par le joueur.
+    pub color: Option<[u8; 3]>,
+    /// Mot de passe du serveur, s'il en exige un.
+    pub password: Option<String>,
+    /// Mot de passe d'administration du serveur.
+    pub admin_password: Option<String>,
+    /// Rejoint le serveur en spectateur.
+    pub spectator: bool,
+    /// Jeton de la dernière session ouverte par le serveur.
+    pub session_token: Option<SessionToken>,
+}
+
+impl PlayerProfile {
+    /// Construit le profil à partir des variables d'environnement `PLAYER_NAME`,
+    /// `PLAYER_COLOR`, `PLAYER_PASSWORD`, `PLAYER_ADMIN_PASSWORD` et
+    /// `PLAYER_SPECTATOR` (`1` ou `true`).
+    /// Une couleur mal formée est ignorée.
+    pub fn from_env() -> Self {
+        Self {
+            name: std::env::var("PLAYER_NAME").unwrap_or_default(),
+            color: std::env::var("PLAYER_COLOR")
+                .ok()
+                .and_then(|color| parse_hex_color(&color)),
+            password: std::env::var("PLAYER_PASSWORD")
+                .ok()
+                .filter(|password| !password.is_empty()),
+            admin_password: std::env::var("PLAYER_ADMIN_PASSWORD")
+                .ok()
+                .filter(|password| !password.is_empty()),
+            spectator: std::env::var("PLAYER_SPECTATOR")
+                .is_ok_and(|spectator| matches!(spectator.trim(), "1" | "true")),
+            session_token: None,
+        }
+    }
+
+    /// Construit les données de connexion envoyées au serveur.
+    ///
+    /// Les mots de passe ne sont jamais envoyés en clair : seule leur empreinte salée
+    /// avec l'identifiant du client l'est (voir `hash_password`).
+    pub fn to_user_data(&self, client_id: ClientId) -> ConnectUserData {
+        ConnectUserData {
+            name: self.name.clone(),
+            color: self.color,
+            password_hash: self
+                .password
+                .as_deref()
+                .map(|password| hash_password(password, client_id)),
+            admin_password_hash: self
+                .admin_password
+                .as_deref()
+                .map(|password| hash_password(password, client_id)),
+            session_token: self.session_token,
+            spectator: self.spectator,
+        }
+    }
+}
+
+/// Convertit une couleur `#rrggbb` (le `#` est facultatif) en `[r, g, b]`.
+fn parse_hex_color(hex: &str) -> Option<[u8; 3]> {
+    let hex = hex.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
 }
 
 /// Identifiant unique du client courant généré localement.
@@ -45,36 +147,424 @@ impl ClientLobby {
 #[derive(Debug, Resource)]
 pub struct CurrentClientId(pub u64);
 
-/// Mappe les entités côté client aux entités correspondantes côté serveur.
-/// Utile pour synchroniser les états entre le client et le serveur.
+/// Raison de la dernière déconnexion du serveur, affichée dans l'écran de déconnexion.
 ///
-/// Contient une table de hachage où la clé est l'entité côté client
-/// et la valeur est l'entité correspondante côté serveur.
+/// - `reason` : raison de jeu envoyée par le serveur, ou déduite du transport.
+/// - `transport` : description de la raison de transport lorsqu'aucune raison de jeu
+///   n'est connue.
+#[derive(Debug, Default, Resource)]
+pub struct LastDisconnect {
+    /// Raison de jeu de la déconnexion.
+    pub reason: Option<DisconnectReason>,
+    /// Raison brute fournie par la couche transport.
+    pub transport: Option<String>,
+}
+
+impl LastDisconnect {
+    /// Indique si une déconnexion a été enregistrée.
+    pub fn is_set(&self) -> bool {
+        self.reason.is_some() || self.transport.is_some()
+    }
+
+    /// Enregistre la raison de jeu envoyée par le serveur.
+    pub fn record(&mut self, reason: DisconnectReason) {
+        self.reason = Some(reason);
+    }
+
+    /// Enregistre une raison de transport, sans écraser une raison de jeu déjà reçue.
+    pub fn record_transport(&mut self, transport: String) {
+        self.transport.get_or_insert(transport);
+    }
+
+    /// Enregistre une raison netcode et en déduit la raison de jeu si le serveur
+    /// n'en a envoyé aucune (délai dépassé, connexion refusée car serveur plein).
+    pub fn record_netcode(&mut self, reason: &NetcodeDisconnectReason) {
+        if self.reason.is_none() {
+            self.reason = match reason {
+                NetcodeDisconnectReason::ConnectionTimedOut
+                | NetcodeDisconnectReason::ConnectionResponseTimedOut
+                | NetcodeDisconnectReason::ConnectionRequestTimedOut => {
+                    Some(DisconnectReason::Timeout)
+                }
+                NetcodeDisconnectReason::ConnectionDenied => Some(DisconnectReason::ServerFull),
+                _ => None,
+            };
+        }
+        self.record_transport(format!("{reason:?}"));
+    }
+
+    /// Oublie la dernière déconnexion, par exemple avant une reconnexion.
+    pub fn clear(&mut self) {
+        self.reason = None;
+        self.transport = None;
+    }
+}
+
+/// Nombre maximal de messages conservés dans l'historique du chat.
+pub const CHAT_SCROLLBACK: usize = 100;
+
+/// Message de chat reçu du serveur.
+#[derive(Debug, Clone)]
+pub struct ChatEntry {
+    /// Nom de l'auteur.
+    pub name: String,
+    /// Texte du message.
+    pub text: String,
+    /// Message réservé à l'équipe.
+    pub team_only: bool,
+}
+
+/// État de la fenêtre de chat.
 ///
-#[derive(Default, Resource)]
-pub struct PlayerMapping(pub(crate) HashMap<Entity, Entity>);
+/// - `entries` : derniers messages reçus, au plus `CHAT_SCROLLBACK`.
+/// - `input` : texte en cours de saisie.
+/// - `team_only` : envoie les messages saisis à l'équipe seulement.
+/// - `typing` : la zone de saisie a le focus ; le clavier ne déplace alors pas le joueur.
+#[derive(Debug, Default, Resource)]
+pub struct ChatLog {
+    /// Historique des messages reçus.
+    pub entries: VecDeque<ChatEntry>,
+    /// Texte en cours de saisie.
+    pub input: String,
+    /// Canal d'envoi sélectionné.
+    pub team_only: bool,
+    /// Indique si le joueur est en train de saisir un message.
+    pub typing: bool,
+}
 
-impl PlayerMapping {
-    /// Ajoute une correspondance entre une entité client et une entité serveur.
-    ///
-    /// - `client_entity` : entité côté client.
-    /// - `server_entity` : entité correspondante côté serveur.
-    pub fn add(&mut self, client_entity: Entity, server_entity: Entity) {
-        self.0.insert(client_entity, server_entity);
+impl ChatLog {
+    /// Ajoute un message à l'historique, en oubliant les plus anciens au-delà
+    /// de `CHAT_SCROLLBACK`.
+    pub fn push(&mut self, entry: ChatEntry) {
+        if self.entries.len() >= CHAT_SCROLLBACK {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
     }
+}
 
-    /// Récupère l'entité serveur associée à une entité client.
-    ///
-    /// - `client_entity` : entité côté client.
-    /// - Retourne une option contenant l'entité serveur si trouvée.
-    pub fn get(&self, client_entity: &Entity) -> Option<&Entity> {
-        self.0.get(client_entity)
+/// Position du client dans la file d'attente du serveur plein.
+///
+/// `None` lorsque le client n'attend pas (il joue, ou n'a rien reçu du serveur).
+#[derive(Debug, Default, Resource)]
+pub struct QueueStatus {
+    /// `(position, total)` : position du client à partir de 1 et taille de la file.
+    pub position: Option<(u32, u32)>,
+}
+
+/// Numéro de séquence de la prochaine `PlayerInput` envoyée au serveur.
+#[derive(Debug, Default, Resource)]
+pub struct InputSequence(pub u32);
+
+/// Direction de déplacement envoyée au serveur au prochain tick fixe, choisie au
+/// clavier ou par le comportement d'un bot.
+#[derive(Debug, Default, Resource)]
+pub struct InputDirection(pub Vec2);
+
+/// Tir envoyé au serveur au prochain tick fixe, visé à la souris.
+///
+/// - `direction` : direction unitaire du tir ; `None` lorsque le joueur ne tire pas.
+/// - `cooldown` : cadence de tir, comptée sur les séquences d'entrées comme le
+///   serveur, pour ne jamais envoyer un tir qu'il refuserait.
+#[derive(Debug, Default, Resource)]
+pub struct InputFire {
+    /// Direction du tir.
+    pub direction: Option<Vec2>,
+    /// Cadence de tir du joueur contrôlé.
+    pub cooldown: FireCooldown,
+}
+
+/// Mode spectateur du client local.
+///
+/// - `spectating` : le client regarde la partie sans joueur (confirmé par le serveur).
+/// - `follow` : joueur suivi par la caméra ; `None` pour la caméra libre.
+#[derive(Debug, Default, Resource)]
+pub struct SpectatorState {
+    /// Indique si le client est spectateur.
+    pub spectating: bool,
+    /// Joueur suivi par la caméra.
+    pub follow: Option<ClientId>,
+}
+
+/// Carte reçue du serveur (voir `ServerMessages::Map`).
+///
+/// - `map` : arène et murs de la partie ; `None` tant que rien n'a été reçu.
+/// - `walls` : entités locales des murs, munies de leur `StaticCollider` pour que
+///   la prédiction du joueur contrôlé puisse résoudre ses collisions comme le serveur.
+#[derive(Debug, Default, Resource)]
+pub struct LoadedMap {
+    /// Carte de la partie.
+    pub map: Option<GameMap>,
+    /// Entités des murs de la carte.
+    pub walls: Vec<Entity>,
+}
+
+/// Vitesses de lecture proposées pour un replay.
+pub const REPLAY_SPEEDS: [f32; 6] = [0.25, 0.5, 1.0, 2.0, 4.0, 8.0];
+
+/// Lecture d'un replay enregistré par le serveur.
+///
+/// - `cursor` : index du prochain enregistrement à lire.
+/// - `tick` : position de lecture, en ticks du serveur (fractionnaire).
+/// - `playing` / `speed` : lecture en cours et multiplicateur de vitesse.
+/// - `seek` : tick à atteindre, traité par `play_replay` à la frame suivante.
+#[derive(Debug, Resource)]
+pub struct ReplayPlayback {
+    /// Replay lu.
+    pub replay: Replay,
+    /// Index du prochain enregistrement.
+    pub cursor: usize,
+    /// Position de lecture.
+    pub tick: f64,
+    /// Indique si la lecture progresse.
+    pub playing: bool,
+    /// Multiplicateur de vitesse de lecture.
+    pub speed: f32,
+    /// Déplacement demandé dans le replay.
+    pub seek: Option<u64>,
+}
+
+impl ReplayPlayback {
+    /// Prépare la lecture d'un replay depuis son début.
+    pub fn new(replay: Replay) -> Self {
+        let mut playback = Self {
+            replay,
+            cursor: 0,
+            tick: 0.0,
+            playing: true,
+            speed: 1.0,
+            seek: None,
+        };
+        playback.tick = playback.first_tick() as f64;
+        playback
     }
 
-    /// Supprime la correspondance pour une entité client donnée.
+    /// Tick du premier enregistrement.
+    pub fn first_tick(&self) -> u64 {
+        self.replay.records.first().map_or(0, ReplayRecord::tick)
+    }
+
+    /// Tick du dernier enregistrement.
+    pub fn last_tick(&self) -> u64 {
+        self.replay.records.last().map_or(0, ReplayRecord::tick)
+    }
+
+    /// Index du dernier état complet enregistré au plus tard au tick `tick`.
+    pub fn keyframe_before(&self, tick: u64) -> Option<usize> {
+        self.replay.records.iter().rposition(|record| {
+            matches!(record, ReplayRecord::Keyframe { .. }) && record.tick() <= tick
+        })
+    }
+
+    /// Convertit un nombre de ticks en durée, selon la fréquence du serveur enregistré.
+    pub fn ticks_to_secs(&self, ticks: f64) -> f64 {
+        ticks / self.replay.header.tick_rate
+    }
+
+    /// Fait progresser la lecture de `delta_secs` secondes, à la vitesse choisie.
+    /// La lecture s'arrête sur le dernier enregistrement.
+    pub fn advance(&mut self, delta_secs: f64) {
+        if !self.playing {
+            return;
+        }
+        let last_tick = self.last_tick() as f64;
+        let ticks = delta_secs * self.replay.header.tick_rate * self.speed as f64;
+        self.tick = (self.tick + ticks).min(last_tick);
+        if self.tick >= last_tick {
+            self.playing = false;
+        }
+    }
+
+    /// Se place au tick `target`, borné au replay.
     ///
-    /// - `client_entity` : entité côté client à retirer.
-    pub fn remove(&mut self, client_entity: &Entity) {
-        self.0.remove(client_entity);
+    /// # Retourne
+    /// * `Vec<ServerMessages>` - Messages du dernier état complet précédant `target`,
+    ///   qui reconstruisent le monde depuis un monde vide. Les messages suivants
+    ///   jusqu'à `target` sont retournés par `due_messages`.
+    pub fn seek_to(&mut self, target: u64) -> Vec<ServerMessages> {
+        let target = target.clamp(self.first_tick(), self.last_tick());
+        self.tick = target as f64;
+        match self.keyframe_before(target) {
+            Some(index) => {
+                self.cursor = index + 1;
+                match &self.replay.records[index] {
+                    ReplayRecord::Keyframe { messages, .. } => messages
+                        .iter()
+                        .map(|payload| deserialize_server_message(payload).0)
+                        .collect(),
+                    ReplayRecord::Messages { .. } => Vec::new(),
+                }
+            }
+            None => {
+                self.cursor = 0;
+                Vec::new()
+            }
+        }
+    }
+
+    /// Retourne les messages adressés à tous les joueurs enregistrés jusqu'à la
+    /// position de lecture, et avance le curseur après eux.
+    pub fn due_messages(&mut self) -> Vec<ServerMessages> {
+        let until = self.tick.floor() as u64;
+        let mut due = Vec::new();
+        while let Some(record) = self.replay.records.get(self.cursor)
+            && record.tick() <= until
+        {
+            if let ReplayRecord::Messages { messages, .. } = record {
+                due.extend(
+                    messages
+                        .iter()
+                        .filter(|message| message.recipient == ReplayRecipient::All)
+                        .map(|message| message.decode()),
+                );
+            }
+            self.cursor += 1;
+        }
+        due
+    }
+}
+
+/// Erreur de chargement du replay demandé, affichée à la place de la lecture
+/// (voir `ReplayPlugin`).
+#[derive(Debug, Resource)]
+pub struct ReplayLoadError(pub String);
+
+/// État d'un bot de test de charge (voir `BotPlugin`).
+///
+/// - `behavior` : comportement choisissant la direction envoyée au serveur.
+/// - `elapsed` : temps écoulé depuis la connexion, en secondes, décalé de la phase
+///   du bot.
+/// - `next_change` : instant du prochain changement de direction aléatoire.
+/// - `stats` : statistiques réseau relevées à chaque frame.
+/// - `leave` : avancement du départ du bot ; il n'envoie plus de direction dès
+///   que son départ est demandé.
+#[derive(Debug, Resource)]
+pub struct BotState {
+    pub behavior: BotBehavior,
+    pub elapsed: f32,
+    pub next_change: f32,
+    pub stats: BotNetworkStats,
+    pub leave: BotLeave,
+}
+
+impl BotState {
+    /// Crée l'état d'un bot démarrant à la phase `phase` (en secondes) de son comportement.
+    pub fn new(behavior: BotBehavior, phase: f32) -> Self {
+        Self {
+            behavior,
+            elapsed: phase,
+            next_change: 0.0,
+            stats: BotNetworkStats::default(),
+            leave: BotLeave::Playing,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game_core::network::serialize_server_message;
+    use game_core::replay::{ReplayHeader, ReplayMessage};
+
+    fn error(text: &str) -> ServerMessages {
+        ServerMessages::Error {
+            message: text.to_string(),
+        }
+    }
+
+    fn messages(tick: u64, recipient: ReplayRecipient, text: &str) -> ReplayRecord {
+        ReplayRecord::Messages {
+            tick,
+            messages: vec![ReplayMessage {
+                recipient,
+                payload: serialize_server_message(&error(text)),
+            }],
+        }
+    }
+
+    fn keyframe(tick: u64, text: &str) -> ReplayRecord {
+        ReplayRecord::Keyframe {
+            tick,
+            messages: vec![serialize_server_message(&error(text))],
+        }
+    }
+
+    /// Replay de 10 ticks par seconde, avec un état complet aux ticks 10 et 20.
+    fn playback() -> ReplayPlayback {
+        ReplayPlayback::new(Replay {
+            header: ReplayHeader::new([0; 32], 10.0),
+            records: vec![
+                keyframe(10, "keyframe 10"),
+                messages(12, ReplayRecipient::All, "tick 12"),
+                messages(15, ReplayRecipient::Client(1), "private"),
+                keyframe(20, "keyframe 20"),
+                messages(25, ReplayRecipient::All, "tick 25"),
+            ],
+            truncated: false,
+        })
+    }
+
+    fn texts(messages: Vec<ServerMessages>) -> Vec<String> {
+        messages
+            .into_iter()
+            .map(|message| match message {
+                ServerMessages::Error { message } => message,
+                other => panic!("unexpected message {other:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn playback_steps_through_messages_sent_to_all() {
+        let mut playback = playback();
+        assert_eq!(playback.tick, 10.0);
+        assert!(texts(playback.due_messages()).is_empty());
+
+        playback.advance(0.25);
+        assert_eq!(playback.tick, 12.5);
+        assert_eq!(texts(playback.due_messages()), ["tick 12"]);
+
+        playback.speed = 2.0;
+        playback.advance(1.0);
+        assert_eq!(playback.tick, 25.0);
+        assert!(!playback.playing);
+        assert_eq!(texts(playback.due_messages()), ["tick 25"]);
+        assert!(playback.due_messages().is_empty());
+    }
+
+    #[test]
+    fn paused_playback_does_not_advance() {
+        let mut playback = playback();
+        playback.playing = false;
+
+        playback.advance(1.0);
+
+        assert_eq!(playback.tick, 10.0);
+    }
+
+    #[test]
+    fn seeking_starts_from_the_previous_keyframe() {
+        let mut playback = playback();
+        playback.advance(2.0);
+        playback.due_messages();
+
+        assert_eq!(texts(playback.seek_to(14)), ["keyframe 10"]);
+        assert_eq!(playback.tick, 14.0);
+        assert_eq!(texts(playback.due_messages()), ["tick 12"]);
+
+        assert_eq!(texts(playback.seek_to(22)), ["keyframe 20"]);
+        assert!(playback.due_messages().is_empty());
+    }
+
+    #[test]
+    fn seeking_is_clamped_to_the_replay() {
+        let mut playback = playback();
+
+        assert_eq!(texts(playback.seek_to(0)), ["keyframe 10"]);
+        assert_eq!(playback.tick, 10.0);
+
+        assert_eq!(texts(playback.seek_to(100)), ["keyframe 20"]);
+        assert_eq!(playback.tick, 25.0);
+        assert_eq!(texts(playback.due_messages()), ["tick 25"]);
     }
 }
@@ -1,2 +1,11 @@
+pub mod bot;
 pub mod camera;
+pub mod chat;
 pub mod client_event;
+pub mod connection_screen;
+pub mod input;
+pub mod physics;
+pub mod player_visual;
+pub mod projectile;
+pub mod replay;
+pub mod spectator;
@@ -0,0 +1,94 @@
+use crate::bot::{random_direction, BotBehavior, BotLeave};
+use crate::resource::{BotState, InputDirection, LastDisconnect};
+use bevy::prelude::{Res, ResMut, Time, Vec2};
+use bevy_renet::renet::RenetClient;
+use game_core::client::{ClientChannel, ClientMessages};
+use game_core::network::serialize_client_message;
+
+/// Choisit la direction envoyée par le bot au prochain tick fixe, selon son
+/// comportement. Un bot qui quitte le serveur s'immobilise.
+pub fn drive_bot(
+    time: Res<Time>,
+    mut bot: ResMut<BotState>,
+    mut direction: ResMut<InputDirection>,
+) {
+    if bot.leave != BotLeave::Playing {
+        direction.0 = Vec2::ZERO;
+        return;
+    }
+
+    bot.elapsed += time.delta_secs();
+    match &bot.behavior {
+        BotBehavior::Random { change_every } => {
+            if bot.elapsed >= bot.next_change {
+                bot.next_change = bot.elapsed + change_every;
+                direction.0 = random_direction();
+            }
+        }
+        BotBehavior::Script(script) => {
+            direction.0 = script.direction_at(bot.elapsed);
+        }
+    }
+}
+
+/// Relève les statistiques réseau du bot (voir `BotNetworkStats`).
+pub fn sample_bot_stats(
+    time: Res<Time>,
+    client: Res<RenetClient>,
+    last_disconnect: Res<LastDisconnect>,
+    mut bot: ResMut<BotState>,
+) {
+    let playing = bot.leave == BotLeave::Playing;
+    let stats = &mut bot.stats;
+    stats.connected = client.is_connected();
+    if !stats.connected {
+        // Une déconnexion volontaire n'est pas comptée comme une perte de connexion.
+        stats.disconnected |= playing && last_disconnect.is_set();
+        return;
+    }
+
+    if stats.connected_after.is_none() {
+        stats.connected_after = Some(time.elapsed_secs());
+    }
+
+    let network_info = client.network_info();
+    if network_info.rtt > 0.0 {
+        stats.rtt_sum += network_info.rtt;
+        stats.rtt_samples += 1;
+        stats.rtt_max = stats.rtt_max.max(network_info.rtt);
+    }
+    stats.bytes_sent_per_sec = network_info.bytes_sent_per_second;
+    stats.bytes_received_per_sec = network_info.bytes_received_per_second;
+    stats.packet_loss = network_info.packet_loss;
+}
+
+/// Annonce le départ du bot au serveur, puis attend l'acquittement du message
+/// (voir `BotLeave`).
+pub fn leave_server(mut client: ResMut<RenetClient>, mut bot: ResMut<BotState>) {
+    bot.leave = match bot.leave {
+        BotLeave::Requested if client.is_connected() => {
+            client.send_message(
+                ClientChannel::Command,
+                serialize_client_message(&ClientMessages::Leave),
+            );
+            BotLeave::Sent
+        }
+        BotLeave::Requested => BotLeave::Done,
+        BotLeave::Sent if !client.is_connected() || is_client_messages_flushed(&client) => {
+            BotLeave::Done
+        }
+        leave => leave,
+    };
+}
+
+/// Indique si tous les messages envoyés sur le canal `ClientChannel::Command` ont
+/// été acquittés par le serveur.
+pub fn is_client_messages_flushed(client: &RenetClient) -> bool {
+    let capacity = ClientChannel::channel_config()
+        .into_iter()
+        .find(|config| config.channel_id == u8::from(ClientChannel::Command))
+        .map(|config| config.max_memory_usage_bytes)
+        .unwrap_or_default();
+
+    client.channel_available_memory(ClientChannel::Command) >= capacity
+}
@@ -1,6 +1,41 @@
-use bevy::camera::Camera2d;
-use bevy::prelude::Commands;
+use crate::system::input::keyboard_direction;
+use bevy::camera::{Camera2d, Projection};
+use bevy::input::mouse::{AccumulatedMouseScroll, MouseScrollUnit};
+use bevy::input::ButtonInput;
+use bevy::prelude::{Commands, KeyCode, Query, Res, Time, Transform, With};
+
+/// Vitesse de déplacement de la caméra libre, en pixels d'écran par seconde.
+const FREE_CAMERA_SPEED: f32 = 600.0;
+
+/// Variation du zoom de la caméra libre par cran de molette.
+const FREE_CAMERA_ZOOM_STEP: f32 = 0.1;
 
 pub fn spawn_camera(mut commands: Commands) {
     commands.spawn(Camera2d);
 }
+
+/// Caméra libre du mode replay et des spectateurs : déplacement au clavier (WASD, soit ZQSD en
+/// AZERTY, ou flèches) et zoom à la molette.
+pub fn free_camera(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    scroll: Res<AccumulatedMouseScroll>,
+    time: Res<Time>,
+    mut cameras: Query<(&mut Transform, &mut Projection), With<Camera2d>>,
+) {
+    let steps = match scroll.unit {
+        MouseScrollUnit::Line => scroll.delta.y,
+        MouseScrollUnit::Pixel => scroll.delta.y / 100.0,
+    };
+
+    for (mut transform, mut projection) in cameras.iter_mut() {
+        let Projection::Orthographic(orthographic) = &mut *projection else {
+            continue;
+        };
+        orthographic.scale =
+            (orthographic.scale * (1.0 - steps * FREE_CAMERA_ZOOM_STEP)).clamp(0.1, 10.0);
+
+        let direction = keyboard_direction(&keyboard);
+        transform.translation +=
+            (direction * FREE_CAMERA_SPEED * orthographic.scale * time.delta_secs()).extend(0.0);
+    }
+}
@@ -0,0 +1,73 @@
+use crate::resource::ChatLog;
+use bevy::prelude::{ResMut, Result};
+use bevy_egui::{egui, EguiContexts};
+use bevy_renet::renet::RenetClient;
+use game_core::chat::MAX_CHAT_MESSAGE_LEN;
+use game_core::client::{ClientChannel, ClientMessages};
+use game_core::network::serialize_client_message;
+
+/// Couleur des messages d'équipe.
+const TEAM_CHAT_COLOR: egui::Color32 = egui::Color32::from_rgb(120, 200, 255);
+
+/// Affiche la fenêtre de chat : l'historique des messages et la zone de saisie.
+///
+/// Le message saisi est envoyé au serveur avec Entrée sur le canal
+/// `ClientChannel::Command` ; il n'est affiché qu'une fois renvoyé par le serveur.
+/// Sans connexion (lecture d'un replay), seul l'historique est affiché.
+pub fn chat_window(
+    mut contexts: EguiContexts,
+    mut chat_log: ResMut<ChatLog>,
+    client: Option<ResMut<RenetClient>>,
+) -> Result {
+    egui::Window::new("Chat")
+        .default_width(320.0)
+        .anchor(egui::Align2::LEFT_BOTTOM, egui::vec2(8.0, -8.0))
+        .show(contexts.ctx_mut()?, |ui| {
+            egui::ScrollArea::vertical()
+                .max_height(160.0)
+                .stick_to_bottom(true)
+                .auto_shrink([false, true])
+                .show(ui, |ui| {
+                    for entry in &chat_log.entries {
+                        let line = if entry.team_only {
+                            egui::RichText::new(format!("[team] {}: {}", entry.name, entry.text))
+                                .color(TEAM_CHAT_COLOR)
+                        } else {
+                            egui::RichText::new(format!("{}: {}", entry.name, entry.text))
+                        };
+                        ui.label(line);
+                    }
+                });
+
+            let Some(mut client) = client else {
+                return;
+            };
+            ui.separator();
+            ui.horizontal(|ui| {
+                let chat_log = &mut *chat_log;
+                ui.checkbox(&mut chat_log.team_only, "Team");
+                let response = ui.add(
+                    egui::TextEdit::singleline(&mut chat_log.input)
+                        .char_limit(MAX_CHAT_MESSAGE_LEN)
+                        .hint_text("Press Enter to send"),
+                );
+
+                if response.lost_focus() && ui.input(|input| input.key_pressed(egui::Key::Enter)) {
+                    let text = std::mem::take(&mut chat_log.input);
+                    if !text.trim().is_empty() {
+                        client.send_message(
+                            ClientChannel::Command,
+                            serialize_client_message(&ClientMessages::Chat {
+                                text,
+                                team_only: chat_log.team_only,
+                            }),
+                        );
+                    }
+                    response.request_focus();
+                }
+                chat_log.typing = response.has_focus();
+            });
+        });
+
+    Ok(())
+}
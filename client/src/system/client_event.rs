@@ -1,60 +1,216 @@
-use crate::resource::{ClientLobby, CurrentClientId, PlayerMapping};
-use bevy::log::error;
-use bevy::prelude::{info, Assets, ColorMaterial, Commands, Mesh, Res, ResMut};
+use crate::resource::{
+    ChatEntry, ChatLog, ClientLobby, CurrentClientId, LastDisconnect, LoadedMap, PlayerProfile,
+    QueueStatus, SpectatorState,
+};
+use bevy::ecs::entity::MapEntities;
+use bevy::log::{error, warn};
+use bevy::prelude::{
+    info, Assets, ColorMaterial, Commands, Mesh, MessageReader, MessageWriter, Res, ResMut, World,
+};
 use bevy_renet::renet::RenetClient;
 use game_core::client::PlayerEntities;
+use game_core::entity_map::NetworkEntityMap;
+use game_core::event::client_event::ServerMessageReceived;
+use game_core::map::wall_bundle;
 use game_core::network::deserialize_server_message;
-use game_core::player::{spawn_player, ControlledPlayer};
-use game_core::server::{ServerChannel, ServerMessages};
+use game_core::player::{player_bundle, ControlledPlayer};
+use game_core::replication::{
+    apply_replication_frame, apply_replication_update, LastReplicationFrame,
+};
+use game_core::server::{DisconnectReason, ServerChannel, ServerMessages};
 
-pub fn on_server_event(
-    current_client_id: Res<CurrentClientId>,
+/// Oublie tous les joueurs et entités reçus du serveur et supprime leurs entités
+/// locales, par exemple avant une reconnexion.
+pub fn clear_server_entities(
+    lobby: &mut ClientLobby,
+    entity_map: &mut NetworkEntityMap,
+    commands: &mut Commands,
+) {
+    lobby.remove_all_players();
+    for (_, client_entity) in entity_map.iter() {
+        commands.entity(client_entity).try_despawn();
+    }
+    entity_map.clear();
+    commands.insert_resource(LastReplicationFrame::default());
+}
+
+/// Reçoit les messages du serveur sur les canaux `ServerChannel::ServerMessages` et
+/// `ServerChannel::NetworkedEntities`, et les émet en `ServerMessageReceived`.
+pub fn receive_server_messages(
     mut client: ResMut<RenetClient>,
+    mut message_writer: MessageWriter<ServerMessageReceived>,
+) {
+    for channel in [
+        ServerChannel::ServerMessages,
+        ServerChannel::NetworkedEntities,
+    ] {
+        let channel = u8::from(channel);
+        while let Some(payload) = client.receive_message(channel) {
+            let message = deserialize_server_message(&payload).0;
+            message_writer.write(ServerMessageReceived { message });
+        }
+    }
+}
+
+/// Applique les messages du serveur (voir `ServerMessageReceived`), reçus du réseau
+/// ou lus dans un replay.
+///
+/// Sans `CurrentClientId` (lecture d'un replay), aucun joueur n'est contrôlé localement.
+/// Le jeton de session reçu est conservé dans le `PlayerProfile`, pour les reconnexions.
+#[allow(clippy::too_many_arguments)]
+pub fn on_server_event(
+    mut message_reader: MessageReader<ServerMessageReceived>,
+    current_client_id: Option<Res<CurrentClientId>>,
     mut lobby: ResMut<ClientLobby>,
-    mut player_mapping: ResMut<PlayerMapping>,
+    mut entity_map: ResMut<NetworkEntityMap>,
+    mut last_disconnect: ResMut<LastDisconnect>,
+    mut chat_log: ResMut<ChatLog>,
+    mut queue_status: ResMut<QueueStatus>,
+    mut spectator: ResMut<SpectatorState>,
+    mut loaded_map: ResMut<LoadedMap>,
+    mut profile: Option<ResMut<PlayerProfile>>,
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
 ) {
-    while let Some(event) = client.receive_message(ServerChannel::ServerMessages) {
-        match deserialize_server_message(&event).0 {
+    for ServerMessageReceived { message } in message_reader.read() {
+        let mut message = message.clone();
+
+        // Crée l'entité locale des entités annoncées par le serveur, sauf si la
+        // réplication l'a déjà créée, puis remplace les entités serveur du message
+        // par les entités locales.
+        let server_entity = message.spawned_entity();
+        if let Some(server_entity) = server_entity
+            && entity_map.client_entity(&server_entity).is_none()
+        {
+            let client_entity = commands.spawn_empty().id();
+            if let Some(orphan) = entity_map.insert(server_entity, client_entity) {
+                commands.entity(orphan).despawn();
+            }
+        }
+        message.map_entities(&mut *entity_map);
+
+        match message {
             ServerMessages::PlayerCreate {
                 client_id,
                 entity,
                 position,
+                info,
             } => {
-                info!("Player created: {client_id} at {position:?} with entity {entity}");
-                let player = spawn_player(
-                    &client_id,
+                info!(
+                    "Player created: {client_id} ({}) at {position:?} with entity {entity}",
+                    info.name
+                );
+                commands.entity(entity).insert(player_bundle(
+                    info,
                     position,
-                    &mut commands,
                     &mut meshes,
                     &mut materials,
-                );
+                ));
 
-                if current_client_id.0 == client_id {
+                if current_client_id
+                    .as_ref()
+                    .is_some_and(|current_client_id| current_client_id.0 == client_id)
+                {
                     commands.entity(entity).insert(ControlledPlayer);
+                    queue_status.position = None;
+                    spectator.spectating = false;
+                    spectator.follow = None;
                 }
 
-                lobby.add_player(
-                    &client_id,
-                    PlayerEntities {
-                        server_entity: entity,
-                        client_entity: player,
-                    },
-                );
-                player_mapping.add(entity, player);
+                if let Some(server_entity) = server_entity {
+                    lobby.add_player(
+                        &client_id,
+                        PlayerEntities {
+                            server_entity,
+                            client_entity: entity,
+                        },
+                    );
+                }
             }
             ServerMessages::PlayerRemove { client_id } => {
                 info!("Player removed: {client_id}");
+                if spectator.follow == Some(client_id) {
+                    spectator.follow = None;
+                }
                 if let Some(PlayerEntities {
                     server_entity,
                     client_entity,
                 }) = lobby.remove_player(&client_id)
                 {
-                    commands.entity(client_entity).despawn();
-                    player_mapping.remove(&server_entity);
+                    // La réplication a pu supprimer l'entité avant ce message.
+                    commands.entity(client_entity).try_despawn();
+                    entity_map.remove_by_server(&server_entity);
+                }
+            }
+            ServerMessages::Session { token } => {
+                if let Some(profile) = profile.as_mut() {
+                    profile.session_token = Some(token);
+                }
+            }
+            ServerMessages::Replication { update } => {
+                commands.queue(move |world: &mut World| apply_replication_update(world, update));
+            }
+            ServerMessages::ReplicationFrame { frame, changes } => {
+                commands
+                    .queue(move |world: &mut World| apply_replication_frame(world, frame, changes));
+            }
+            ServerMessages::ServerShutdown {
+                reason,
+                restart_eta,
+            } => match restart_eta {
+                Some(eta) => warn!("Server shutting down ({reason}), restart in {eta:?}"),
+                None => warn!("Server shutting down ({reason})"),
+            },
+            ServerMessages::Disconnect { reason } => {
+                if matches!(reason, DisconnectReason::Left) {
+                    info!("Disconnected by server: {reason}");
+                } else {
+                    warn!("Disconnected by server: {reason}");
                 }
+                last_disconnect.record(reason);
+            }
+            ServerMessages::QueuePosition { position, total } => {
+                info!("Server full, waiting in queue: {position}/{total}");
+                queue_status.position = Some((position, total));
+            }
+            ServerMessages::Chat {
+                client_id,
+                name,
+                text,
+                team_only,
+            } => {
+                match client_id {
+                    Some(client_id) => info!("[chat] {name} ({client_id}): {text}"),
+                    None => info!("[chat] {name}: {text}"),
+                }
+                chat_log.push(ChatEntry {
+                    name,
+                    text,
+                    team_only,
+                });
+            }
+            ServerMessages::Spectating => {
+                info!("Spectating the game");
+                spectator.spectating = true;
+            }
+            ServerMessages::Map { map } => {
+                info!(
+                    "Map received: {}x{} with {} walls",
+                    map.size[0],
+                    map.size[1],
+                    map.walls.len()
+                );
+                for wall in loaded_map.walls.drain(..) {
+                    commands.entity(wall).try_despawn();
+                }
+                for wall in &map.walls {
+                    match wall_bundle(wall, &mut meshes, &mut materials) {
+                        Some(bundle) => loaded_map.walls.push(commands.spawn(bundle).id()),
+                        None => warn!("Ignoring non-convex wall {wall:?}"),
+                    }
+                }
+                loaded_map.map = Some(map);
             }
             ServerMessages::Error { message } => {
                 error!("Server error message: {}", message);
@@ -62,3 +218,104 @@ pub fn on_server_event(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::prelude::{App, AppTypeRegistry, Entity, Update, Vec3};
+    use game_core::player::{Health, PlayerInfo};
+    use game_core::replication::{
+        ComponentChange, Replicated, ReplicationPlugin, ReplicationRegistry, ReplicationUpdate,
+    };
+
+    fn client_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(ReplicationPlugin)
+            .add_message::<ServerMessageReceived>()
+            .init_resource::<ClientLobby>()
+            .init_resource::<NetworkEntityMap>()
+            .init_resource::<LastDisconnect>()
+            .init_resource::<ChatLog>()
+            .init_resource::<QueueStatus>()
+            .init_resource::<SpectatorState>()
+            .init_resource::<LoadedMap>()
+            .init_resource::<Assets<Mesh>>()
+            .init_resource::<Assets<ColorMaterial>>()
+            .add_systems(Update, on_server_event);
+        app
+    }
+
+    fn receive(app: &mut App, message: ServerMessages) {
+        app.world_mut()
+            .write_message(ServerMessageReceived { message });
+        app.update();
+    }
+
+    /// Différence annonçant l'entité serveur `entity` et sa santé.
+    fn health_update(app: &App, entity: Entity, health: Health) -> ReplicationUpdate {
+        let mut server = App::new();
+        server.add_plugins(ReplicationPlugin);
+        let server_entity = server.world_mut().spawn((Replicated, health)).id();
+        let registry = app.world().resource::<ReplicationRegistry>();
+        let component = registry.id_of::<Health>().unwrap();
+        let type_registry = server.world().resource::<AppTypeRegistry>().read();
+        let data = (registry.get(component).unwrap().serialize)(
+            &server.world().entity(server_entity),
+            &type_registry,
+        )
+        .unwrap();
+
+        ReplicationUpdate {
+            spawns: vec![entity],
+            changes: vec![ComponentChange {
+                entity,
+                component,
+                data,
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn player_create_reuses_replicated_entity() {
+        let mut app = client_app();
+        let server_entity = Entity::from_raw_u32(12).unwrap();
+
+        let update = health_update(&app, server_entity, Health(40));
+        receive(&mut app, ServerMessages::Replication { update });
+        let replicated = app
+            .world()
+            .resource::<NetworkEntityMap>()
+            .client_entity(&server_entity)
+            .unwrap();
+
+        receive(
+            &mut app,
+            ServerMessages::PlayerCreate {
+                client_id: 3,
+                position: Vec3::new(5.0, 6.0, 0.0),
+                entity: server_entity,
+                info: PlayerInfo {
+                    id: 3,
+                    name: "Bob".to_string(),
+                    color: [255, 0, 0],
+                },
+            },
+        );
+
+        let entity_map = app.world().resource::<NetworkEntityMap>();
+        assert_eq!(entity_map.len(), 1);
+        assert_eq!(entity_map.client_entity(&server_entity), Some(replicated));
+        assert_eq!(
+            app.world()
+                .resource::<ClientLobby>()
+                .get_player_entities(&3)
+                .map(|entities| entities.client_entity),
+            Some(replicated)
+        );
+        let player = app.world().entity(replicated);
+        assert_eq!(player.get::<Health>(), Some(&Health(40)));
+        assert!(player.contains::<Replicated>());
+        assert_eq!(player.get::<PlayerInfo>().map(|info| info.id), Some(3));
+    }
+}
@@ -0,0 +1,102 @@
+use crate::plugin::client_plugin::new_client_transport;
+use crate::resource::{
+    ClientLobby, CurrentClientId, LastDisconnect, PlayerProfile, QueueStatus, SpectatorState,
+};
+use crate::system::client_event::clear_server_entities;
+use bevy::app::AppExit;
+use bevy::prelude::{Commands, Local, MessageWriter, Res, ResMut, Result};
+use bevy_egui::{egui, EguiContexts};
+use game_core::entity_map::NetworkEntityMap;
+
+/// Affiche l'écran de déconnexion avec la raison fournie par le serveur.
+///
+/// Le bouton « Reconnect » recrée la connexion avec le même identifiant client,
+/// ce qui permet de récupérer son joueur pendant le délai de grâce du serveur.
+/// Si le serveur a refusé la connexion faute de mot de passe valide, l'écran
+/// demande le mot de passe avant de se reconnecter.
+#[allow(clippy::too_many_arguments)]
+pub fn disconnected_screen(
+    mut contexts: EguiContexts,
+    mut last_disconnect: ResMut<LastDisconnect>,
+    mut lobby: ResMut<ClientLobby>,
+    mut queue_status: ResMut<QueueStatus>,
+    mut spectator: ResMut<SpectatorState>,
+    mut entity_map: ResMut<NetworkEntityMap>,
+    current_client_id: Res<CurrentClientId>,
+    mut profile: ResMut<PlayerProfile>,
+    mut password: Local<String>,
+    mut commands: Commands,
+    mut exit_writer: MessageWriter<AppExit>,
+) -> Result {
+    if !last_disconnect.is_set() {
+        return Ok(());
+    }
+
+    let requires_password = last_disconnect
+        .reason
+        .as_ref()
+        .is_some_and(|reason| reason.requires_password());
+    let mut reconnect = false;
+    egui::Window::new("Disconnected")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(contexts.ctx_mut()?, |ui| {
+            match (&last_disconnect.reason, &last_disconnect.transport) {
+                (Some(reason), _) => ui.label(reason.to_string()),
+                (None, Some(transport)) => ui.label(format!("Connection lost ({transport})")),
+                (None, None) => ui.label("Connection lost"),
+            };
+
+            if requires_password {
+                ui.horizontal(|ui| {
+                    ui.label("Password");
+                    let response =
+                        ui.add(egui::TextEdit::singleline(&mut *password).password(true));
+                    reconnect |= response.lost_focus()
+                        && ui.input(|input| input.key_pressed(egui::Key::Enter));
+                });
+            }
+
+            ui.horizontal(|ui| {
+                reconnect |= ui.button("Reconnect").clicked();
+                if ui.button("Quit").clicked() {
+                    exit_writer.write(AppExit::Success);
+                }
+            });
+        });
+
+    if reconnect {
+        clear_server_entities(&mut lobby, &mut entity_map, &mut commands);
+        last_disconnect.clear();
+        queue_status.position = None;
+        *spectator = SpectatorState::default();
+        if requires_password {
+            profile.password = Some(std::mem::take(&mut *password));
+        }
+
+        let (client, transport) = new_client_transport(current_client_id.0, &profile);
+        commands.insert_resource(client);
+        commands.insert_resource(transport);
+    }
+
+    Ok(())
+}
+
+/// Affiche la position du client dans la file d'attente lorsque le serveur est plein.
+pub fn queue_screen(mut contexts: EguiContexts, queue_status: Res<QueueStatus>) -> Result {
+    let Some((position, total)) = queue_status.position else {
+        return Ok(());
+    };
+
+    egui::Window::new("Server full")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(contexts.ctx_mut()?, |ui| {
+            ui.label("Waiting for a free slot...");
+            ui.label(format!("Position in queue: {position}/{total}"));
+        });
+
+    Ok(())
+}
@@ -0,0 +1,131 @@
+use crate::resource::{
+    ChatLog, CurrentClientId, InputDirection, InputFire, InputSequence, SpectatorState,
+};
+use crate::system::projectile::spawn_predicted_projectile;
+use bevy::asset::Assets;
+use bevy::camera::Camera;
+use bevy::input::ButtonInput;
+use bevy::mesh::Mesh;
+use bevy::prelude::{
+    ColorMaterial, Commands, GlobalTransform, KeyCode, MouseButton, Query, Res, ResMut, Transform,
+    Vec2, With,
+};
+use bevy::window::{PrimaryWindow, Window};
+use bevy_renet::renet::RenetClient;
+use game_core::client::{ClientChannel, PlayerInput};
+use game_core::network::serialize_player_input;
+use game_core::player::ControlledPlayer;
+
+/// Choisit la direction de déplacement au clavier (WASD, soit ZQSD en AZERTY,
+/// ou flèches). Le joueur reste immobile pendant la saisie d'un message de chat.
+pub fn keyboard_input_direction(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    chat_log: Res<ChatLog>,
+    mut direction: ResMut<InputDirection>,
+) {
+    direction.0 = if chat_log.typing {
+        Vec2::ZERO
+    } else {
+        keyboard_direction(&keyboard)
+    };
+}
+
+/// Vise à la souris : tant que le bouton gauche est enfoncé, l'`InputFire` pointe
+/// du joueur contrôlé vers le curseur. Le joueur ne tire pas pendant la saisie d'un
+/// message de chat.
+pub fn mouse_input_fire(
+    mouse: Res<ButtonInput<MouseButton>>,
+    chat_log: Res<ChatLog>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    players: Query<&Transform, With<ControlledPlayer>>,
+    mut fire: ResMut<InputFire>,
+) {
+    fire.direction = None;
+    if chat_log.typing || !mouse.pressed(MouseButton::Left) {
+        return;
+    }
+
+    let Some(cursor) = windows.iter().find_map(Window::cursor_position) else {
+        return;
+    };
+    let Some(target) = cameras.iter().find_map(|(camera, camera_transform)| {
+        camera.viewport_to_world_2d(camera_transform, cursor).ok()
+    }) else {
+        return;
+    };
+    let Ok(player) = players.single() else {
+        return;
+    };
+    fire.direction = (target - player.translation.truncate()).try_normalize();
+}
+
+/// Envoie au serveur l'`InputDirection` et l'`InputFire` courantes, une fois par
+/// tick fixe.
+///
+/// Seules les directions sont transmises : le serveur calcule le déplacement et
+/// les tirs, et renvoie leurs positions par réplication. Un tir n'est envoyé que
+/// si la cadence de tir le permet ; son projectile est aussitôt prédit localement.
+/// Un spectateur n'envoie rien.
+#[allow(clippy::too_many_arguments)]
+pub fn send_player_input(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    direction: Res<InputDirection>,
+    mut fire: ResMut<InputFire>,
+    spectator: Res<SpectatorState>,
+    current_client_id: Res<CurrentClientId>,
+    players: Query<&Transform, With<ControlledPlayer>>,
+    mut sequence: ResMut<InputSequence>,
+    mut client: ResMut<RenetClient>,
+) {
+    if spectator.spectating {
+        return;
+    }
+
+    sequence.0 = sequence.0.wrapping_add(1);
+    let player = players.single().ok();
+    let fire_direction = fire
+        .direction
+        .filter(|_| player.is_some() && fire.cooldown.is_ready(sequence.0));
+    if let (Some(fire_direction), Some(player)) = (fire_direction, player) {
+        fire.cooldown.fire(sequence.0);
+        spawn_predicted_projectile(
+            current_client_id.0,
+            sequence.0,
+            player.translation.truncate(),
+            fire_direction,
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+        );
+    }
+
+    let input = PlayerInput {
+        sequence: sequence.0,
+        direction: direction.0.to_array(),
+        fire: fire_direction.map(|direction| direction.to_array()),
+    };
+    client.send_message(ClientChannel::Input, serialize_player_input(&input));
+}
+
+/// Direction normalisée correspondant aux touches enfoncées.
+pub fn keyboard_direction(keyboard: &ButtonInput<KeyCode>) -> Vec2 {
+    let axis = |positive: [KeyCode; 2], negative: [KeyCode; 2]| {
+        let pressed = |keys: [KeyCode; 2]| keyboard.any_pressed(keys) as i8 as f32;
+        pressed(positive) - pressed(negative)
+    };
+
+    Vec2::new(
+        axis(
+            [KeyCode::KeyD, KeyCode::ArrowRight],
+            [KeyCode::KeyA, KeyCode::ArrowLeft],
+        ),
+        axis(
+            [KeyCode::KeyW, KeyCode::ArrowUp],
+            [KeyCode::KeyS, KeyCode::ArrowDown],
+        ),
+    )
+    .normalize_or_zero()
+}
@@ -0,0 +1,36 @@
+use bevy::asset::Assets;
+use bevy::mesh::Mesh;
+use bevy::prelude::{
+    Added, ColorMaterial, Commands, Entity, Query, Res, ResMut, Time, Transform, Without,
+};
+use game_core::physics::{body_visual_bundle, PhysicsBody, Velocity};
+use game_core::projectile::PredictedProjectile;
+
+/// Affiche la forme de chaque corps physique répliqué.
+pub fn spawn_body_visuals(
+    mut commands: Commands,
+    bodies: Query<(Entity, &PhysicsBody), Added<PhysicsBody>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (entity, body) in bodies.iter() {
+        commands
+            .entity(entity)
+            .insert(body_visual_bundle(body, &mut meshes, &mut materials));
+    }
+}
+
+/// Fait avancer les corps physiques et les projectiles distants à leur `Velocity`
+/// entre deux états reçus du serveur : ils se comportent comme des corps
+/// cinématiques, que chaque réplication de leur `Transform` recale sur la
+/// simulation du serveur. Les projectiles prédits ont leur propre simulation.
+pub fn extrapolate_bodies(
+    time: Res<Time>,
+    mut bodies: Query<(&Velocity, &mut Transform), Without<PredictedProjectile>>,
+) {
+    for (velocity, mut transform) in bodies.iter_mut() {
+        if *velocity != Velocity::default() {
+            velocity.integrate(&mut transform, time.delta_secs());
+        }
+    }
+}
@@ -0,0 +1,106 @@
+use bevy::asset::Assets;
+use bevy::mesh::{Mesh, Mesh2d};
+use bevy::prelude::{
+    default, Added, Annulus, Changed, Children, Color, ColorMaterial, Commands, Component, Entity,
+    MeshMaterial2d, Name, Query, Rectangle, ResMut, Text2d, TextColor, TextFont, Transform, Vec3,
+    With,
+};
+use game_core::player::{ControlledPlayer, Health, PlayerInfo, PLAYER_RADIUS};
+
+/// Couleur de l'anneau entourant le joueur contrôlé localement.
+const HIGHLIGHT_COLOR: Color = Color::srgb(1.0, 0.9, 0.3);
+
+/// Couleur de la barre de vie des joueurs.
+const HEALTH_BAR_COLOR: Color = Color::srgb(0.3, 0.85, 0.35);
+
+/// Hauteur de la barre de vie, affichée sous le joueur.
+const HEALTH_BAR_HEIGHT: f32 = 5.0;
+
+/// Barre de vie d'un joueur, enfant de son entité.
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct HealthBar;
+
+/// Ajoute une étiquette affichant le nom au-dessus de chaque joueur répliqué.
+pub fn spawn_nameplates(
+    mut commands: Commands,
+    players: Query<(Entity, &PlayerInfo), Added<PlayerInfo>>,
+) {
+    for (entity, info) in players.iter() {
+        commands.entity(entity).with_child((
+            Name::new("Nameplate"),
+            Text2d::new(info.name.clone()),
+            TextFont {
+                font_size: 18.0,
+                ..default()
+            },
+            TextColor(Color::WHITE),
+            Transform::from_xyz(0.0, PLAYER_RADIUS + 16.0, 1.0),
+        ));
+    }
+}
+
+/// Entoure le joueur contrôlé localement d'un anneau pour le distinguer des autres.
+pub fn highlight_controlled_player(
+    mut commands: Commands,
+    players: Query<Entity, Added<ControlledPlayer>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for entity in players.iter() {
+        commands.entity(entity).with_child((
+            Name::new("Highlight"),
+            Mesh2d(meshes.add(Mesh::from(Annulus::new(
+                PLAYER_RADIUS + 2.0,
+                PLAYER_RADIUS + 6.0,
+            )))),
+            MeshMaterial2d(materials.add(ColorMaterial::from_color(HIGHLIGHT_COLOR))),
+            Transform::default(),
+        ));
+    }
+}
+
+/// Ajoute une barre de vie sous chaque joueur répliqué avec sa `Health`.
+pub fn spawn_health_bars(
+    mut commands: Commands,
+    players: Query<(Entity, &Health), Added<Health>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for (entity, health) in players.iter() {
+        commands.entity(entity).with_child((
+            Name::new("Health bar"),
+            HealthBar,
+            Mesh2d(meshes.add(Mesh::from(Rectangle::new(
+                2.0 * PLAYER_RADIUS,
+                HEALTH_BAR_HEIGHT,
+            )))),
+            MeshMaterial2d(materials.add(ColorMaterial::from_color(HEALTH_BAR_COLOR))),
+            health_bar_transform(health),
+        ));
+    }
+}
+
+/// Raccourcit la barre de vie des joueurs dont la `Health` a changé.
+pub fn update_health_bars(
+    players: Query<(&Health, &Children), Changed<Health>>,
+    mut bars: Query<&mut Transform, With<HealthBar>>,
+) {
+    for (health, children) in players.iter() {
+        let mut bars = bars.iter_many_mut(children);
+        while let Some(mut transform) = bars.fetch_next() {
+            *transform = health_bar_transform(health);
+        }
+    }
+}
+
+/// Position et échelle de la barre de vie : alignée à gauche sous le joueur, sa
+/// largeur suit la part des points de vie restants.
+fn health_bar_transform(health: &Health) -> Transform {
+    let fraction = health.fraction();
+    Transform::from_xyz(
+        -(1.0 - fraction) * PLAYER_RADIUS,
+        -(PLAYER_RADIUS + 10.0),
+        1.0,
+    )
+    .with_scale(Vec3::new(fraction, 1.0, 1.0))
+}
@@ -0,0 +1,203 @@
+use crate::resource::{CurrentClientId, LoadedMap};
+use bevy::asset::Assets;
+use bevy::mesh::{Mesh, Mesh2d};
+use bevy::prelude::{
+    Added, ColorMaterial, Commands, Entity, Query, Res, ResMut, Time, Transform, Vec2, Visibility,
+    With, Without,
+};
+use bevy_renet::renet::ClientId;
+use game_core::map::StaticCollider;
+use game_core::physics::{PhysicsBody, Velocity};
+use game_core::player::{PlayerInfo, PLAYER_RADIUS};
+use game_core::projectile::{
+    muzzle_position, projectile_bundle, projectile_hits_shape, projectile_visual_bundle,
+    sweep_projectile, PredictedProjectile, Projectile, ProjectileLifetime, ReconciledProjectile,
+    PROJECTILE_RADIUS,
+};
+
+/// Vitesse à laquelle un projectile prédit rejoint le projectile du serveur : la
+/// fraction de l'écart restant après `t` secondes est `exp(-t × taux)`.
+const PROJECTILE_RECONCILE_RATE: f32 = 12.0;
+
+/// Écart, en unités de jeu, en deçà duquel le projectile prédit laisse la place
+/// au projectile du serveur.
+const PROJECTILE_RECONCILE_DISTANCE: f32 = 1.0;
+
+/// Prédit le projectile du tir `shot_id`, tiré dans `direction` par le joueur
+/// contrôlé centré en `shooter`, sans attendre le serveur.
+pub fn spawn_predicted_projectile(
+    owner: ClientId,
+    shot_id: u32,
+    shooter: Vec2,
+    direction: Vec2,
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+) {
+    commands.spawn((
+        projectile_bundle(
+            Projectile { owner, shot_id },
+            muzzle_position(shooter, direction),
+            direction,
+            meshes,
+            materials,
+        ),
+        PredictedProjectile,
+    ));
+}
+
+/// Affiche chaque projectile répliqué.
+pub fn spawn_projectile_visuals(
+    mut commands: Commands,
+    projectiles: Query<Entity, (Added<Projectile>, Without<Mesh2d>)>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    for entity in projectiles.iter() {
+        commands
+            .entity(entity)
+            .insert(projectile_visual_bundle(&mut meshes, &mut materials));
+    }
+}
+
+/// Fait avancer les projectiles prédits comme le serveur : un projectile prédit
+/// disparaît à la fin de sa durée de vie ou au premier joueur (hors tireur), mur,
+/// corps physique ou bord de l'arène touché. Les dégâts restent décidés par le
+/// serveur ; le projectile du serveur rattaché à une prédiction qui disparaît est
+/// affiché à sa place.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn update_predicted_projectiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    current_client_id: Res<CurrentClientId>,
+    loaded_map: Res<LoadedMap>,
+    mut projectiles: Query<
+        (
+            Entity,
+            &Velocity,
+            &mut ProjectileLifetime,
+            &mut Transform,
+            Option<&ReconciledProjectile>,
+        ),
+        With<PredictedProjectile>,
+    >,
+    players: Query<(&PlayerInfo, &Transform), Without<PredictedProjectile>>,
+    walls: Query<(&StaticCollider, &Transform), Without<PredictedProjectile>>,
+    bodies: Query<(&PhysicsBody, &Transform), Without<PredictedProjectile>>,
+) {
+    let delta_secs = time.delta_secs();
+    let arena_half_size = loaded_map.map.as_ref().map(|map| map.half_size());
+    let hits = |position: Vec2| {
+        let hits_arena =
+            arena_half_size.is_some_and(|half_size| position.abs().cmpgt(half_size).any());
+        let hits_player = players.iter().any(|(info, transform)| {
+            info.id != current_client_id.0
+                && position.distance(transform.translation.truncate())
+                    < PLAYER_RADIUS + PROJECTILE_RADIUS
+        });
+        let hits_wall = walls.iter().any(|(collider, transform)| {
+            projectile_hits_shape(
+                position,
+                transform.translation.truncate(),
+                transform.rotation,
+                &collider.shape,
+            )
+        });
+        let hits_body = bodies.iter().any(|(body, transform)| {
+            projectile_hits_shape(
+                position,
+                transform.translation.truncate(),
+                transform.rotation,
+                &body.shape,
+            )
+        });
+        (hits_arena || hits_player || hits_wall || hits_body).then_some(())
+    };
+
+    for (entity, velocity, mut lifetime, mut transform, reconciled) in projectiles.iter_mut() {
+        lifetime.0 -= delta_secs;
+        let start = transform.translation.truncate();
+        let end = start + velocity.linear * delta_secs;
+        if lifetime.0 <= 0.0 || sweep_projectile(start, end, &hits).is_some() {
+            commands.entity(entity).despawn();
+            if let Some(reconciled) = reconciled {
+                commands
+                    .entity(reconciled.server)
+                    .try_insert(Visibility::Inherited);
+            }
+            continue;
+        }
+        transform.translation = end.extend(transform.translation.z);
+    }
+}
+
+/// Rattache chaque projectile prédit au projectile du serveur de même
+/// `Projectile::shot_id`, dès que celui-ci est répliqué (voir `ReconciledProjectile`).
+///
+/// Le projectile du serveur, parti plus tard, est en retrait : il est caché
+/// pendant que la prédiction le rejoint (voir `blend_reconciled_projectiles`),
+/// plutôt que de faire reculer le tir à l'écran.
+///
+/// Un tir refusé par le serveur n'a pas de projectile répliqué : sa prédiction
+/// disparaît seule à la fin de sa durée de vie.
+#[allow(clippy::type_complexity)]
+pub fn reconcile_predicted_projectiles(
+    mut commands: Commands,
+    current_client_id: Res<CurrentClientId>,
+    replicated: Query<(Entity, &Projectile), (Added<Projectile>, Without<PredictedProjectile>)>,
+    predicted: Query<
+        (Entity, &Projectile),
+        (With<PredictedProjectile>, Without<ReconciledProjectile>),
+    >,
+) {
+    for (server, projectile) in replicated.iter() {
+        if projectile.owner != current_client_id.0 {
+            continue;
+        }
+        let Some((entity, _)) = predicted
+            .iter()
+            .find(|(_, predicted)| *predicted == projectile)
+        else {
+            continue;
+        };
+        commands
+            .entity(entity)
+            .insert(ReconciledProjectile { server });
+        commands.entity(server).insert(Visibility::Hidden);
+    }
+}
+
+/// Rapproche chaque projectile prédit du projectile du serveur auquel il est
+/// rattaché, après leur avancée de la frame.
+///
+/// Une fois l'écart inférieur à `PROJECTILE_RECONCILE_DISTANCE`, la prédiction
+/// disparaît et le projectile du serveur est affiché. Elle disparaît aussi avec
+/// lui, s'il touche sa cible avant.
+pub fn blend_reconciled_projectiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut predicted: Query<
+        (Entity, &ReconciledProjectile, &mut Transform),
+        With<PredictedProjectile>,
+    >,
+    replicated: Query<&Transform, (With<Projectile>, Without<PredictedProjectile>)>,
+) {
+    let blend = 1.0 - (-PROJECTILE_RECONCILE_RATE * time.delta_secs()).exp();
+    for (entity, reconciled, mut transform) in predicted.iter_mut() {
+        let Ok(server) = replicated.get(reconciled.server) else {
+            commands.entity(entity).despawn();
+            continue;
+        };
+
+        let target = server.translation.truncate();
+        let position = transform.translation.truncate().lerp(target, blend);
+        if position.distance(target) < PROJECTILE_RECONCILE_DISTANCE {
+            commands.entity(entity).despawn();
+            commands
+                .entity(reconciled.server)
+                .insert(Visibility::Inherited);
+            continue;
+        }
+        transform.translation = position.extend(transform.translation.z);
+    }
+}
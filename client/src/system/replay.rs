@@ -0,0 +1,117 @@
+use crate::resource::{ChatLog, ClientLobby, ReplayLoadError, ReplayPlayback, REPLAY_SPEEDS};
+use crate::system::client_event::clear_server_entities;
+use bevy::app::AppExit;
+use bevy::prelude::{Commands, MessageWriter, Res, ResMut, Result, Time};
+use bevy_egui::{egui, EguiContexts};
+use game_core::entity_map::NetworkEntityMap;
+use game_core::event::client_event::ServerMessageReceived;
+
+/// Fait avancer la lecture du replay et émet les messages enregistrés en
+/// `ServerMessageReceived`, comme s'ils venaient du serveur.
+///
+/// Seuls les messages adressés à tous les joueurs sont rejoués. Pour se déplacer
+/// dans le replay, le monde est vidé puis reconstruit à partir du dernier état
+/// complet précédant la cible, et les messages suivants sont rejoués jusqu'à elle.
+#[allow(clippy::too_many_arguments)]
+pub fn play_replay(
+    time: Res<Time>,
+    mut playback: ResMut<ReplayPlayback>,
+    mut lobby: ResMut<ClientLobby>,
+    mut entity_map: ResMut<NetworkEntityMap>,
+    mut chat_log: ResMut<ChatLog>,
+    mut commands: Commands,
+    mut message_writer: MessageWriter<ServerMessageReceived>,
+) {
+    if let Some(target) = playback.seek.take() {
+        clear_server_entities(&mut lobby, &mut entity_map, &mut commands);
+        chat_log.entries.clear();
+        for message in playback.seek_to(target) {
+            message_writer.write(ServerMessageReceived { message });
+        }
+    } else {
+        playback.advance(time.delta_secs_f64());
+    }
+
+    for message in playback.due_messages() {
+        message_writer.write(ServerMessageReceived { message });
+    }
+}
+
+/// Affiche les contrôles du replay : lecture/pause, vitesse et barre de progression.
+pub fn replay_controls(mut contexts: EguiContexts, mut playback: ResMut<ReplayPlayback>) -> Result {
+    let first_tick = playback.first_tick();
+    let last_tick = playback.last_tick();
+
+    egui::Window::new("Replay")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -8.0))
+        .show(contexts.ctx_mut()?, |ui| {
+            ui.horizontal(|ui| {
+                let label = if playback.playing { "Pause" } else { "Play" };
+                if ui.button(label).clicked() {
+                    if !playback.playing && playback.tick >= last_tick as f64 {
+                        playback.seek = Some(first_tick);
+                    }
+                    playback.playing = !playback.playing;
+                }
+
+                for speed in REPLAY_SPEEDS {
+                    if ui
+                        .selectable_label(playback.speed == speed, format!("x{speed}"))
+                        .clicked()
+                    {
+                        playback.speed = speed;
+                    }
+                }
+            });
+
+            ui.spacing_mut().slider_width = 400.0;
+            let mut target = playback.tick as u64;
+            let response =
+                ui.add(egui::Slider::new(&mut target, first_tick..=last_tick).show_value(false));
+            if response.changed() {
+                playback.seek = Some(target);
+            }
+
+            let elapsed = playback.ticks_to_secs(playback.tick - first_tick as f64);
+            let total = playback.ticks_to_secs((last_tick - first_tick) as f64);
+            ui.label(format!(
+                "{} / {}",
+                format_duration(elapsed),
+                format_duration(total)
+            ));
+            if playback.replay.truncated {
+                ui.label("The end of this replay is truncated");
+            }
+        });
+
+    Ok(())
+}
+
+/// Affiche l'erreur de chargement du replay ; le bouton « Quit » ferme le client
+/// avec un code d'erreur.
+pub fn replay_error_screen(
+    mut contexts: EguiContexts,
+    error: Res<ReplayLoadError>,
+    mut exit_writer: MessageWriter<AppExit>,
+) -> Result {
+    egui::Window::new("Replay")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(egui::Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(contexts.ctx_mut()?, |ui| {
+            ui.label(&error.0);
+            if ui.button("Quit").clicked() {
+                exit_writer.write(AppExit::error());
+            }
+        });
+
+    Ok(())
+}
+
+/// Formate une durée en secondes sous la forme `mm:ss`.
+fn format_duration(secs: f64) -> String {
+    let secs = secs.max(0.0) as u64;
+    format!("{:02}:{:02}", secs / 60, secs % 60)
+}
@@ -0,0 +1,98 @@
+use crate::resource::{ClientLobby, QueueStatus, SpectatorState};
+use bevy::camera::Camera2d;
+use bevy::prelude::{Query, Res, ResMut, Result, Transform, With, Without};
+use bevy_egui::{egui, EguiContexts};
+use bevy_renet::renet::RenetClient;
+use game_core::client::{ClientChannel, ClientMessages};
+use game_core::network::serialize_client_message;
+use game_core::player::PlayerInfo;
+
+/// Affiche les commandes du mode spectateur.
+///
+/// Un joueur peut passer en spectateur ; un spectateur choisit entre la caméra
+/// libre et le suivi d'un joueur, et peut demander à rejoindre la partie. Les
+/// demandes sont envoyées au serveur sur le canal `ClientChannel::Command`.
+pub fn spectator_window(
+    mut contexts: EguiContexts,
+    mut spectator: ResMut<SpectatorState>,
+    mut client: ResMut<RenetClient>,
+    lobby: Res<ClientLobby>,
+    queue_status: Res<QueueStatus>,
+    players: Query<&PlayerInfo>,
+) -> Result {
+    let mut request = None;
+    egui::Window::new("Spectator")
+        .collapsible(true)
+        .resizable(false)
+        .anchor(egui::Align2::RIGHT_TOP, egui::vec2(-8.0, 8.0))
+        .show(contexts.ctx_mut()?, |ui| {
+            if !spectator.spectating {
+                if ui.button("Spectate").clicked() {
+                    request = Some(ClientMessages::Spectate);
+                }
+                return;
+            }
+
+            let mut follow = spectator.follow;
+            ui.radio_value(&mut follow, None, "Free camera");
+
+            let mut followable: Vec<_> = lobby
+                .iter()
+                .filter_map(|(client_id, entities)| {
+                    players
+                        .get(entities.client_entity)
+                        .ok()
+                        .map(|info| (*client_id, info.name.as_str()))
+                })
+                .collect();
+            followable.sort_by_key(|(client_id, _)| *client_id);
+            for (client_id, name) in followable {
+                ui.radio_value(&mut follow, Some(client_id), format!("Follow {name}"));
+            }
+            spectator.follow = follow;
+
+            ui.separator();
+            if queue_status.position.is_some() {
+                ui.label("Waiting for a free slot...");
+            } else if ui.button("Join game").clicked() {
+                request = Some(ClientMessages::JoinGame);
+            }
+        });
+
+    if let Some(message) = request {
+        client.send_message(ClientChannel::Command, serialize_client_message(&message));
+    }
+
+    Ok(())
+}
+
+/// Centre la caméra sur le joueur suivi par le spectateur.
+///
+/// Le suivi s'arrête si le joueur a quitté le lobby.
+pub fn follow_player(
+    mut spectator: ResMut<SpectatorState>,
+    lobby: Res<ClientLobby>,
+    players: Query<&Transform, (With<PlayerInfo>, Without<Camera2d>)>,
+    mut cameras: Query<&mut Transform, With<Camera2d>>,
+) {
+    let Some(follow) = spectator.follow else {
+        return;
+    };
+    let Some(target) = lobby
+        .get_player_entities(&follow)
+        .and_then(|entities| players.get(entities.client_entity).ok())
+    else {
+        spectator.follow = None;
+        return;
+    };
+
+    for mut transform in cameras.iter_mut() {
+        transform.translation.x = target.translation.x;
+        transform.translation.y = target.translation.y;
+    }
+}
+
+/// Condition d'exécution : le client est spectateur et ne suit aucun joueur.
+pub fn spectating_free_camera(spectator: Res<SpectatorState>) -> bool {
+    spectator.spectating && spectator.follow.is_none()
+}
@@ -0,0 +1,17 @@
+/// Longueur maximale (en caractères) d'un message de chat.
+pub const MAX_CHAT_MESSAGE_LEN: usize = 200;
+
+/// Nettoie un message de chat reçu du réseau.
+///
+/// - Supprime les caractères de contrôle et les espaces en début et fin de message.
+/// - Retourne `None` si le message est vide ou dépasse `MAX_CHAT_MESSAGE_LEN` caractères.
+pub fn sanitize_chat_text(raw: &str) -> Option<String> {
+    let text: String = raw.chars().filter(|c| !c.is_control()).collect();
+    let text = text.trim();
+
+    if text.is_empty() || text.chars().count() > MAX_CHAT_MESSAGE_LEN {
+        None
+    } else {
+        Some(text.to_string())
+    }
+}
@@ -1,5 +1,10 @@
+use crate::network::{PROTOCOL_ID, PROTOCOL_VERSION};
+use crate::player::clean_player_name;
+use bevy::log::error;
 use bevy::prelude::Entity;
-use bevy_renet::renet::{ChannelConfig, SendType};
+use bevy_renet::netcode::NETCODE_USER_DATA_BYTES;
+use bevy_renet::renet::{ChannelConfig, ClientId, SendType};
+use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
 /// Informations liant l'entité côté client à l'entité correspondante côté serveur.
@@ -12,10 +17,50 @@ pub struct PlayerEntities {
     pub server_entity: Entity,
 }
 
+/// Messages envoyés par le client au serveur sur le canal `ClientChannel::Command`.
+///
+/// Ces messages sont sérialisés via `serde` ; le serveur doit les valider, car ils
+/// proviennent d'un client non fiable.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ClientMessages {
+    /// Message de chat.
+    ///
+    /// - `text` : texte saisi par le joueur (au plus `MAX_CHAT_MESSAGE_LEN` caractères).
+    /// - `team_only` : réserve le message aux membres de l'équipe du joueur.
+    Chat { text: String, team_only: bool },
+    /// Quitte la partie (ou la file d'attente) pour la regarder en spectateur.
+    Spectate,
+    /// Demande à un spectateur de rejoindre la partie dès qu'une place est libre.
+    JoinGame,
+    /// Quitte définitivement le serveur : le joueur est retiré sans attendre le
+    /// délai de reconnexion. Le client se déconnecte une fois le message acquitté.
+    Leave,
+}
+
+/// Entrée de déplacement envoyée par le client à chaque tick fixe sur le canal
+/// `ClientChannel::Input`.
+///
+/// - `sequence` : numéro croissant de l'entrée, permettant d'écarter les entrées
+///   rejouées ou désordonnées.
+/// - `direction` : direction de déplacement souhaitée `[x, y]`, de norme au plus 1.
+/// - `fire` : direction `[x, y]` d'un tir à ce tick, le cas échéant ; le tir est
+///   identifié par `sequence` (voir `Projectile::shot_id`).
+///
+/// Le client n'envoie jamais sa position ni sa vitesse : le serveur calcule le
+/// déplacement et crée les projectiles à partir de ces entrées, après les avoir
+/// validées.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlayerInput {
+    pub sequence: u32,
+    pub direction: [f32; 2],
+    pub fire: Option<[f32; 2]>,
+}
+
 /// Canal utilisé par le client pour envoyer des paquets au serveur.
 ///
 /// - `Input` : envoie les entrées du joueur (contrôles, mouvements) à haute fréquence.
 /// - `Command` : envoie des commandes ponctuelles (ex : chat, actions, requêtes).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ClientChannel {
     /// Entrées de contrôle du joueur.
     Input,
@@ -65,3 +110,187 @@ impl ClientChannel {
         ]
     }
 }
+
+/// Empreinte salée d'un mot de passe de serveur (voir `hash_password`).
+pub type PasswordHash = [u8; 32];
+
+/// Calcule l'empreinte du mot de passe envoyée par le client à la connexion.
+///
+/// Le mot de passe est salé avec le `PROTOCOL_ID` et l'identifiant du client :
+/// l'empreinte ne peut pas être rejouée par un autre client. Les `user_data`
+/// circulant en clair, cela ne protège pas d'une écoute du réseau.
+pub fn hash_password(password: &str, client_id: ClientId) -> PasswordHash {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&PROTOCOL_ID.to_le_bytes());
+    hasher.update(&client_id.to_le_bytes());
+    hasher.update(password.as_bytes());
+    *hasher.finalize().as_bytes()
+}
+
+/// Vérifie l'empreinte d'un mot de passe en temps constant.
+pub fn verify_password(password: &str, client_id: ClientId, hash: &PasswordHash) -> bool {
+    blake3::Hash::from_bytes(hash_password(password, client_id)) == blake3::Hash::from_bytes(*hash)
+}
+
+/// Jeton de session remis par le serveur au seul client d'un joueur (voir
+/// `ServerMessages::Session`).
+///
+/// Renvoyé dans les `ConnectUserData` d'une reconnexion, il prouve que le client
+/// est bien celui du joueur déconnecté : l'identifiant, choisi par le client, ne
+/// suffit pas.
+pub type SessionToken = [u8; 32];
+
+/// Compare deux jetons de session en temps constant.
+pub fn verify_session_token(expected: &SessionToken, token: &SessionToken) -> bool {
+    blake3::Hash::from_bytes(*expected) == blake3::Hash::from_bytes(*token)
+}
+
+/// Taille de l'en-tête des `user_data` : le `PROTOCOL_VERSION` du client.
+const USER_DATA_VERSION_BYTES: usize = 4;
+
+/// Données transmises par le client dans le champ `user_data` de la connexion netcode.
+///
+/// Les `NETCODE_USER_DATA_BYTES` (256) octets disponibles commencent par le
+/// `PROTOCOL_VERSION` du client, lisible quelle que soit sa version, suivi des
+/// données sérialisées en bincode ; les octets restants sont laissés à zéro. Le
+/// serveur doit valider et nettoyer ces données, qui proviennent d'un client non fiable.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConnectUserData {
+    /// Nom souhaité par le joueur.
+    pub name: String,
+    /// Couleur cosmétique `[r, g, b]` souhaitée par le joueur.
+    pub color: Option<[u8; 3]>,
+    /// Empreinte du mot de passe du serveur, si le joueur en a saisi un.
+    pub password_hash: Option<PasswordHash>,
+    /// Empreinte du mot de passe d'administration, si le joueur s'identifie comme
+    /// administrateur.
+    pub admin_password_hash: Option<PasswordHash>,
+    /// Jeton de la session précédente, pour récupérer son joueur après une coupure.
+    pub session_token: Option<SessionToken>,
+    /// Rejoint le serveur en spectateur, sans créer de joueur.
+    pub spectator: bool,
+}
+
+impl ConnectUserData {
+    /// Encode les données dans le tampon `user_data` de netcode.
+    ///
+    /// Le nom est d'abord nettoyé et tronqué à `MAX_PLAYER_NAME_LEN` caractères
+    /// (voir `clean_player_name`) : les données tiennent alors toujours dans le tampon.
+    /// En cas d'échec malgré tout, l'erreur est journalisée et un tampon vide est
+    /// retourné : le serveur utilisera alors ses valeurs par défaut.
+    pub fn to_user_data(&self) -> [u8; NETCODE_USER_DATA_BYTES] {
+        let data = ConnectUserData {
+            name: clean_player_name(&self.name),
+            ..self.clone()
+        };
+        let mut user_data = [0; NETCODE_USER_DATA_BYTES];
+        user_data[..USER_DATA_VERSION_BYTES].copy_from_slice(&PROTOCOL_VERSION.to_le_bytes());
+        if let Err(err) = bincode::serde::encode_into_slice(
+            &data,
+            &mut user_data[USER_DATA_VERSION_BYTES..],
+            bincode::config::standard(),
+        ) {
+            error!("User data serialization error: {:?}", err);
+            user_data[USER_DATA_VERSION_BYTES..].fill(0);
+        }
+        user_data
+    }
+
+    /// Lit le `PROTOCOL_VERSION` du client en tête du tampon `user_data` de netcode.
+    pub fn protocol_version(user_data: &[u8; NETCODE_USER_DATA_BYTES]) -> u32 {
+        let mut version = [0; USER_DATA_VERSION_BYTES];
+        version.copy_from_slice(&user_data[..USER_DATA_VERSION_BYTES]);
+        u32::from_le_bytes(version)
+    }
+
+    /// Décode les données depuis le tampon `user_data` de netcode.
+    ///
+    /// Retourne `None` si le tampon ne contient pas de données valides, ou s'il a
+    /// été encodé par une autre version du protocole.
+    pub fn from_user_data(user_data: &[u8; NETCODE_USER_DATA_BYTES]) -> Option<Self> {
+        if Self::protocol_version(user_data) != PROTOCOL_VERSION {
+            return None;
+        }
+        bincode::serde::decode_from_slice(
+            &user_data[USER_DATA_VERSION_BYTES..],
+            bincode::config::standard(),
+        )
+        .map(|(data, _)| data)
+        .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::MAX_PLAYER_NAME_LEN;
+
+    #[test]
+    fn user_data_round_trip() {
+        let data = ConnectUserData {
+            name: "Alice".to_string(),
+            color: Some([10, 20, 30]),
+            password_hash: Some(hash_password("secret", 7)),
+            admin_password_hash: Some(hash_password("admin", 7)),
+            session_token: Some([7; 32]),
+            spectator: true,
+        };
+
+        let user_data = data.to_user_data();
+
+        assert_eq!(
+            ConnectUserData::protocol_version(&user_data),
+            PROTOCOL_VERSION
+        );
+        let decoded = ConnectUserData::from_user_data(&user_data).unwrap();
+        assert_eq!(decoded.name, "Alice");
+        assert_eq!(decoded.color, Some([10, 20, 30]));
+        assert!(verify_password(
+            "secret",
+            7,
+            &decoded.password_hash.unwrap()
+        ));
+        assert!(verify_password(
+            "admin",
+            7,
+            &decoded.admin_password_hash.unwrap()
+        ));
+        assert_eq!(decoded.session_token, Some([7; 32]));
+        assert!(decoded.spectator);
+    }
+
+    #[test]
+    fn long_names_are_truncated_to_fit() {
+        // Chaque caractère occupe 4 octets en UTF-8 : 1000 caractères ne tiendraient
+        // pas dans les 256 octets des `user_data`.
+        let data = ConnectUserData {
+            name: "\u{1F600}".repeat(1000),
+            color: Some([255, 255, 255]),
+            password_hash: Some(hash_password("secret", u64::MAX)),
+            admin_password_hash: Some(hash_password("admin", u64::MAX)),
+            session_token: Some([u8::MAX; 32]),
+            spectator: true,
+        };
+
+        let decoded = ConnectUserData::from_user_data(&data.to_user_data()).unwrap();
+
+        assert_eq!(decoded.name, "\u{1F600}".repeat(MAX_PLAYER_NAME_LEN));
+        assert_eq!(decoded.color, Some([255, 255, 255]));
+        assert!(decoded.password_hash.is_some());
+        assert!(decoded.admin_password_hash.is_some());
+        assert!(decoded.session_token.is_some());
+        assert!(decoded.spectator);
+    }
+
+    #[test]
+    fn user_data_of_another_version_is_rejected() {
+        let mut user_data = ConnectUserData::default().to_user_data();
+        user_data[..USER_DATA_VERSION_BYTES].copy_from_slice(&(PROTOCOL_VERSION + 1).to_le_bytes());
+
+        assert_eq!(
+            ConnectUserData::protocol_version(&user_data),
+            PROTOCOL_VERSION + 1
+        );
+        assert!(ConnectUserData::from_user_data(&user_data).is_none());
+    }
+}
@@ -0,0 +1,383 @@
+use bevy::math::Vec2;
+use bevy::mesh::Mesh;
+use bevy::prelude::{ConvexPolygon, Rectangle};
+use serde::{Deserialize, Serialize};
+
+/// Nombre de passes de résolution contre le décor : un cercle repoussé hors d'un
+/// collider peut en chevaucher un autre, par exemple dans un angle.
+pub const STATIC_COLLISION_PASSES: usize = 4;
+
+/// Nombre de passes de séparation des joueurs : écarter deux joueurs peut en
+/// pousser un contre un troisième.
+pub const PLAYER_COLLISION_PASSES: usize = 4;
+
+/// Forme d'un collider, relative au centre de son entité.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ColliderShape {
+    /// Rectangle aligné sur les axes, de demi-dimensions `half_size`.
+    Aabb { half_size: Vec2 },
+    /// Polygone convexe dont les sommets sont donnés dans le sens trigonométrique.
+    Polygon { points: Vec<Vec2> },
+}
+
+impl ColliderShape {
+    /// Polygone convexe de sommets `points`, remis dans le sens trigonométrique.
+    ///
+    /// Retourne `None` si le polygone a moins de 3 sommets ou n'est pas convexe.
+    pub fn polygon(mut points: Vec<Vec2>) -> Option<Self> {
+        if points.len() < 3 {
+            return None;
+        }
+        if signed_area(&points) < 0.0 {
+            points.reverse();
+        }
+        let convex = (0..points.len()).all(|i| {
+            let a = points[i];
+            let b = points[(i + 1) % points.len()];
+            let c = points[(i + 2) % points.len()];
+            (b - a).perp_dot(c - b) >= 0.0
+        });
+        convex.then_some(ColliderShape::Polygon { points })
+    }
+
+    /// Demi-dimensions du rectangle englobant la forme, centré sur son entité.
+    pub fn half_extents(&self) -> Vec2 {
+        match self {
+            ColliderShape::Aabb { half_size } => *half_size,
+            ColliderShape::Polygon { points } => points
+                .iter()
+                .fold(Vec2::ZERO, |extents, point| extents.max(point.abs())),
+        }
+    }
+
+    /// Maillage 2D de la forme, centré sur l'origine.
+    pub fn mesh(&self) -> Mesh {
+        match self {
+            ColliderShape::Aabb { half_size } => Mesh::from(Rectangle::from_size(*half_size * 2.0)),
+            ColliderShape::Polygon { points } => {
+                Mesh::from(ConvexPolygon::new_unchecked(points.iter().copied()))
+            }
+        }
+    }
+
+    /// Déplacement qui sort le cercle `(circle, radius)` de la forme centrée en
+    /// `center`, ou `None` s'ils ne se chevauchent pas.
+    pub fn circle_pushout(&self, center: Vec2, circle: Vec2, radius: f32) -> Option<Vec2> {
+        match self {
+            ColliderShape::Aabb { half_size } => {
+                circle_aabb_pushout(circle, radius, center, *half_size)
+            }
+            ColliderShape::Polygon { points } => {
+                circle_polygon_pushout(circle, radius, center, points)
+            }
+        }
+    }
+}
+
+/// Repousse le cercle `(position, radius)` hors des corps `bodies` (centre, angle
+/// autour de l'axe Z en radians, et forme).
+///
+/// Contrairement au décor, les corps peuvent tourner : chaque forme est testée
+/// dans le repère du corps.
+pub fn push_out_of_bodies(
+    position: Vec2,
+    radius: f32,
+    bodies: &[(Vec2, f32, &ColliderShape)],
+) -> Vec2 {
+    let mut position = position;
+    for (center, rotation, shape) in bodies {
+        let rotation = Vec2::from_angle(*rotation);
+        let local = *center + Vec2::new(rotation.x, -rotation.y).rotate(position - *center);
+        if let Some(pushout) = shape.circle_pushout(*center, local, radius) {
+            position += rotation.rotate(pushout);
+        }
+    }
+    position
+}
+
+/// Déplacement qui sépare le cercle `a` du cercle `b`, appliqué à `a` seul, ou
+/// `None` s'ils ne se chevauchent pas.
+///
+/// Deux cercles de même centre sont séparés selon l'axe X, pour que le client et
+/// le serveur obtiennent le même résultat.
+pub fn circle_circle_pushout(a: Vec2, radius_a: f32, b: Vec2, radius_b: f32) -> Option<Vec2> {
+    let offset = a - b;
+    let distance = offset.length();
+    let penetration = radius_a + radius_b - distance;
+    if penetration <= 0.0 {
+        return None;
+    }
+    Some(offset.try_normalize().unwrap_or(Vec2::X) * penetration)
+}
+
+/// Déplacement qui sort le cercle `(circle, radius)` du rectangle aligné sur les axes
+/// `(center, half_size)`, ou `None` s'ils ne se chevauchent pas.
+///
+/// Un cercle dont le centre est dans le rectangle sort par le côté le plus proche.
+pub fn circle_aabb_pushout(
+    circle: Vec2,
+    radius: f32,
+    center: Vec2,
+    half_size: Vec2,
+) -> Option<Vec2> {
+    let local = circle - center;
+    let closest = local.clamp(-half_size, half_size);
+    if closest != local {
+        let offset = local - closest;
+        let distance = offset.length();
+        return (distance < radius).then(|| offset / distance * (radius - distance));
+    }
+
+    let depth = half_size - local.abs();
+    let sign = Vec2::new(sign_or_one(local.x), sign_or_one(local.y));
+    Some(if depth.x < depth.y {
+        Vec2::new(sign.x * (depth.x + radius), 0.0)
+    } else {
+        Vec2::new(0.0, sign.y * (depth.y + radius))
+    })
+}
+
+/// Déplacement qui sort le cercle `(circle, radius)` du polygone convexe de sommets
+/// `points` (relatifs à `center`, dans le sens trigonométrique), ou `None` s'ils ne
+/// se chevauchent pas.
+///
+/// Un cercle dont le centre est dans le polygone sort par le côté le plus proche.
+pub fn circle_polygon_pushout(
+    circle: Vec2,
+    radius: f32,
+    center: Vec2,
+    points: &[Vec2],
+) -> Option<Vec2> {
+    let local = circle - center;
+    let mut inside = points.len() >= 3;
+    let mut nearest: Option<(f32, Vec2, Vec2)> = None;
+    for (index, &start) in points.iter().enumerate() {
+        let end = points[(index + 1) % points.len()];
+        let edge = end - start;
+        let outward = Vec2::new(edge.y, -edge.x).normalize_or_zero();
+        inside &= edge.perp_dot(local - start) >= 0.0;
+
+        let t =
+            ((local - start).dot(edge) / edge.length_squared().max(f32::EPSILON)).clamp(0.0, 1.0);
+        let closest = start + edge * t;
+        let distance = local.distance(closest);
+        if nearest.is_none_or(|(nearest, ..)| distance < nearest) {
+            nearest = Some((distance, closest, outward));
+        }
+    }
+    let (distance, closest, outward) = nearest?;
+
+    if inside {
+        return Some(outward * (distance + radius));
+    }
+    (distance < radius).then(|| (local - closest) / distance * (radius - distance))
+}
+
+/// Ramène le cercle `(position, radius)` dans l'arène de demi-dimensions `half_size`,
+/// centrée sur l'origine.
+pub fn clamp_to_arena(position: Vec2, radius: f32, half_size: Vec2) -> Vec2 {
+    let limit = (half_size - Vec2::splat(radius)).max(Vec2::ZERO);
+    position.clamp(-limit, limit)
+}
+
+/// Sépare deux cercles qui se chevauchent.
+///
+/// - `share_a` : part de la correction appliquée à `a`, entre 0 (seul `b` bouge)
+///   et 1 (seul `a` bouge).
+///
+/// Retourne `true` si les cercles se chevauchaient.
+pub fn separate_circles(
+    a: &mut Vec2,
+    radius_a: f32,
+    b: &mut Vec2,
+    radius_b: f32,
+    share_a: f32,
+) -> bool {
+    let Some(pushout) = circle_circle_pushout(*a, radius_a, *b, radius_b) else {
+        return false;
+    };
+    *a += pushout * share_a;
+    *b -= pushout * (1.0 - share_a);
+    true
+}
+
+/// Étape de collision d'un cercle contre le décor : il est repoussé hors des
+/// colliders `statics` (centre et forme), puis ramené dans l'arène.
+///
+/// Utilisée par le serveur pour chaque joueur, elle peut l'être telle quelle par
+/// la prédiction du client pour son joueur.
+pub fn resolve_static_collisions(
+    position: Vec2,
+    radius: f32,
+    statics: &[(Vec2, &ColliderShape)],
+    arena_half_size: Vec2,
+) -> Vec2 {
+    let mut position = clamp_to_arena(position, radius, arena_half_size);
+    for _ in 0..STATIC_COLLISION_PASSES {
+        let mut moved = false;
+        for (center, shape) in statics {
+            if let Some(pushout) = shape.circle_pushout(*center, position, radius) {
+                position += pushout;
+                moved = true;
+            }
+        }
+        position = clamp_to_arena(position, radius, arena_half_size);
+        if !moved {
+            break;
+        }
+    }
+    position
+}
+
+/// Aire signée d'un polygone : positive si ses sommets tournent dans le sens trigonométrique.
+fn signed_area(points: &[Vec2]) -> f32 {
+    (0..points.len())
+        .map(|i| points[i].perp_dot(points[(i + 1) % points.len()]))
+        .sum::<f32>()
+        / 2.0
+}
+
+fn sign_or_one(value: f32) -> f32 {
+    if value < 0.0 {
+        -1.0
+    } else {
+        1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RADIUS: f32 = 40.0;
+
+    fn assert_close(actual: Vec2, expected: Vec2) {
+        assert!(
+            actual.distance(expected) < 1e-3,
+            "expected {expected}, got {actual}"
+        );
+    }
+
+    #[test]
+    fn overlapping_circles_are_pushed_apart() {
+        let (mut a, mut b) = (Vec2::new(-30.0, 0.0), Vec2::new(30.0, 0.0));
+
+        assert!(separate_circles(&mut a, RADIUS, &mut b, RADIUS, 0.5));
+
+        assert_close(a, Vec2::new(-40.0, 0.0));
+        assert_close(b, Vec2::new(40.0, 0.0));
+        assert!(!separate_circles(&mut a, RADIUS, &mut b, RADIUS, 0.5));
+    }
+
+    #[test]
+    fn coincident_circles_separate_along_x() {
+        let pushout = circle_circle_pushout(Vec2::ZERO, RADIUS, Vec2::ZERO, RADIUS);
+
+        assert_eq!(pushout, Some(Vec2::new(2.0 * RADIUS, 0.0)));
+    }
+
+    #[test]
+    fn circle_is_pushed_out_of_a_box() {
+        let half_size = Vec2::new(100.0, 20.0);
+
+        // Chevauchement par le côté, puis par un coin.
+        let pushout = circle_aabb_pushout(Vec2::new(0.0, 50.0), RADIUS, Vec2::ZERO, half_size);
+        assert_close(pushout.unwrap(), Vec2::new(0.0, 10.0));
+        let corner = Vec2::new(100.0, 20.0) + Vec2::splat(20.0);
+        let pushout = circle_aabb_pushout(corner, RADIUS, Vec2::ZERO, half_size).unwrap();
+        assert!((corner + pushout).distance(Vec2::new(100.0, 20.0)) - RADIUS < 1e-3);
+
+        // Centre dans le rectangle : sortie par le côté le plus proche.
+        let pushout = circle_aabb_pushout(Vec2::new(50.0, -15.0), RADIUS, Vec2::ZERO, half_size);
+        assert_close(pushout.unwrap(), Vec2::new(0.0, -45.0));
+
+        assert_eq!(
+            circle_aabb_pushout(Vec2::new(0.0, 61.0), RADIUS, Vec2::ZERO, half_size),
+            None
+        );
+    }
+
+    #[test]
+    fn circle_is_pushed_out_of_a_polygon() {
+        let shape = ColliderShape::polygon(vec![
+            Vec2::new(-100.0, -100.0),
+            Vec2::new(100.0, 100.0),
+            Vec2::new(100.0, -100.0),
+        ])
+        .expect("a convex triangle");
+
+        // Au-dessus de l'hypoténuse y = x, à 10 unités d'elle.
+        let circle = Vec2::new(0.0, 10.0 * std::f32::consts::SQRT_2);
+        let pushout = shape.circle_pushout(Vec2::ZERO, circle, RADIUS).unwrap();
+        assert_close(pushout, Vec2::new(-1.0, 1.0).normalize() * 30.0);
+
+        // Centre dans le triangle, près de son côté droit.
+        let pushout = shape
+            .circle_pushout(Vec2::ZERO, Vec2::new(90.0, -50.0), RADIUS)
+            .unwrap();
+        assert_close(pushout, Vec2::new(50.0, 0.0));
+
+        assert_eq!(
+            shape.circle_pushout(Vec2::ZERO, Vec2::new(-100.0, 100.0), RADIUS),
+            None
+        );
+    }
+
+    #[test]
+    fn concave_polygon_is_rejected() {
+        let arrow = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(100.0, 50.0),
+            Vec2::new(0.0, 100.0),
+            Vec2::new(30.0, 50.0),
+        ];
+
+        assert_eq!(ColliderShape::polygon(arrow), None);
+        assert_eq!(ColliderShape::polygon(vec![Vec2::ZERO, Vec2::X]), None);
+    }
+
+    #[test]
+    fn circle_is_pushed_out_of_a_rotated_body() {
+        let shape = ColliderShape::Aabb {
+            half_size: Vec2::new(100.0, 20.0),
+        };
+        let center = Vec2::new(50.0, 0.0);
+        let rotation = std::f32::consts::FRAC_PI_2;
+
+        // Tourné d'un quart de tour, le rectangle s'étend le long de l'axe Y.
+        let position =
+            push_out_of_bodies(Vec2::new(60.0, 80.0), RADIUS, &[(center, rotation, &shape)]);
+        assert_close(position, Vec2::new(110.0, 80.0));
+
+        let free = Vec2::new(50.0, 150.0);
+        assert_close(
+            push_out_of_bodies(free, RADIUS, &[(center, rotation, &shape)]),
+            free,
+        );
+        assert_close(
+            push_out_of_bodies(Vec2::new(50.0, 50.0), RADIUS, &[(center, 0.0, &shape)]),
+            Vec2::new(50.0, 60.0),
+        );
+    }
+
+    #[test]
+    fn static_resolution_keeps_circle_in_arena_and_out_of_walls() {
+        let half_size = Vec2::new(500.0, 300.0);
+        let wall = ColliderShape::Aabb {
+            half_size: Vec2::new(20.0, 300.0),
+        };
+        // Le couloir entre le mur et le bord droit de l'arène a la largeur d'un joueur.
+        let statics = [(Vec2::new(400.0, 0.0), &wall)];
+
+        let position =
+            resolve_static_collisions(Vec2::new(430.0, 290.0), RADIUS, &statics, half_size);
+
+        assert_close(position, Vec2::new(460.0, 260.0));
+        assert!(position.x.abs() <= half_size.x - RADIUS + 1e-3);
+        assert!(position.y.abs() <= half_size.y - RADIUS + 1e-3);
+        assert_eq!(
+            wall.circle_pushout(Vec2::new(400.0, 0.0), position, RADIUS),
+            None
+        );
+    }
+}
@@ -0,0 +1,239 @@
+use bevy::ecs::entity::EntityMapper;
+use bevy::prelude::{Entity, Resource};
+use std::collections::HashMap;
+
+/// Correspondance bidirectionnelle entre les entités du serveur et celles du client.
+///
+/// Chaque entité répliquée par le serveur est recréée localement par le client sous
+/// un autre identifiant. Cette table permet de retrouver l'entité locale à partir de
+/// l'entité serveur (pour appliquer un message reçu) et inversement (pour désigner
+/// une entité dans un message envoyé au serveur).
+///
+/// Implémente `EntityMapper` : les identifiants serveur contenus dans un message
+/// implémentant `MapEntities` sont remplacés par les entités locales via
+/// `message.map_entities(&mut entity_map)`. Une entité serveur inconnue est remplacée
+/// par `Entity::PLACEHOLDER`.
+#[derive(Debug, Default, Resource)]
+pub struct NetworkEntityMap {
+    /// Entité serveur -> entité client.
+    server_to_client: HashMap<Entity, Entity>,
+    /// Entité client -> entité serveur.
+    client_to_server: HashMap<Entity, Entity>,
+    /// Index d'entité serveur -> entité serveur, pour détecter la réutilisation d'index.
+    server_by_index: HashMap<u32, Entity>,
+}
+
+impl NetworkEntityMap {
+    /// Associe une entité serveur à une entité client.
+    ///
+    /// Les associations existantes de l'une ou l'autre entité sont remplacées. Si le
+    /// serveur a réutilisé l'index d'une entité disparue (génération différente),
+    /// l'ancienne association est retirée : l'entité serveur correspondante n'existe
+    /// plus.
+    ///
+    /// - `server_entity` : entité côté serveur.
+    /// - `client_entity` : entité correspondante côté client.
+    /// - Retourne l'entité client devenue orpheline, que l'appelant doit despawn.
+    pub fn insert(&mut self, server_entity: Entity, client_entity: Entity) -> Option<Entity> {
+        let orphan = self
+            .server_by_index
+            .get(&server_entity.index())
+            .copied()
+            .and_then(|stale| self.remove_by_server(&stale))
+            .filter(|orphan| *orphan != client_entity);
+        self.remove_by_client(&client_entity);
+
+        self.server_to_client.insert(server_entity, client_entity);
+        self.client_to_server.insert(client_entity, server_entity);
+        self.server_by_index
+            .insert(server_entity.index(), server_entity);
+        orphan
+    }
+
+    /// Récupère l'entité client associée à une entité serveur.
+    pub fn client_entity(&self, server_entity: &Entity) -> Option<Entity> {
+        self.server_to_client.get(server_entity).copied()
+    }
+
+    /// Récupère l'entité serveur associée à une entité client.
+    pub fn server_entity(&self, client_entity: &Entity) -> Option<Entity> {
+        self.client_to_server.get(client_entity).copied()
+    }
+
+    /// Supprime l'association d'une entité serveur.
+    ///
+    /// - Retourne l'entité client qui lui était associée, si trouvée.
+    pub fn remove_by_server(&mut self, server_entity: &Entity) -> Option<Entity> {
+        let client_entity = self.server_to_client.remove(server_entity)?;
+        self.client_to_server.remove(&client_entity);
+        self.server_by_index.remove(&server_entity.index());
+        Some(client_entity)
+    }
+
+    /// Supprime l'association d'une entité client.
+    ///
+    /// - Retourne l'entité serveur qui lui était associée, si trouvée.
+    pub fn remove_by_client(&mut self, client_entity: &Entity) -> Option<Entity> {
+        let server_entity = self.client_to_server.remove(client_entity)?;
+        self.server_to_client.remove(&server_entity);
+        self.server_by_index.remove(&server_entity.index());
+        Some(server_entity)
+    }
+
+    /// Supprime toutes les associations.
+    pub fn clear(&mut self) {
+        self.server_to_client.clear();
+        self.client_to_server.clear();
+        self.server_by_index.clear();
+    }
+
+    /// Nombre d'entités associées.
+    pub fn len(&self) -> usize {
+        self.server_to_client.len()
+    }
+
+    /// Indique si aucune entité n'est associée.
+    pub fn is_empty(&self) -> bool {
+        self.server_to_client.is_empty()
+    }
+
+    /// Itère sur les couples `(entité serveur, entité client)`.
+    pub fn iter(&self) -> impl Iterator<Item = (Entity, Entity)> + '_ {
+        self.server_to_client
+            .iter()
+            .map(|(server_entity, client_entity)| (*server_entity, *client_entity))
+    }
+}
+
+impl EntityMapper for NetworkEntityMap {
+    fn get_mapped(&mut self, source: Entity) -> Entity {
+        self.client_entity(&source).unwrap_or(Entity::PLACEHOLDER)
+    }
+
+    fn set_mapped(&mut self, source: Entity, target: Entity) {
+        self.insert(source, target);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::PlayerInfo;
+    use crate::server::ServerMessages;
+    use bevy::ecs::entity::MapEntities;
+    use bevy::prelude::{Vec3, World};
+
+    #[test]
+    fn spawn_maps_both_directions() {
+        let mut world = World::new();
+        let server_entity = world.spawn_empty().id();
+        let client_entity = world.spawn_empty().id();
+        let mut map = NetworkEntityMap::default();
+
+        assert_eq!(map.insert(server_entity, client_entity), None);
+
+        assert_eq!(map.client_entity(&server_entity), Some(client_entity));
+        assert_eq!(map.server_entity(&client_entity), Some(server_entity));
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn despawn_removes_both_directions() {
+        let mut world = World::new();
+        let server_entity = world.spawn_empty().id();
+        let client_entity = world.spawn_empty().id();
+        let mut map = NetworkEntityMap::default();
+        map.insert(server_entity, client_entity);
+
+        assert_eq!(map.remove_by_server(&server_entity), Some(client_entity));
+        assert_eq!(map.client_entity(&server_entity), None);
+        assert_eq!(map.server_entity(&client_entity), None);
+        assert!(map.is_empty());
+
+        map.insert(server_entity, client_entity);
+        assert_eq!(map.remove_by_client(&client_entity), Some(server_entity));
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn reused_server_index_evicts_stale_mapping() {
+        let mut server_world = World::new();
+        let mut client_world = World::new();
+        let old_server_entity = server_world.spawn_empty().id();
+        let old_client_entity = client_world.spawn_empty().id();
+        let mut map = NetworkEntityMap::default();
+        map.insert(old_server_entity, old_client_entity);
+
+        // Le despawn n'a pas été reçu : le serveur réutilise l'index avec une nouvelle génération.
+        server_world.despawn(old_server_entity);
+        let new_server_entity = server_world.spawn_empty().id();
+        assert_eq!(new_server_entity.index(), old_server_entity.index());
+        assert_ne!(new_server_entity, old_server_entity);
+
+        let new_client_entity = client_world.spawn_empty().id();
+        let orphan = map.insert(new_server_entity, new_client_entity);
+
+        assert_eq!(orphan, Some(old_client_entity));
+        assert_eq!(map.client_entity(&old_server_entity), None);
+        assert_eq!(map.server_entity(&old_client_entity), None);
+        assert_eq!(
+            map.client_entity(&new_server_entity),
+            Some(new_client_entity)
+        );
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn remapping_a_client_entity_replaces_previous_server_entity() {
+        let mut world = World::new();
+        let first_server_entity = world.spawn_empty().id();
+        let second_server_entity = world.spawn_empty().id();
+        let client_entity = world.spawn_empty().id();
+        let mut map = NetworkEntityMap::default();
+
+        map.insert(first_server_entity, client_entity);
+        map.insert(second_server_entity, client_entity);
+
+        assert_eq!(map.client_entity(&first_server_entity), None);
+        assert_eq!(
+            map.server_entity(&client_entity),
+            Some(second_server_entity)
+        );
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn server_messages_are_remapped_to_client_entities() {
+        let mut world = World::new();
+        let server_entity = world.spawn_empty().id();
+        let client_entity = world.spawn_empty().id();
+        let unknown_entity = world.spawn_empty().id();
+        let mut map = NetworkEntityMap::default();
+        map.insert(server_entity, client_entity);
+
+        let player_create = |entity| ServerMessages::PlayerCreate {
+            client_id: 1,
+            position: Vec3::ZERO,
+            entity,
+            info: PlayerInfo {
+                id: 1,
+                name: "Player".to_string(),
+                color: [0, 0, 0],
+            },
+        };
+
+        let mut message = player_create(server_entity);
+        message.map_entities(&mut map);
+        assert!(matches!(
+            message,
+            ServerMessages::PlayerCreate { entity, .. } if entity == client_entity
+        ));
+
+        let mut message = player_create(unknown_entity);
+        message.map_entities(&mut map);
+        assert!(matches!(
+            message,
+            ServerMessages::PlayerCreate { entity, .. } if entity == Entity::PLACEHOLDER
+        ));
+    }
+}
@@ -1,2 +1,3 @@
+pub mod client_event;
 pub mod game_event;
 pub mod server_event;
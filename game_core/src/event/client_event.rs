@@ -0,0 +1,13 @@
+use crate::server::ServerMessages;
+use bevy::prelude::Message;
+
+/// Message du serveur à traiter par le client.
+///
+/// Émis par la réception réseau du client, ou par la lecture d'un replay : le
+/// traitement des messages ne dépend ainsi pas de leur provenance.
+///
+/// - `message` : message décodé, dont les entités sont encore celles du serveur.
+#[derive(Message, Debug, Clone)]
+pub struct ServerMessageReceived {
+    pub message: ServerMessages,
+}
@@ -1,3 +1,4 @@
+use crate::player::PlayerInfo;
 use bevy::math::Vec3;
 use bevy::prelude::{Entity, Message};
 use bevy_renet::renet::ClientId;
@@ -6,20 +7,55 @@ use bevy_renet::renet::ClientId;
 /// Énumération des événements de jeu envoyés via le système de messages.
 ///
 /// - `PlayerCreated` : déclenché lorsqu'un joueur est créé sur le serveur.
+/// - `PlayerReconnected` : déclenché lorsqu'un joueur récupère son entité après une déconnexion.
 /// - `PlayerRemoved` : déclenché lorsqu'un joueur est retiré (déconnexion/suppression).
+/// - `PlayerQueued` : déclenché lorsqu'un client attend une place dans la partie.
+/// - `SpectatorJoined` : déclenché lorsqu'un client regarde la partie sans joueur.
+/// - `PlayerKilled` : déclenché lorsqu'un projectile retire ses derniers points de vie à un joueur.
 pub enum GameEvent {
     /// Un nouveau joueur a été créé.
     ///
     /// * `client_id` : identifiant du client associé au joueur.
     /// * `entity` : entité Bevy représentant le joueur.
     /// * `position` : position initiale du joueur dans le monde.
+    /// * `info` : nom et couleur du joueur.
     PlayerCreated {
         client_id: ClientId,
         entity: Entity,
         position: Vec3,
+        info: PlayerInfo,
+    },
+    /// Un joueur déconnecté s'est reconnecté pendant le délai de grâce.
+    ///
+    /// * `client_id` : identifiant du client associé au joueur.
+    /// * `entity` : entité Bevy conservée pour le joueur.
+    /// * `position` : position actuelle du joueur dans le monde.
+    PlayerReconnected {
+        client_id: ClientId,
+        entity: Entity,
+        position: Vec3,
     },
     /// Un joueur a été retiré.
     ///
     /// * `client_id` : identifiant du client retiré.
     PlayerRemoved { client_id: ClientId },
+    /// Un client attend qu'une place se libère dans la partie.
+    ///
+    /// * `client_id` : identifiant du client en attente.
+    /// * `position` : position du client dans la file, à partir de 1.
+    /// * `total` : nombre de clients dans la file.
+    PlayerQueued {
+        client_id: ClientId,
+        position: usize,
+        total: usize,
+    },
+    /// Un client regarde la partie en spectateur.
+    ///
+    /// * `client_id` : identifiant du spectateur.
+    SpectatorJoined { client_id: ClientId },
+    /// Un joueur a perdu tous ses points de vie et réapparaît.
+    ///
+    /// * `victim` : identifiant du joueur touché.
+    /// * `killer` : identifiant du tireur.
+    PlayerKilled { victim: ClientId, killer: ClientId },
 }
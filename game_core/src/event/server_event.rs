@@ -0,0 +1,77 @@
+use crate::client::{ClientMessages, PlayerInput};
+use crate::server::DisconnectReason;
+use bevy::math::Vec2;
+use bevy::prelude::Message;
+use bevy_renet::renet::ClientId;
+use std::time::Duration;
+
+/// Demande d'arrêt propre du serveur.
+///
+/// Peut être émise par un signal système (Ctrl+C, SIGTERM) ou par une commande
+/// d'administration. Le serveur prévient alors les clients, vide ses canaux fiables,
+/// déconnecte tout le monde puis s'arrête.
+///
+/// - `reason` : raison de l'arrêt transmise aux clients.
+/// - `restart_eta` : délai estimé avant le redémarrage, s'il est prévu.
+#[derive(Message, Debug, Clone)]
+pub struct ShutdownRequest {
+    pub reason: String,
+    pub restart_eta: Option<Duration>,
+}
+
+/// Demande aux systèmes concernés d'écrire leur état persistant sur disque.
+///
+/// Émise notamment juste avant l'arrêt du serveur.
+#[derive(Message, Debug, Clone, Copy, Default)]
+pub struct PersistState;
+
+/// Demande de déconnexion d'un client pour une raison de jeu.
+///
+/// Le serveur envoie d'abord la raison au client sur le canal fiable, puis coupe
+/// la connexion une fois le message acquitté (ou le délai écoulé).
+///
+/// - `client_id` : identifiant du client à déconnecter.
+/// - `reason` : raison transmise au client.
+#[derive(Message, Debug, Clone)]
+pub struct DisconnectClient {
+    pub client_id: ClientId,
+    pub reason: DisconnectReason,
+}
+
+/// Message reçu d'un client sur le canal `ClientChannel::Command`.
+///
+/// Émis par le système de réception centralisé du serveur, après application des
+/// limites de débit et désérialisation.
+///
+/// - `client_id` : identifiant du client émetteur.
+/// - `message` : message décodé.
+#[derive(Message, Debug, Clone)]
+pub struct ClientMessageReceived {
+    pub client_id: ClientId,
+    pub message: ClientMessages,
+}
+
+/// Entrée de déplacement reçue d'un client sur le canal `ClientChannel::Input`.
+///
+/// Émise après application des limites de débit et désérialisation ; l'entrée
+/// doit encore être validée avant d'être appliquée au joueur.
+///
+/// - `client_id` : identifiant du client émetteur.
+/// - `input` : entrée décodée.
+#[derive(Message, Debug, Clone, Copy)]
+pub struct PlayerInputReceived {
+    pub client_id: ClientId,
+    pub input: PlayerInput,
+}
+
+/// Tir validé d'un joueur, à transformer en projectile.
+///
+/// - `client_id` : identifiant du tireur.
+/// - `shot_id` : séquence de l'entrée du tir (voir `Projectile::shot_id`).
+/// - `direction` : direction unitaire du tir.
+#[derive(Message, Debug, Clone, Copy)]
+pub struct ShotFired {
+    pub client_id: ClientId,
+    pub shot_id: u32,
+    pub direction: Vec2,
+}
@@ -1,5 +1,13 @@
+pub mod chat;
 pub mod client;
+pub mod collision;
+pub mod entity_map;
 pub mod event;
+pub mod map;
 pub mod network;
+pub mod physics;
 pub mod player;
+pub mod projectile;
+pub mod replay;
+pub mod replication;
 pub mod server;
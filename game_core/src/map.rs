@@ -0,0 +1,185 @@
+use crate::collision::ColliderShape;
+use crate::physics::{BodyKind, PhysicsBody};
+use bevy::asset::Assets;
+use bevy::math::Vec2;
+use bevy::mesh::{Mesh, Mesh2d};
+use bevy::prelude::{
+    Bundle, Color, ColorMaterial, Component, MeshMaterial2d, Name, ResMut, Transform,
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Couleur des murs de la carte.
+pub const WALL_COLOR: Color = Color::srgb(0.35, 0.35, 0.42);
+
+/// Mur de la carte.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Wall {
+    /// Rectangle aligné sur les axes.
+    ///
+    /// - `center` : position `[x, y]` du centre du mur.
+    /// - `size` : largeur et hauteur du mur.
+    Rect { center: [f32; 2], size: [f32; 2] },
+    /// Polygone convexe de sommets `points`, en coordonnées de l'arène.
+    Polygon { points: Vec<[f32; 2]> },
+}
+
+impl Wall {
+    /// Centre du mur : le centre du rectangle ou la moyenne des sommets du polygone.
+    pub fn center(&self) -> Vec2 {
+        match self {
+            Wall::Rect { center, .. } => Vec2::from_array(*center),
+            Wall::Polygon { points } => {
+                points.iter().copied().map(Vec2::from_array).sum::<Vec2>()
+                    / points.len().max(1) as f32
+            }
+        }
+    }
+
+    /// Collider statique du mur, relatif à son `center`, ou `None` si le polygone
+    /// n'est pas convexe.
+    pub fn collider(&self) -> Option<StaticCollider> {
+        let shape = match self {
+            Wall::Rect { size, .. } => ColliderShape::Aabb {
+                half_size: Vec2::from_array(*size) / 2.0,
+            },
+            Wall::Polygon { points } => {
+                let center = self.center();
+                ColliderShape::polygon(
+                    points
+                        .iter()
+                        .map(|point| Vec2::from_array(*point) - center)
+                        .collect(),
+                )?
+            }
+        };
+        Some(StaticCollider { shape })
+    }
+}
+
+/// Caisse de la carte, que les joueurs poussent.
+///
+/// - `center` : position `[x, y]` initiale du centre de la caisse.
+/// - `size` : largeur et hauteur de la caisse.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Crate {
+    pub center: [f32; 2],
+    pub size: [f32; 2],
+}
+
+impl Crate {
+    /// Corps physique dynamique de la caisse.
+    pub fn body(&self) -> PhysicsBody {
+        PhysicsBody {
+            kind: BodyKind::Dynamic,
+            shape: ColliderShape::Aabb {
+                half_size: Vec2::from_array(self.size) / 2.0,
+            },
+        }
+    }
+}
+
+/// Carte d'une partie : une arène rectangulaire centrée sur l'origine, ses murs et
+/// ses caisses.
+///
+/// - `size` : largeur et hauteur de l'arène.
+/// - `walls` : murs statiques de l'arène.
+/// - `crates` : caisses mobiles, simulées seulement par un serveur compilé avec la
+///   feature `physics` (ignorées sinon).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameMap {
+    pub size: [f32; 2],
+    pub walls: Vec<Wall>,
+    #[serde(default)]
+    pub crates: Vec<Crate>,
+}
+
+impl Default for GameMap {
+    /// Arène de 2000 × 1400 avec deux barres horizontales et deux piliers, qui
+    /// laissent libre la ligne d'apparition des joueurs, et deux caisses au-delà
+    /// des barres.
+    fn default() -> Self {
+        Self {
+            size: [2000.0, 1400.0],
+            walls: vec![
+                Wall::Rect {
+                    center: [0.0, 260.0],
+                    size: [600.0, 40.0],
+                },
+                Wall::Rect {
+                    center: [0.0, -260.0],
+                    size: [600.0, 40.0],
+                },
+                Wall::Rect {
+                    center: [-650.0, 0.0],
+                    size: [40.0, 400.0],
+                },
+                Wall::Rect {
+                    center: [650.0, 0.0],
+                    size: [40.0, 400.0],
+                },
+            ],
+            crates: vec![
+                Crate {
+                    center: [0.0, 450.0],
+                    size: [60.0, 60.0],
+                },
+                Crate {
+                    center: [0.0, -450.0],
+                    size: [60.0, 60.0],
+                },
+            ],
+        }
+    }
+}
+
+impl GameMap {
+    /// Demi-dimensions de l'arène.
+    pub fn half_size(&self) -> Vec2 {
+        Vec2::from_array(self.size) / 2.0
+    }
+
+    /// Charge une carte depuis un fichier RON.
+    ///
+    /// # Panique
+    ///
+    /// Panique si le fichier ne peut pas être lu ou interprété, ou si l'un de ses
+    /// murs est un polygone non convexe : le serveur ne démarre pas sur une carte
+    /// différente de celle demandée.
+    pub fn load(path: &Path) -> Self {
+        let content = std::fs::read_to_string(path)
+            .unwrap_or_else(|err| panic!("Failed to read map {}: {err}", path.display()));
+        let map: Self = ron::from_str(&content)
+            .unwrap_or_else(|err| panic!("Invalid map {}: {err}", path.display()));
+        if let Some(wall) = map.walls.iter().find(|wall| wall.collider().is_none()) {
+            panic!("Invalid map {}: {wall:?} is not convex", path.display());
+        }
+        map
+    }
+}
+
+/// Collider statique, centré sur la `Transform` de son entité (voir `ColliderShape`).
+#[derive(Debug, Clone, PartialEq, Component)]
+pub struct StaticCollider {
+    /// Forme du collider.
+    pub shape: ColliderShape,
+}
+
+/// Composants d'un mur : sa forme en `WALL_COLOR`, munie de son `StaticCollider`.
+///
+/// Retourne `None` si le mur est un polygone non convexe.
+pub fn wall_bundle(
+    wall: &Wall,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+) -> Option<impl Bundle> {
+    let collider = wall.collider()?;
+    let mesh = collider.shape.mesh();
+    Some((
+        Name::new("Wall"),
+        collider,
+        Transform::from_translation(wall.center().extend(-1.0)),
+        Mesh2d(meshes.add(mesh)),
+        MeshMaterial2d(materials.add(ColorMaterial::from_color(WALL_COLOR))),
+    ))
+}
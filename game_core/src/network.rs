@@ -1,4 +1,4 @@
-use crate::client::ClientChannel;
+use crate::client::{ClientChannel, ClientMessages, PlayerInput};
 use crate::server::{ServerChannel, ServerMessages};
 use bevy::log::error;
 use bevy_renet::renet::ConnectionConfig;
@@ -6,11 +6,21 @@ use serde::{Deserialize, Serialize};
 use std::net::{SocketAddr, UdpSocket};
 use std::time::SystemTime;
 
-/// Identifiant de protocole utilisé pour vérifier la compatibilité client/serveur.
-/// Incrémentez cette valeur à chaque changement incompatible du protocole réseau.
-/// Si le client et le serveur n'ont pas le même `PROTOCOL_ID', la connexion échoue.
+/// Identifiant netcode du jeu.
+///
+/// Si le client et le serveur n'ont pas le même `PROTOCOL_ID', la poignée de main
+/// échoue sans que le client sache pourquoi : cette valeur ne change donc pas d'une
+/// version à l'autre, la compatibilité est vérifiée par `PROTOCOL_VERSION`.
 pub const PROTOCOL_ID: u64 = 1;
 
+/// Version du protocole réseau, transmise dans les `user_data` de la connexion
+/// (voir `ConnectUserData`).
+///
+/// Incrémentez cette valeur à chaque changement incompatible des messages échangés
+/// ou des composants répliqués : le serveur refuse alors les clients d'une autre
+/// version avec `DisconnectReason::VersionMismatch`.
+pub const PROTOCOL_VERSION: u32 = 4;
+
 #[derive(Debug, Serialize, Deserialize, Default)]
 /// Représente un snapshot des entités synchronisées et leurs positions.
 ///
@@ -111,3 +121,55 @@ pub fn serialize_server_message(message: &ServerMessages) -> Vec<u8> {
         Vec::new()
     })
 }
+
+/// Désérialise un message client encodé en bincode.
+///
+/// # Paramètres
+/// - `message` : tranche d'octets contenant le message sérialisé.
+///
+/// # Retour
+/// - `Option<ClientMessages>` : le message décodé, ou `None` si le message est invalide
+///   (l'erreur est journalisée via `bevy::log::error`).
+pub fn deserialize_client_message(message: &[u8]) -> Option<ClientMessages> {
+    bincode::serde::decode_from_slice(message, bincode::config::standard())
+        .map(|(message, _)| message)
+        .map_err(|err| error!("Client message deserialization error: {:?}", err))
+        .ok()
+}
+
+/// Sérialise un `ClientMessages` en `Vec<u8>` au format bincode.
+///
+/// # Paramètres
+/// - `message` : référence vers le message client à sérialiser.
+///
+/// # Retour
+/// - `Vec<u8>` : octets sérialisés. En cas d'échec, la fonction journalise l'erreur
+///   via `bevy::log::error` et retourne un vecteur vide.
+pub fn serialize_client_message(message: &ClientMessages) -> Vec<u8> {
+    bincode::serde::encode_to_vec(message, bincode::config::standard()).unwrap_or_else(|err| {
+        error!("Serialization error: {:?}", err);
+        Vec::new()
+    })
+}
+
+/// Désérialise une `PlayerInput` encodée en bincode.
+///
+/// # Retour
+/// - `Option<PlayerInput>` : l'entrée décodée, ou `None` si elle est invalide
+///   (l'erreur est journalisée via `bevy::log::error`).
+pub fn deserialize_player_input(input: &[u8]) -> Option<PlayerInput> {
+    bincode::serde::decode_from_slice(input, bincode::config::standard())
+        .map(|(input, _)| input)
+        .map_err(|err| error!("Player input deserialization error: {:?}", err))
+        .ok()
+}
+
+/// Sérialise une `PlayerInput` en `Vec<u8>` au format bincode.
+///
+/// En cas d'échec, l'erreur est journalisée et un vecteur vide est retourné.
+pub fn serialize_player_input(input: &PlayerInput) -> Vec<u8> {
+    bincode::serde::encode_to_vec(input, bincode::config::standard()).unwrap_or_else(|err| {
+        error!("Serialization error: {:?}", err);
+        Vec::new()
+    })
+}
@@ -0,0 +1,103 @@
+use crate::collision::ColliderShape;
+use crate::map::WALL_COLOR;
+use bevy::asset::Assets;
+use bevy::math::Vec2;
+use bevy::mesh::{Mesh, Mesh2d};
+use bevy::prelude::{
+    Bundle, Color, ColorMaterial, Component, MeshMaterial2d, Name, ResMut, Transform,
+};
+use serde::{Deserialize, Serialize};
+
+/// Couleur des corps physiques dynamiques.
+pub const DYNAMIC_BODY_COLOR: Color = Color::srgb(0.62, 0.45, 0.26);
+
+/// Type d'un corps physique.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BodyKind {
+    /// Corps simulé par le serveur : poussé par les joueurs et les impulsions.
+    Dynamic,
+    /// Corps immobile.
+    Fixed,
+}
+
+/// Corps physique répliqué : son type et sa forme, centrée sur la `Transform` de son
+/// entité.
+///
+/// Le serveur le simule lorsqu'il est compilé avec la feature `physics` ; sa
+/// position, sa rotation (`Transform`) et sa `Velocity` sont répliquées.
+#[derive(Debug, Clone, PartialEq, Component, Serialize, Deserialize)]
+pub struct PhysicsBody {
+    pub kind: BodyKind,
+    pub shape: ColliderShape,
+}
+
+impl PhysicsBody {
+    /// Nom de l'entité du corps.
+    pub fn name(&self) -> &'static str {
+        match self.kind {
+            BodyKind::Dynamic => "Dynamic body",
+            BodyKind::Fixed => "Fixed body",
+        }
+    }
+}
+
+/// Vitesse d'un corps physique.
+///
+/// - `linear` : vitesse linéaire, en unités par seconde.
+/// - `angular` : vitesse angulaire autour de l'axe Z, en radians par seconde.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Component, Serialize, Deserialize)]
+pub struct Velocity {
+    pub linear: Vec2,
+    pub angular: f32,
+}
+
+impl Velocity {
+    /// Déplace `transform` à vitesse constante pendant `delta_secs` secondes.
+    ///
+    /// Le client fait ainsi avancer les corps distants entre deux états reçus du
+    /// serveur, comme des corps cinématiques.
+    pub fn integrate(&self, transform: &mut Transform, delta_secs: f32) {
+        transform.translation += (self.linear * delta_secs).extend(0.0);
+        transform.rotate_z(self.angular * delta_secs);
+    }
+}
+
+/// Composants d'affichage d'un corps physique : sa forme, aux couleurs de son type.
+pub fn body_visual_bundle(
+    body: &PhysicsBody,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+) -> impl Bundle {
+    let color = match body.kind {
+        BodyKind::Dynamic => DYNAMIC_BODY_COLOR,
+        BodyKind::Fixed => WALL_COLOR,
+    };
+    (
+        Name::new(body.name()),
+        Mesh2d(meshes.add(body.shape.mesh())),
+        MeshMaterial2d(materials.add(ColorMaterial::from_color(color))),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_2;
+
+    #[test]
+    fn integrate_moves_and_rotates() {
+        let mut transform = Transform::from_xyz(10.0, 0.0, -1.0);
+        let velocity = Velocity {
+            linear: Vec2::new(100.0, -50.0),
+            angular: FRAC_PI_2,
+        };
+
+        velocity.integrate(&mut transform, 0.5);
+
+        assert_eq!(transform.translation.x, 60.0);
+        assert_eq!(transform.translation.y, -25.0);
+        assert_eq!(transform.translation.z, -1.0);
+        let (_, angle) = transform.rotation.to_axis_angle();
+        assert!((angle - FRAC_PI_2 / 2.0).abs() < 1e-5);
+    }
+}
@@ -1,21 +1,146 @@
 use bevy::asset::Assets;
-use bevy::math::Vec3;
+use bevy::math::{Vec2, Vec3};
 use bevy::mesh::{Mesh, Mesh2d};
 use bevy::prelude::{
-    Circle, ColorMaterial, Commands, Component, Entity, MeshMaterial2d, Name, ResMut, Transform,
+    Bundle, Circle, Color, ColorMaterial, Commands, Component, Entity, MeshMaterial2d, Name,
+    ResMut, Transform,
 };
 use bevy_renet::renet::ClientId;
+use serde::{Deserialize, Serialize};
+
+/// Longueur maximale (en caractères) d'un nom de joueur.
+pub const MAX_PLAYER_NAME_LEN: usize = 16;
+
+/// Rayon du cercle représentant un joueur.
+pub const PLAYER_RADIUS: f32 = 40.0;
+
+/// Vitesse de déplacement d'un joueur, en unités par seconde.
+pub const PLAYER_SPEED: f32 = 300.0;
+
+/// Points de vie d'un joueur à son apparition.
+pub const PLAYER_MAX_HEALTH: u32 = 100;
+
+/// Palette de couleurs attribuées par le serveur aux joueurs n'en ayant pas choisi.
+pub const PLAYER_PALETTE: [[u8; 3]; 8] = [
+    [231, 76, 60],
+    [52, 152, 219],
+    [46, 204, 113],
+    [241, 196, 15],
+    [155, 89, 182],
+    [230, 126, 34],
+    [26, 188, 156],
+    [236, 112, 160],
+];
 
 /// Représente un joueur connecté au serveur.
 ///
-/// Contient l'identifiant réseau fourni par `bevy_renet` et le nom affiché.
-#[derive(Debug, Component)]
+/// Contient l'identifiant réseau fourni par `bevy_renet`, le nom affiché et la
+/// couleur du joueur. Attaché à l'entité du joueur par le serveur et
+/// répliqué aux clients via `ServerMessages::PlayerCreate`.
+#[derive(Debug, Clone, Component, Serialize, Deserialize)]
 pub struct PlayerInfo {
     /// Identifiant unique du client (fourni par `bevy_renet').
     pub id: ClientId,
     /// Nom affiché du joueur.
     pub name: String,
+    /// Couleur `[r, g, b]` du joueur, choisie par lui ou attribuée par le serveur.
+    pub color: [u8; 3],
+}
+
+impl PlayerInfo {
+    /// Couleur du joueur convertie en `Color` Bevy.
+    pub fn bevy_color(&self) -> Color {
+        let [r, g, b] = self.color;
+        Color::srgb_u8(r, g, b)
+    }
 }
+
+/// Couleur attribuée par défaut à un joueur, choisie dans `PLAYER_PALETTE`
+/// à partir de son identifiant.
+pub fn default_player_color(client_id: &ClientId) -> [u8; 3] {
+    PLAYER_PALETTE[(*client_id % PLAYER_PALETTE.len() as u64) as usize]
+}
+
+/// Nettoie un nom de joueur, éventuellement vide.
+///
+/// - Supprime les caractères de contrôle et de format (voir `is_format_char`), puis
+///   normalise les espaces.
+/// - Tronque le nom à `MAX_PLAYER_NAME_LEN` caractères.
+///
+/// Appliqué par le client avant l'envoi, pour que le nom tienne dans les `user_data`
+/// de la connexion, puis par le serveur via `sanitize_player_name`.
+pub fn clean_player_name(raw: &str) -> String {
+    let name = raw
+        .split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| !c.is_control() && !is_format_char(*c))
+                .collect::<String>()
+        })
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let name: String = name.chars().take(MAX_PLAYER_NAME_LEN).collect();
+    name.trim_end().to_string()
+}
+
+/// Indique si un caractère appartient à la catégorie Unicode des caractères de
+/// format (`Cf`) : espaces de largeur nulle, marques et surcharges bidirectionnelles,
+/// etc.
+///
+/// Invisibles, ils permettent d'usurper le nom d'un autre joueur ou d'inverser
+/// l'affichage du texte qui les suit.
+fn is_format_char(c: char) -> bool {
+    matches!(
+        c,
+        '\u{AD}'
+            | '\u{600}'..='\u{605}'
+            | '\u{61C}'
+            | '\u{6DD}'
+            | '\u{70F}'
+            | '\u{890}'..='\u{891}'
+            | '\u{8E2}'
+            | '\u{180E}'
+            | '\u{200B}'..='\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{2064}'
+            | '\u{2066}'..='\u{206F}'
+            | '\u{FEFF}'
+            | '\u{FFF9}'..='\u{FFFB}'
+            | '\u{110BD}'
+            | '\u{110CD}'
+            | '\u{13430}'..='\u{1343F}'
+            | '\u{1BCA0}'..='\u{1BCA3}'
+            | '\u{1D173}'..='\u{1D17A}'
+            | '\u{E0001}'
+            | '\u{E0020}'..='\u{E007F}'
+    )
+}
+
+/// Nettoie un nom de joueur reçu du réseau (voir `clean_player_name`).
+///
+/// Retourne `Player_<client_id>` si le nom obtenu est vide.
+pub fn sanitize_player_name(raw: &str, client_id: &ClientId) -> String {
+    let name = clean_player_name(raw);
+
+    if name.is_empty() {
+        format!("Player_{client_id}")
+    } else {
+        name
+    }
+}
+
+/// Nombre d'équipes entre lesquelles le serveur répartit les joueurs.
+pub const TEAM_COUNT: u8 = 2;
+
+/// Équipe d'un joueur (de `0` à `TEAM_COUNT - 1`), utilisée notamment pour le chat
+/// d'équipe.
+///
+/// Attribuée par le serveur à la création du joueur, dans l'équipe la moins
+/// nombreuse. Un joueur sans équipe ne reçoit que ses propres messages d'équipe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component, Serialize, Deserialize)]
+pub struct Team(pub u8);
+
 /// Marque une entité comme contrôlée par le joueur local.
 ///
 /// Utilisé pour identifier l'entité du joueur que le client local contrôle
@@ -24,19 +149,165 @@ pub struct PlayerInfo {
 #[derive(Component)]
 pub struct ControlledPlayer;
 
+/// Points de vie d'un joueur, retirés par les projectiles ; répliqués aux clients.
+///
+/// Un joueur qui n'en a plus réapparaît avec `PLAYER_MAX_HEALTH` points de vie.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component, Serialize, Deserialize)]
+pub struct Health(pub u32);
+
+impl Default for Health {
+    fn default() -> Self {
+        Self(PLAYER_MAX_HEALTH)
+    }
+}
+
+impl Health {
+    /// Retire `amount` points de vie, sans descendre sous 0.
+    ///
+    /// Retourne `true` si le joueur n'a plus de points de vie.
+    pub fn damage(&mut self, amount: u32) -> bool {
+        self.0 = self.0.saturating_sub(amount);
+        self.0 == 0
+    }
+
+    /// Part des points de vie restants, entre 0 et 1.
+    pub fn fraction(&self) -> f32 {
+        (self.0 as f32 / PLAYER_MAX_HEALTH as f32).clamp(0.0, 1.0)
+    }
+}
+
+/// Direction de déplacement courante d'un joueur, de norme au plus 1.
+///
+/// Mise à jour par le serveur à partir des dernières entrées validées du joueur
+/// (voir `PlayerInput`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Component)]
+pub struct MoveDirection(pub Vec2);
+
+/// Ramène une direction de déplacement reçue du réseau dans les bornes légales.
+///
+/// - Retourne `None` si l'une des composantes n'est pas finie (`NaN`, infini).
+/// - Sinon, retourne la direction dont la norme est limitée à 1.
+pub fn clamp_move_direction(direction: [f32; 2]) -> Option<Vec2> {
+    let direction = Vec2::from_array(direction);
+    direction
+        .is_finite()
+        .then(|| direction.clamp_length_max(1.0))
+}
+
+/// Déplace un joueur dans `direction` à `PLAYER_SPEED` pendant `delta_secs` secondes.
+pub fn move_player(transform: &mut Transform, direction: Vec2, delta_secs: f32) {
+    transform.translation += (direction * PLAYER_SPEED * delta_secs).extend(0.0);
+}
+
+/// Composants d'un joueur : un cercle de rayon `PLAYER_RADIUS` à sa couleur.
+///
+/// Permet d'équiper une entité existante, par exemple une entité client déjà
+/// enregistrée dans la `NetworkEntityMap`.
+pub fn player_bundle(
+    info: PlayerInfo,
+    position: Vec3,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+) -> impl Bundle {
+    let color = info.bevy_color();
+    (
+        Name::new(info.name.clone()),
+        info,
+        Transform::from_translation(position),
+        Mesh2d(meshes.add(Mesh::from(Circle::new(PLAYER_RADIUS)))),
+        MeshMaterial2d(materials.add(ColorMaterial::from_color(color))),
+    )
+}
+
+/// Crée l'entité d'un joueur (voir `player_bundle`).
 pub fn spawn_player(
-    client_id: &ClientId,
+    info: PlayerInfo,
     position: Vec3,
     commands: &mut Commands,
     meshes: &mut ResMut<Assets<Mesh>>,
     materials: &mut ResMut<Assets<ColorMaterial>>,
 ) -> Entity {
     commands
-        .spawn((
-            Name::new(format!("Player_{client_id}")),
-            Transform::from_translation(position),
-            Mesh2d(meshes.add(Mesh::from(Circle::new(40.0)))),
-            MeshMaterial2d(materials.add(ColorMaterial::default())),
-        ))
+        .spawn(player_bundle(info, position, meshes, materials))
         .id()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn damage_stops_at_zero() {
+        let mut health = Health::default();
+
+        assert!(!health.damage(30));
+        assert_eq!(health, Health(PLAYER_MAX_HEALTH - 30));
+        assert_eq!(health.fraction(), 0.7);
+
+        assert!(health.damage(PLAYER_MAX_HEALTH));
+        assert_eq!(health, Health(0));
+        assert_eq!(health.fraction(), 0.0);
+        assert!(health.damage(1));
+    }
+
+    #[test]
+    fn sanitize_strips_control_characters() {
+        assert_eq!(sanitize_player_name("Al\u{7}ice\u{0}", &1), "Alice");
+        assert_eq!(sanitize_player_name("Bob\u{1b}[31m", &1), "Bob[31m");
+        // Caractères de format : largeur nulle, marques et surcharges bidirectionnelles.
+        assert_eq!(
+            sanitize_player_name("A\u{200B}d\u{200D}m\u{200F}in", &1),
+            "Admin"
+        );
+        assert_eq!(sanitize_player_name("\u{202E}nimda\u{202C}", &1), "nimda");
+        assert_eq!(
+            sanitize_player_name("\u{2066}Eve\u{2069}\u{FEFF}", &1),
+            "Eve"
+        );
+        assert_eq!(sanitize_player_name("\u{200B}\u{2060}", &1), "Player_1");
+    }
+
+    #[test]
+    fn sanitize_normalizes_whitespace() {
+        assert_eq!(
+            sanitize_player_name("  Jean \t\n Dupont ", &1),
+            "Jean Dupont"
+        );
+    }
+
+    #[test]
+    fn sanitize_truncates_long_names() {
+        let name = sanitize_player_name(&"é".repeat(MAX_PLAYER_NAME_LEN + 10), &1);
+        assert_eq!(name.chars().count(), MAX_PLAYER_NAME_LEN);
+
+        let spaced = format!("{} b", "a".repeat(MAX_PLAYER_NAME_LEN - 1));
+        assert_eq!(
+            sanitize_player_name(&spaced, &1),
+            "a".repeat(MAX_PLAYER_NAME_LEN - 1)
+        );
+    }
+
+    #[test]
+    fn sanitize_falls_back_to_default_name() {
+        assert_eq!(sanitize_player_name("", &42), "Player_42");
+        assert_eq!(sanitize_player_name(" \t\n ", &42), "Player_42");
+        assert_eq!(sanitize_player_name("\u{0}\u{7f}", &42), "Player_42");
+    }
+
+    #[test]
+    fn move_direction_is_clamped_to_unit_length() {
+        assert_eq!(clamp_move_direction([0.5, 0.0]), Some(Vec2::new(0.5, 0.0)));
+        assert_eq!(clamp_move_direction([0.0, 0.0]), Some(Vec2::ZERO));
+
+        let clamped = clamp_move_direction([30.0, 40.0]).unwrap();
+        assert!((clamped - Vec2::new(0.6, 0.8)).length() < 1e-6);
+        assert!(clamp_move_direction([f32::MAX, f32::MAX]).unwrap().length() <= 1.0 + 1e-6);
+    }
+
+    #[test]
+    fn non_finite_move_direction_is_rejected() {
+        assert_eq!(clamp_move_direction([f32::NAN, 0.0]), None);
+        assert_eq!(clamp_move_direction([0.0, f32::INFINITY]), None);
+        assert_eq!(clamp_move_direction([f32::NEG_INFINITY, 1.0]), None);
+    }
+}
@@ -0,0 +1,254 @@
+use crate::collision::ColliderShape;
+use crate::physics::Velocity;
+use crate::player::PLAYER_RADIUS;
+use bevy::asset::Assets;
+use bevy::math::{Quat, Vec2};
+use bevy::mesh::{Mesh, Mesh2d};
+use bevy::prelude::{
+    Bundle, Circle, Color, ColorMaterial, Component, Entity, MeshMaterial2d, Name, ResMut,
+    Transform,
+};
+use bevy_renet::renet::ClientId;
+use serde::{Deserialize, Serialize};
+
+/// Vitesse des projectiles, en unités par seconde.
+pub const PROJECTILE_SPEED: f32 = 900.0;
+
+/// Rayon des projectiles.
+pub const PROJECTILE_RADIUS: f32 = 6.0;
+
+/// Durée de vie des projectiles, en secondes.
+pub const PROJECTILE_LIFETIME: f32 = 1.2;
+
+/// Points de vie retirés par un projectile.
+pub const PROJECTILE_DAMAGE: u32 = 20;
+
+/// Vitesse communiquée par un projectile au corps dynamique qu'il touche.
+pub const PROJECTILE_KNOCKBACK: f32 = 250.0;
+
+/// Nombre minimal d'entrées, donc de ticks fixes, entre deux tirs d'un joueur.
+pub const FIRE_COOLDOWN_TICKS: u32 = 16;
+
+/// Couleur des projectiles.
+pub const PROJECTILE_COLOR: Color = Color::srgb(1.0, 0.85, 0.35);
+
+/// Projectile tiré par un joueur, simulé par le serveur et répliqué aux clients.
+///
+/// - `owner` : identifiant du client qui a tiré.
+/// - `shot_id` : séquence de la `PlayerInput` du tir, qui permet au tireur de
+///   reconnaître le projectile qu'il a prédit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component, Serialize, Deserialize)]
+pub struct Projectile {
+    pub owner: ClientId,
+    pub shot_id: u32,
+}
+
+/// Projectile prédit par le client qui l'a tiré, en attendant celui du serveur.
+///
+/// Il rejoint puis laisse la place au projectile répliqué de même
+/// `Projectile::shot_id` (voir `ReconciledProjectile`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Component)]
+pub struct PredictedProjectile;
+
+/// Projectile prédit rattaché au projectile répliqué `server` du même tir.
+///
+/// Le projectile répliqué reste caché pendant que la prédiction, en avance, le
+/// rejoint progressivement ; elle lui cède ensuite la place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub struct ReconciledProjectile {
+    pub server: Entity,
+}
+
+/// Temps restant avant la disparition d'un projectile, en secondes.
+#[derive(Debug, Clone, Copy, PartialEq, Component)]
+pub struct ProjectileLifetime(pub f32);
+
+/// Cadence de tir d'un joueur, mesurée en séquences d'entrées : un tir n'est permis
+/// que `FIRE_COOLDOWN_TICKS` entrées après le précédent.
+///
+/// Le client et le serveur comptent sur les mêmes séquences : un client qui la
+/// respecte n'a jamais de tir refusé, quelle que soit la gigue du réseau.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FireCooldown {
+    last_shot: Option<u32>,
+}
+
+impl FireCooldown {
+    /// Indique si un tir est permis à l'entrée de numéro `sequence`.
+    pub fn is_ready(&self, sequence: u32) -> bool {
+        self.last_shot
+            .is_none_or(|last| sequence.wrapping_sub(last) >= FIRE_COOLDOWN_TICKS)
+    }
+
+    /// Enregistre un tir à l'entrée `sequence`, s'il est permis.
+    ///
+    /// Retourne `false`, sans rien enregistrer, si le tir est trop rapproché du
+    /// précédent.
+    pub fn fire(&mut self, sequence: u32) -> bool {
+        let ready = self.is_ready(sequence);
+        if ready {
+            self.last_shot = Some(sequence);
+        }
+        ready
+    }
+}
+
+/// Ramène une direction de tir reçue du réseau à une direction unitaire.
+///
+/// Retourne `None` si l'une des composantes n'est pas finie ou si la direction est nulle.
+pub fn fire_direction(direction: [f32; 2]) -> Option<Vec2> {
+    Vec2::from_array(direction).try_normalize()
+}
+
+/// Position d'apparition d'un projectile tiré dans `direction` par le joueur
+/// centré en `shooter` : juste devant son cercle.
+pub fn muzzle_position(shooter: Vec2, direction: Vec2) -> Vec2 {
+    shooter + direction * (PLAYER_RADIUS + PROJECTILE_RADIUS)
+}
+
+/// Parcourt le trajet d'un projectile de `start` à `end` par pas d'au plus
+/// `PROJECTILE_RADIUS`, et retourne la première position où `hit` touche quelque
+/// chose, avec ce qui est touché.
+///
+/// Un projectile rapide ne traverse ainsi pas un obstacle mince entre deux ticks.
+pub fn sweep_projectile<T>(
+    start: Vec2,
+    end: Vec2,
+    mut hit: impl FnMut(Vec2) -> Option<T>,
+) -> Option<(Vec2, T)> {
+    let steps = (start.distance(end) / PROJECTILE_RADIUS).ceil().max(1.0) as u32;
+    (1..=steps).find_map(|step| {
+        let position = start.lerp(end, step as f32 / steps as f32);
+        hit(position).map(|target| (position, target))
+    })
+}
+
+/// Indique si le projectile centré en `position` touche la forme `shape` d'une
+/// entité centrée en `center` et tournée de `rotation`.
+pub fn projectile_hits_shape(
+    position: Vec2,
+    center: Vec2,
+    rotation: Quat,
+    shape: &ColliderShape,
+) -> bool {
+    let local = (rotation.inverse() * (position - center).extend(0.0)).truncate();
+    shape
+        .circle_pushout(Vec2::ZERO, local, PROJECTILE_RADIUS)
+        .is_some()
+}
+
+/// Composants d'un projectile tiré depuis `position` dans la direction unitaire
+/// `direction`.
+pub fn projectile_bundle(
+    projectile: Projectile,
+    position: Vec2,
+    direction: Vec2,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+) -> impl Bundle {
+    (
+        projectile_visual_bundle(meshes, materials),
+        projectile,
+        Velocity {
+            linear: direction * PROJECTILE_SPEED,
+            angular: 0.0,
+        },
+        ProjectileLifetime(PROJECTILE_LIFETIME),
+        Transform::from_translation(position.extend(0.5)),
+    )
+}
+
+/// Composants d'affichage d'un projectile : un disque de `PROJECTILE_COLOR`.
+pub fn projectile_visual_bundle(
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+) -> impl Bundle {
+    (
+        Name::new("Projectile"),
+        Mesh2d(meshes.add(Mesh::from(Circle::new(PROJECTILE_RADIUS)))),
+        MeshMaterial2d(materials.add(ColorMaterial::from_color(PROJECTILE_COLOR))),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f32::consts::FRAC_PI_4;
+
+    #[test]
+    fn cooldown_counts_sequences() {
+        let mut cooldown = FireCooldown::default();
+
+        assert!(cooldown.fire(10));
+        assert!(!cooldown.fire(10 + FIRE_COOLDOWN_TICKS - 1));
+        assert!(cooldown.fire(10 + FIRE_COOLDOWN_TICKS));
+        assert!(!cooldown.is_ready(11 + FIRE_COOLDOWN_TICKS));
+    }
+
+    #[test]
+    fn cooldown_survives_sequence_wrap() {
+        let mut cooldown = FireCooldown::default();
+
+        assert!(cooldown.fire(u32::MAX - 2));
+        assert!(!cooldown.fire(3));
+        assert!(cooldown.fire(FIRE_COOLDOWN_TICKS));
+    }
+
+    #[test]
+    fn fire_direction_rejects_degenerate_input() {
+        assert_eq!(fire_direction([3.0, 4.0]), Some(Vec2::new(0.6, 0.8)));
+        assert_eq!(fire_direction([0.0, 0.0]), None);
+        assert_eq!(fire_direction([f32::NAN, 1.0]), None);
+        assert_eq!(fire_direction([f32::INFINITY, 0.0]), None);
+    }
+
+    #[test]
+    fn sweep_stops_at_first_hit() {
+        let wall = |position: Vec2| (position.x >= 50.0).then_some("wall");
+
+        let (position, target) = sweep_projectile(Vec2::ZERO, Vec2::new(100.0, 0.0), wall).unwrap();
+
+        assert_eq!(target, "wall");
+        assert!((50.0..50.0 + PROJECTILE_RADIUS).contains(&position.x));
+        assert!(sweep_projectile(Vec2::ZERO, Vec2::new(40.0, 0.0), wall).is_none());
+    }
+
+    #[test]
+    fn sweep_does_not_tunnel_through_thin_obstacles() {
+        let hits_shape = |position: Vec2| {
+            projectile_hits_shape(
+                position,
+                Vec2::new(30.0, 0.0),
+                Quat::IDENTITY,
+                &ColliderShape::Aabb {
+                    half_size: Vec2::new(1.0, 20.0),
+                },
+            )
+            .then_some(())
+        };
+
+        assert!(sweep_projectile(Vec2::ZERO, Vec2::new(60.0, 0.0), hits_shape).is_some());
+    }
+
+    #[test]
+    fn hits_account_for_shape_rotation() {
+        let shape = ColliderShape::Aabb {
+            half_size: Vec2::new(50.0, 5.0),
+        };
+        let rotation = Quat::from_rotation_z(FRAC_PI_4);
+        let along_diagonal = Vec2::splat(30.0);
+
+        assert!(projectile_hits_shape(
+            along_diagonal,
+            Vec2::ZERO,
+            rotation,
+            &shape
+        ));
+        assert!(!projectile_hits_shape(
+            along_diagonal,
+            Vec2::ZERO,
+            Quat::IDENTITY,
+            &shape
+        ));
+    }
+}
@@ -0,0 +1,362 @@
+use crate::network::{deserialize_server_message, get_current_time, PROTOCOL_ID, PROTOCOL_VERSION};
+use crate::replication::ReplicationRegistry;
+use crate::server::ServerMessages;
+use bevy_renet::renet::ClientId;
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+
+/// Signature placée en tête de chaque fichier de replay.
+pub const REPLAY_MAGIC: [u8; 4] = *b"LZRP";
+
+/// Version du format des fichiers de replay.
+/// Incrémentez cette valeur à chaque changement incompatible du format.
+pub const REPLAY_FORMAT_VERSION: u16 = 1;
+
+/// Taille maximale d'un enregistrement, au-delà de laquelle le fichier est jugé corrompu.
+const MAX_REPLAY_CHUNK_LEN: usize = 64 * 1024 * 1024;
+
+/// Extension des fichiers de replay.
+pub const REPLAY_EXTENSION: &str = "replay";
+
+/// Empreinte du protocole réseau (voir `protocol_fingerprint`).
+pub type ProtocolFingerprint = [u8; 32];
+
+/// Calcule l'empreinte du protocole : le `PROTOCOL_ID`, le `PROTOCOL_VERSION` et les
+/// composants répliqués, dans leur ordre d'enregistrement (qui détermine leurs
+/// `ReplicationId`).
+///
+/// Un replay ne peut être relu que par un client de même empreinte.
+pub fn protocol_fingerprint(registry: &ReplicationRegistry) -> ProtocolFingerprint {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(&PROTOCOL_ID.to_le_bytes());
+    hasher.update(&PROTOCOL_VERSION.to_le_bytes());
+    for (_, rule) in registry.iter() {
+        hasher.update(rule.name.as_bytes());
+        hasher.update(&[0]);
+    }
+    *hasher.finalize().as_bytes()
+}
+
+/// En-tête d'un fichier de replay, écrit après `REPLAY_MAGIC` et la version du format.
+///
+/// - `protocol_id` / `fingerprint` : protocole du serveur enregistré.
+/// - `tick_rate` : nombre de ticks du serveur par seconde.
+/// - `started_at` : début de l'enregistrement, en secondes depuis `UNIX_EPOCH`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayHeader {
+    pub protocol_id: u64,
+    pub fingerprint: ProtocolFingerprint,
+    pub tick_rate: f64,
+    pub started_at: u64,
+}
+
+impl ReplayHeader {
+    /// Crée l'en-tête d'un enregistrement commençant maintenant.
+    pub fn new(fingerprint: ProtocolFingerprint, tick_rate: f64) -> Self {
+        Self {
+            protocol_id: PROTOCOL_ID,
+            fingerprint,
+            tick_rate,
+            started_at: get_current_time().as_secs(),
+        }
+    }
+
+    /// Vérifie que le replay a été enregistré avec le protocole `fingerprint`.
+    pub fn check(&self, fingerprint: &ProtocolFingerprint) -> Result<(), String> {
+        if self.protocol_id != PROTOCOL_ID {
+            return Err(format!(
+                "Replay recorded with protocol {}, expected {PROTOCOL_ID}",
+                self.protocol_id
+            ));
+        }
+        if &self.fingerprint != fingerprint {
+            return Err("Replay recorded with different replicated components".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Destinataire d'un message enregistré.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReplayRecipient {
+    /// Message vu par tous les joueurs (diffusion, réplication).
+    All,
+    /// Message adressé à un seul client (erreur, file d'attente, déconnexion...).
+    Client(ClientId),
+}
+
+/// Message du serveur enregistré, tel qu'envoyé sur le réseau.
+///
+/// - `recipient` : destinataire du message.
+/// - `payload` : `ServerMessages` sérialisé en bincode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayMessage {
+    pub recipient: ReplayRecipient,
+    pub payload: Vec<u8>,
+}
+
+impl ReplayMessage {
+    /// Décode le message enregistré (voir `deserialize_server_message`).
+    pub fn decode(&self) -> ServerMessages {
+        deserialize_server_message(&self.payload).0
+    }
+}
+
+/// Enregistrement d'un fichier de replay.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ReplayRecord {
+    /// Messages envoyés par le serveur pendant un tick.
+    Messages {
+        tick: u64,
+        messages: Vec<ReplayMessage>,
+    },
+    /// État complet observé à la fin d'un tick : messages reconstruisant cet état
+    /// depuis un monde vide. Permet de se déplacer dans le replay sans le relire
+    /// depuis le début.
+    Keyframe { tick: u64, messages: Vec<Vec<u8>> },
+}
+
+impl ReplayRecord {
+    /// Tick du serveur auquel l'enregistrement se rapporte.
+    pub fn tick(&self) -> u64 {
+        match self {
+            ReplayRecord::Messages { tick, .. } | ReplayRecord::Keyframe { tick, .. } => *tick,
+        }
+    }
+}
+
+/// Écrit un fichier de replay au fil de l'eau.
+///
+/// Chaque enregistrement est préfixé de sa longueur et écrit immédiatement sur
+/// le disque : un serveur interrompu laisse un fichier lisible, dont seul le
+/// dernier enregistrement peut être tronqué.
+pub struct ReplayWriter {
+    file: BufWriter<File>,
+}
+
+impl ReplayWriter {
+    /// Crée le fichier de replay et y écrit l'en-tête.
+    pub fn create(path: &Path, header: &ReplayHeader) -> Result<Self, String> {
+        let file = File::create(path)
+            .map_err(|err| format!("Failed to create {}: {err}", path.display()))?;
+        let mut writer = Self {
+            file: BufWriter::new(file),
+        };
+
+        writer
+            .file
+            .write_all(&REPLAY_MAGIC)
+            .and_then(|_| writer.file.write_all(&REPLAY_FORMAT_VERSION.to_le_bytes()))
+            .map_err(|err| err.to_string())?;
+        writer.write_chunk(header)?;
+        Ok(writer)
+    }
+
+    /// Ajoute un enregistrement au fichier.
+    pub fn write(&mut self, record: &ReplayRecord) -> Result<(), String> {
+        self.write_chunk(record)
+    }
+
+    fn write_chunk<T: Serialize>(&mut self, value: &T) -> Result<(), String> {
+        let bytes = bincode::serde::encode_to_vec(value, bincode::config::standard())
+            .map_err(|err| err.to_string())?;
+        self.file
+            .write_all(&(bytes.len() as u32).to_le_bytes())
+            .and_then(|_| self.file.write_all(&bytes))
+            .and_then(|_| self.file.flush())
+            .map_err(|err| err.to_string())
+    }
+}
+
+/// Contenu d'un fichier de replay.
+///
+/// - `truncated` : le dernier enregistrement était incomplet (serveur interrompu)
+///   et a été ignoré.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    pub header: ReplayHeader,
+    pub records: Vec<ReplayRecord>,
+    pub truncated: bool,
+}
+
+impl Replay {
+    /// Lit un fichier de replay.
+    ///
+    /// Retourne une erreur si le fichier n'est pas un replay, ou si sa version de
+    /// format n'est pas `REPLAY_FORMAT_VERSION`. Un dernier enregistrement tronqué
+    /// est ignoré.
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let file =
+            File::open(path).map_err(|err| format!("Failed to open {}: {err}", path.display()))?;
+        let mut reader = BufReader::new(file);
+
+        let mut magic = [0; 4];
+        let mut version = [0; 2];
+        reader
+            .read_exact(&mut magic)
+            .and_then(|_| reader.read_exact(&mut version))
+            .map_err(|_| format!("{} is not a replay file", path.display()))?;
+        if magic != REPLAY_MAGIC {
+            return Err(format!("{} is not a replay file", path.display()));
+        }
+        let version = u16::from_le_bytes(version);
+        if version != REPLAY_FORMAT_VERSION {
+            return Err(format!(
+                "Replay format version {version} is not supported (expected {REPLAY_FORMAT_VERSION})"
+            ));
+        }
+
+        let header = read_chunk(&mut reader)?
+            .ok_or_else(|| format!("{} has no replay header", path.display()))?;
+        let mut records = Vec::new();
+        let truncated = loop {
+            match read_chunk(&mut reader) {
+                Ok(Some(record)) => records.push(record),
+                Ok(None) => break false,
+                Err(_) => break true,
+            }
+        };
+
+        Ok(Self {
+            header,
+            records,
+            truncated,
+        })
+    }
+}
+
+/// Lit un bloc préfixé de sa longueur.
+///
+/// Retourne `Ok(None)` à la fin du fichier, et une erreur pour un bloc tronqué
+/// ou invalide.
+fn read_chunk<T: for<'de> Deserialize<'de>>(reader: &mut impl Read) -> Result<Option<T>, String> {
+    let mut len = [0; 4];
+    match reader.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(err) => return Err(err.to_string()),
+    }
+
+    let len = u32::from_le_bytes(len) as usize;
+    if len > MAX_REPLAY_CHUNK_LEN {
+        return Err(format!("Replay record of {len} bytes is too large"));
+    }
+
+    let mut bytes = vec![0; len];
+    reader
+        .read_exact(&mut bytes)
+        .map_err(|err| err.to_string())?;
+    bincode::serde::decode_from_slice(&bytes, bincode::config::standard())
+        .map(|(value, _)| Some(value))
+        .map_err(|err| err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::serialize_server_message;
+    use std::path::PathBuf;
+
+    fn replay_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("{name}_{}.{REPLAY_EXTENSION}", std::process::id()))
+    }
+
+    fn message(recipient: ReplayRecipient, text: &str) -> ReplayMessage {
+        ReplayMessage {
+            recipient,
+            payload: serialize_server_message(&ServerMessages::Error {
+                message: text.to_string(),
+            }),
+        }
+    }
+
+    fn write_replay(path: &Path, header: &ReplayHeader) {
+        let mut writer = ReplayWriter::create(path, header).unwrap();
+        writer
+            .write(&ReplayRecord::Keyframe {
+                tick: 0,
+                messages: vec![message(ReplayRecipient::All, "keyframe").payload],
+            })
+            .unwrap();
+        writer
+            .write(&ReplayRecord::Messages {
+                tick: 3,
+                messages: vec![
+                    message(ReplayRecipient::All, "hello"),
+                    message(ReplayRecipient::Client(7), "private"),
+                ],
+            })
+            .unwrap();
+    }
+
+    #[test]
+    fn written_replay_is_read_back() {
+        let path = replay_path("replay_round_trip");
+        let header = ReplayHeader::new([1; 32], 60.0);
+        write_replay(&path, &header);
+
+        let replay = Replay::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(replay.header, header);
+        assert!(!replay.truncated);
+        assert_eq!(replay.records.len(), 2);
+        assert_eq!(replay.records[0].tick(), 0);
+        let ReplayRecord::Messages { tick, messages } = &replay.records[1] else {
+            panic!("expected messages, got {:?}", replay.records[1]);
+        };
+        assert_eq!(*tick, 3);
+        assert_eq!(messages[1].recipient, ReplayRecipient::Client(7));
+        assert!(matches!(
+            messages[0].decode(),
+            ServerMessages::Error { message } if message == "hello"
+        ));
+    }
+
+    #[test]
+    fn truncated_last_record_is_ignored() {
+        let path = replay_path("replay_truncated");
+        write_replay(&path, &ReplayHeader::new([1; 32], 60.0));
+        let len = std::fs::metadata(&path).unwrap().len();
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_len(len - 3)
+            .unwrap();
+
+        let replay = Replay::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(replay.truncated);
+        assert_eq!(replay.records.len(), 1);
+        assert_eq!(replay.records[0].tick(), 0);
+    }
+
+    #[test]
+    fn other_files_are_rejected() {
+        let path = replay_path("replay_invalid");
+        std::fs::write(&path, b"not a replay").unwrap();
+
+        let result = Replay::read(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn header_check_rejects_other_protocols() {
+        let header = ReplayHeader::new([1; 32], 60.0);
+
+        assert!(header.check(&[1; 32]).is_ok());
+        assert!(header.check(&[2; 32]).is_err());
+        assert!(ReplayHeader {
+            protocol_id: PROTOCOL_ID + 1,
+            ..header
+        }
+        .check(&[1; 32])
+        .is_err());
+    }
+}
@@ -0,0 +1,626 @@
+use crate::entity_map::NetworkEntityMap;
+use crate::physics::{PhysicsBody, Velocity};
+use crate::player::Health;
+use crate::projectile::Projectile;
+use bevy::app::{App, Plugin};
+use bevy::ecs::component::Tick;
+use bevy::log::warn;
+use bevy::prelude::{
+    AppTypeRegistry, ChildOf, Children, Component, Entity, EntityRef, EntityWorldMut, Mut, Reflect,
+    Resource, Transform, World,
+};
+use bevy::reflect::serde::{TypedReflectDeserializer, TypedReflectSerializer};
+use bevy::reflect::{FromReflect, GetTypeRegistration, TypePath, TypeRegistry};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::any::TypeId;
+
+/// Identifiant d'un composant répliqué : son rang d'enregistrement dans le
+/// `ReplicationRegistry`.
+///
+/// Le client et le serveur doivent donc enregistrer les composants dans le même
+/// ordre ; c'est le rôle de `ReplicationPlugin`, partagé par les deux.
+pub type ReplicationId = u16;
+
+/// Marque une entité du serveur dont les composants enregistrés sont répliqués
+/// automatiquement vers les clients.
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct Replicated;
+
+/// Modification d'un composant répliqué.
+///
+/// - `entity` : entité côté serveur.
+/// - `component` : identifiant du composant dans le `ReplicationRegistry`.
+/// - `data` : valeur du composant sérialisée en bincode.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComponentChange {
+    pub entity: Entity,
+    pub component: ReplicationId,
+    pub data: Vec<u8>,
+}
+
+/// Différence entre deux états répliqués, envoyée dans `ServerMessages::Replication`.
+///
+/// Les entités sont celles du serveur ; le client les convertit à l'application
+/// via sa `NetworkEntityMap`. L'ordre d'application est : créations, modifications,
+/// retraits de composants, puis suppressions d'entités.
+///
+/// Les créations et modifications sont triées dans l'ordre de la hiérarchie : un
+/// parent précède ses enfants, eux-mêmes rangés dans l'ordre de ses `Children`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct ReplicationUpdate {
+    /// Entités répliquées apparues depuis le dernier envoi.
+    pub spawns: Vec<Entity>,
+    /// Composants insérés ou modifiés.
+    pub changes: Vec<ComponentChange>,
+    /// Composants retirés : `(entité, composant)`.
+    pub removals: Vec<(Entity, ReplicationId)>,
+    /// Entités répliquées disparues depuis le dernier envoi.
+    pub despawns: Vec<Entity>,
+}
+
+impl ReplicationUpdate {
+    /// Indique si la différence ne contient aucune modification.
+    pub fn is_empty(&self) -> bool {
+        self.spawns.is_empty()
+            && self.changes.is_empty()
+            && self.removals.is_empty()
+            && self.despawns.is_empty()
+    }
+}
+
+/// Numéro de la dernière `ServerMessages::ReplicationFrame` appliquée par le client.
+///
+/// Les frames arrivées dans le désordre sur le canal non fiable sont ignorées. Remis
+/// à zéro lorsque le client oublie les entités du serveur (reconnexion, replay).
+#[derive(Debug, Default, Clone, Copy, Resource)]
+pub struct LastReplicationFrame(pub Option<u64>);
+
+/// Fonctions de (dé)sérialisation d'un type de composant répliqué.
+///
+/// Le `TypeRegistry` n'est utilisé que par les composants répliqués par réflexion.
+#[derive(Clone, Copy)]
+pub struct ReplicationRule {
+    /// Nom du type de composant, pour les journaux.
+    pub name: &'static str,
+    /// Type du composant, pour retrouver sa règle.
+    pub type_id: TypeId,
+    /// Sérialise le composant de l'entité, s'il est présent.
+    pub serialize: fn(&EntityRef, &TypeRegistry) -> Option<Vec<u8>>,
+    /// Indique si le composant de l'entité a changé entre les ticks `last_run` et
+    /// `this_run`, ou `None` s'il est absent.
+    pub changed: fn(&EntityRef, Tick, Tick) -> Option<bool>,
+    /// Les modifications du composant sont envoyées sur le canal non fiable
+    /// (voir `ServerMessages::ReplicationFrame`).
+    pub unreliable: bool,
+    /// Désérialise le composant, convertit ses références d'entités et l'insère.
+    pub write:
+        fn(&mut EntityWorldMut, &[u8], &mut NetworkEntityMap, &TypeRegistry) -> Result<(), String>,
+    /// Retire le composant de l'entité.
+    pub remove: fn(&mut EntityWorldMut),
+}
+
+/// Registre des composants répliqués, indexé par `ReplicationId`.
+#[derive(Default, Clone, Resource)]
+pub struct ReplicationRegistry {
+    rules: Vec<ReplicationRule>,
+}
+
+impl ReplicationRegistry {
+    /// Ajoute une règle de réplication et retourne son identifiant.
+    pub fn register(&mut self, rule: ReplicationRule) -> ReplicationId {
+        self.rules.push(rule);
+        (self.rules.len() - 1) as ReplicationId
+    }
+
+    /// Récupère la règle associée à un identifiant.
+    pub fn get(&self, id: ReplicationId) -> Option<&ReplicationRule> {
+        self.rules.get(id as usize)
+    }
+
+    /// Récupère l'identifiant du composant `C`, s'il est répliqué.
+    pub fn id_of<C: Component>(&self) -> Option<ReplicationId> {
+        self.rules
+            .iter()
+            .position(|rule| rule.type_id == TypeId::of::<C>())
+            .map(|id| id as ReplicationId)
+    }
+
+    /// Envoie les modifications du composant `C`, déjà enregistré, sur le canal
+    /// non fiable.
+    pub fn set_unreliable<C: Component>(&mut self) {
+        if let Some(rule) = self
+            .rules
+            .iter_mut()
+            .find(|rule| rule.type_id == TypeId::of::<C>())
+        {
+            rule.unreliable = true;
+        }
+    }
+
+    /// Itère sur les couples `(identifiant, règle)`.
+    pub fn iter(&self) -> impl Iterator<Item = (ReplicationId, &ReplicationRule)> {
+        self.rules
+            .iter()
+            .enumerate()
+            .map(|(id, rule)| (id as ReplicationId, rule))
+    }
+}
+
+/// Extension d'`App` pour déclarer les composants répliqués.
+pub trait AppReplicationExt {
+    /// Réplique le composant `C` des entités `Replicated`, sérialisé via `serde`.
+    ///
+    /// Les références d'entités du composant sont converties côté client par
+    /// `Component::map_entities`.
+    fn replicate<C>(&mut self) -> &mut Self
+    where
+        C: Component + Serialize + DeserializeOwned;
+
+    /// Réplique le composant `C` des entités `Replicated`, sérialisé par réflexion
+    /// via le `TypeRegistry` : aucune implémentation `serde` n'est nécessaire.
+    ///
+    /// Le type est enregistré dans l'`AppTypeRegistry`. Ses références d'entités
+    /// (champs marqués `#[entities]`) sont converties côté client par
+    /// `Component::map_entities`.
+    fn replicate_reflect<C>(&mut self) -> &mut Self
+    where
+        C: Component + Reflect + FromReflect + TypePath + GetTypeRegistration;
+
+    /// Envoie les modifications du composant `C`, déjà répliqué, sur le canal non
+    /// fiable : réservé aux composants modifiés à chaque frame, comme les positions.
+    ///
+    /// L'apparition et le retrait du composant restent envoyés sur le canal fiable.
+    fn replicate_unreliable<C: Component>(&mut self) -> &mut Self;
+}
+
+impl AppReplicationExt for App {
+    fn replicate<C>(&mut self) -> &mut Self
+    where
+        C: Component + Serialize + DeserializeOwned,
+    {
+        self.init_resource::<ReplicationRegistry>();
+        self.world_mut()
+            .resource_mut::<ReplicationRegistry>()
+            .register(ReplicationRule {
+                name: std::any::type_name::<C>(),
+                type_id: TypeId::of::<C>(),
+                serialize: serialize_component::<C>,
+                changed: component_changed::<C>,
+                unreliable: false,
+                write: write_component::<C>,
+                remove: remove_component::<C>,
+            });
+        self
+    }
+
+    fn replicate_reflect<C>(&mut self) -> &mut Self
+    where
+        C: Component + Reflect + FromReflect + TypePath + GetTypeRegistration,
+    {
+        self.register_type::<C>();
+        self.init_resource::<ReplicationRegistry>();
+        self.world_mut()
+            .resource_mut::<ReplicationRegistry>()
+            .register(ReplicationRule {
+                name: C::type_path(),
+                type_id: TypeId::of::<C>(),
+                serialize: serialize_reflect_component::<C>,
+                changed: component_changed::<C>,
+                unreliable: false,
+                write: write_reflect_component::<C>,
+                remove: remove_component::<C>,
+            });
+        self
+    }
+
+    fn replicate_unreliable<C: Component>(&mut self) -> &mut Self {
+        self.world_mut()
+            .resource_mut::<ReplicationRegistry>()
+            .set_unreliable::<C>();
+        self
+    }
+}
+
+/// Déclare les composants de jeu répliqués.
+///
+/// Ajouté par le client et par le serveur pour garantir un ordre d'enregistrement
+/// (donc des `ReplicationId`) identique des deux côtés.
+///
+/// `ChildOf` est répliqué pour reproduire la hiérarchie des entités répliquées ;
+/// `Children` est reconstruit par Bevy côté client. `Transform` est répliqué pour
+/// refléter les déplacements décidés par le serveur, `PhysicsBody` et `Velocity`
+/// pour les corps physiques et les projectiles qu'il simule, `Health` et
+/// `Projectile` pour le combat. Les modifications de `Transform` et `Velocity`,
+/// envoyées à chaque frame, passent par le canal non fiable.
+pub struct ReplicationPlugin;
+
+impl Plugin for ReplicationPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ReplicationRegistry>()
+            .replicate_reflect::<ChildOf>()
+            .replicate_reflect::<Transform>()
+            .replicate::<PhysicsBody>()
+            .replicate::<Velocity>()
+            .replicate::<Health>()
+            .replicate::<Projectile>()
+            .replicate_unreliable::<Transform>()
+            .replicate_unreliable::<Velocity>();
+        app.init_resource::<LastReplicationFrame>();
+    }
+}
+
+fn component_changed<C: Component>(
+    entity: &EntityRef,
+    last_run: Tick,
+    this_run: Tick,
+) -> Option<bool> {
+    entity
+        .get_change_ticks::<C>()
+        .map(|ticks| ticks.is_changed(last_run, this_run))
+}
+
+fn serialize_component<C: Component + Serialize>(
+    entity: &EntityRef,
+    _: &TypeRegistry,
+) -> Option<Vec<u8>> {
+    let component = entity.get::<C>()?;
+    bincode::serde::encode_to_vec(component, bincode::config::standard()).ok()
+}
+
+fn write_component<C: Component + DeserializeOwned>(
+    entity: &mut EntityWorldMut,
+    data: &[u8],
+    entity_map: &mut NetworkEntityMap,
+    _: &TypeRegistry,
+) -> Result<(), String> {
+    let (mut component, _): (C, usize) =
+        bincode::serde::decode_from_slice(data, bincode::config::standard())
+            .map_err(|err| err.to_string())?;
+    C::map_entities(&mut component, entity_map);
+    entity.insert(component);
+    Ok(())
+}
+
+fn serialize_reflect_component<C: Component + Reflect>(
+    entity: &EntityRef,
+    type_registry: &TypeRegistry,
+) -> Option<Vec<u8>> {
+    let component = entity.get::<C>()?;
+    let serializer = TypedReflectSerializer::new(component.as_partial_reflect(), type_registry);
+    bincode::serde::encode_to_vec(&serializer, bincode::config::standard()).ok()
+}
+
+fn write_reflect_component<C: Component + FromReflect + TypePath>(
+    entity: &mut EntityWorldMut,
+    data: &[u8],
+    entity_map: &mut NetworkEntityMap,
+    type_registry: &TypeRegistry,
+) -> Result<(), String> {
+    let deserializer = TypedReflectDeserializer::of::<C>(type_registry);
+    let (reflected, _) =
+        bincode::serde::seed_decode_from_slice(deserializer, data, bincode::config::standard())
+            .map_err(|err| err.to_string())?;
+    let mut component = C::from_reflect(reflected.as_ref())
+        .ok_or_else(|| format!("{} cannot be built from reflection", C::type_path()))?;
+    C::map_entities(&mut component, entity_map);
+    entity.insert(component);
+    Ok(())
+}
+
+fn remove_component<C: Component>(entity: &mut EntityWorldMut) {
+    entity.remove::<C>();
+}
+
+/// Applique une différence reçue du serveur au monde du client.
+///
+/// Les entités serveur inconnues sont créées (marquées `Replicated`) et enregistrées
+/// dans la `NetworkEntityMap` ; une entité déjà connue, par exemple un joueur créé par
+/// `ServerMessages::PlayerCreate`, est réutilisée.
+pub fn apply_replication_update(world: &mut World, update: ReplicationUpdate) {
+    let registry = world.get_resource_or_init::<ReplicationRegistry>().clone();
+    let type_registry = world.get_resource_or_init::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    world.init_resource::<NetworkEntityMap>();
+
+    world.resource_scope(|world, mut entity_map: Mut<NetworkEntityMap>| {
+        for server_entity in update.spawns {
+            if entity_map.client_entity(&server_entity).is_some() {
+                continue;
+            }
+            let client_entity = world.spawn(Replicated).id();
+            if let Some(orphan) = entity_map.insert(server_entity, client_entity) {
+                world.despawn(orphan);
+            }
+        }
+
+        for change in update.changes {
+            let Some(rule) = registry.get(change.component) else {
+                warn!("Unknown replicated component {}", change.component);
+                continue;
+            };
+            let Some(mut entity) = entity_map
+                .client_entity(&change.entity)
+                .and_then(|entity| world.get_entity_mut(entity).ok())
+            else {
+                continue;
+            };
+            if let Err(err) =
+                (rule.write)(&mut entity, &change.data, &mut entity_map, &type_registry)
+            {
+                warn!("Failed to replicate {}: {err}", rule.name);
+            }
+        }
+
+        for (server_entity, component) in update.removals {
+            let Some(rule) = registry.get(component) else {
+                continue;
+            };
+            if let Some(mut entity) = entity_map
+                .client_entity(&server_entity)
+                .and_then(|entity| world.get_entity_mut(entity).ok())
+            {
+                (rule.remove)(&mut entity);
+            }
+        }
+
+        // Le despawn d'un parent emporte ses enfants : leurs associations sont retirées
+        // aussi, et leur propre suppression, si elle suit, est ignorée.
+        for server_entity in update.despawns {
+            let Some(client_entity) = entity_map.remove_by_server(&server_entity) else {
+                continue;
+            };
+            if world.get_entity(client_entity).is_err() {
+                continue;
+            }
+            for descendant in descendants(world, client_entity) {
+                entity_map.remove_by_client(&descendant);
+            }
+            world.despawn(client_entity);
+        }
+    });
+}
+
+/// Applique une frame non fiable reçue du serveur (voir
+/// `ServerMessages::ReplicationFrame`).
+///
+/// Une frame plus ancienne que la dernière appliquée est ignorée. Seuls les
+/// composants déjà présents sont modifiés : leur apparition passe par le canal
+/// fiable, et une valeur en retard ne doit pas recréer un composant retiré.
+pub fn apply_replication_frame(world: &mut World, frame: u64, changes: Vec<ComponentChange>) {
+    let last_frame = world.get_resource_or_init::<LastReplicationFrame>();
+    if last_frame.0.is_some_and(|last_frame| frame <= last_frame) {
+        return;
+    }
+    world.insert_resource(LastReplicationFrame(Some(frame)));
+
+    let registry = world.get_resource_or_init::<ReplicationRegistry>().clone();
+    let type_registry = world.get_resource_or_init::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    world.init_resource::<NetworkEntityMap>();
+
+    world.resource_scope(|world, mut entity_map: Mut<NetworkEntityMap>| {
+        for change in changes {
+            let Some(rule) = registry.get(change.component) else {
+                continue;
+            };
+            let Some(mut entity) = entity_map
+                .client_entity(&change.entity)
+                .and_then(|entity| world.get_entity_mut(entity).ok())
+                .filter(|entity| entity.contains_type_id(rule.type_id))
+            else {
+                continue;
+            };
+            if let Err(err) =
+                (rule.write)(&mut entity, &change.data, &mut entity_map, &type_registry)
+            {
+                warn!("Failed to replicate {}: {err}", rule.name);
+            }
+        }
+    });
+}
+
+/// Descendants d'une entité, parcourus via `Children`.
+fn descendants(world: &World, entity: Entity) -> Vec<Entity> {
+    let mut descendants = Vec::new();
+    let mut stack = vec![entity];
+    while let Some(parent) = stack.pop() {
+        if let Some(children) = world.get::<Children>(parent) {
+            descendants.extend(children.iter());
+            stack.extend(children.iter());
+        }
+    }
+    descendants
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::prelude::Vec3;
+
+    fn replication_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(ReplicationPlugin);
+        app
+    }
+
+    /// Construit l'état complet des entités `entities` du monde serveur, parents d'abord.
+    fn full_update(server: &App, entities: &[Entity]) -> ReplicationUpdate {
+        let registry = server.world().resource::<ReplicationRegistry>().clone();
+        let type_registry = server.world().resource::<AppTypeRegistry>().clone();
+        let type_registry = type_registry.read();
+        let mut update = ReplicationUpdate {
+            spawns: entities.to_vec(),
+            ..Default::default()
+        };
+        for entity in entities {
+            let entity_ref = server.world().entity(*entity);
+            for (id, rule) in registry.iter() {
+                if let Some(data) = (rule.serialize)(&entity_ref, &type_registry) {
+                    update.changes.push(ComponentChange {
+                        entity: *entity,
+                        component: id,
+                        data,
+                    });
+                }
+            }
+        }
+        update
+    }
+
+    fn client_entity(client: &App, server_entity: Entity) -> Entity {
+        client
+            .world()
+            .resource::<NetworkEntityMap>()
+            .client_entity(&server_entity)
+            .unwrap()
+    }
+
+    #[test]
+    fn serde_and_reflect_components_round_trip() {
+        let mut server = replication_app();
+        let mut client = replication_app();
+        let transform = Transform::from_xyz(12.0, -4.0, 1.0).with_scale(Vec3::splat(2.0));
+        let entity = server
+            .world_mut()
+            .spawn((Replicated, Health(42), transform))
+            .id();
+
+        let update = full_update(&server, &[entity]);
+        apply_replication_update(client.world_mut(), update);
+
+        let replicated = client.world().entity(client_entity(&client, entity));
+        assert_eq!(replicated.get::<Health>(), Some(&Health(42)));
+        assert_eq!(replicated.get::<Transform>(), Some(&transform));
+    }
+
+    #[test]
+    fn child_of_is_mapped_to_client_entities() {
+        let mut server = replication_app();
+        let mut client = replication_app();
+        let parent = server.world_mut().spawn(Replicated).id();
+        let child = server.world_mut().spawn((Replicated, ChildOf(parent))).id();
+
+        let update = full_update(&server, &[parent, child]);
+        apply_replication_update(client.world_mut(), update);
+
+        let client_parent = client_entity(&client, parent);
+        let client_child = client_entity(&client, child);
+        assert_eq!(
+            client.world().get::<ChildOf>(client_child),
+            Some(&ChildOf(client_parent))
+        );
+    }
+
+    #[test]
+    fn removal_takes_component_off_client_entity() {
+        let mut server = replication_app();
+        let mut client = replication_app();
+        let entity = server.world_mut().spawn((Replicated, Health(10))).id();
+        let update = full_update(&server, &[entity]);
+        apply_replication_update(client.world_mut(), update);
+
+        let health = server
+            .world()
+            .resource::<ReplicationRegistry>()
+            .id_of::<Health>()
+            .unwrap();
+        apply_replication_update(
+            client.world_mut(),
+            ReplicationUpdate {
+                removals: vec![(entity, health)],
+                ..Default::default()
+            },
+        );
+
+        let client_entity = client_entity(&client, entity);
+        assert!(client.world().get::<Health>(client_entity).is_none());
+    }
+
+    #[test]
+    fn despawn_removes_descendants() {
+        let mut server = replication_app();
+        let mut client = replication_app();
+        let root = server.world_mut().spawn(Replicated).id();
+        let child = server.world_mut().spawn((Replicated, ChildOf(root))).id();
+        let grandchild = server.world_mut().spawn((Replicated, ChildOf(child))).id();
+        let update = full_update(&server, &[root, child, grandchild]);
+        apply_replication_update(client.world_mut(), update);
+        let client_entities =
+            [root, child, grandchild].map(|entity| client_entity(&client, entity));
+
+        // Le despawn des enfants, s'il suit celui du parent, est ignoré.
+        apply_replication_update(
+            client.world_mut(),
+            ReplicationUpdate {
+                despawns: vec![root, grandchild],
+                ..Default::default()
+            },
+        );
+
+        for entity in client_entities {
+            assert!(client.world().get_entity(entity).is_err());
+        }
+        assert!(client.world().resource::<NetworkEntityMap>().is_empty());
+    }
+
+    #[test]
+    fn frames_update_known_components_in_order() {
+        let mut server = replication_app();
+        let mut client = replication_app();
+        let entity = server.world_mut().spawn((Replicated, Health(10))).id();
+        let update = full_update(&server, &[entity]);
+        apply_replication_update(client.world_mut(), update);
+        let registry = server.world().resource::<ReplicationRegistry>().clone();
+        let change = |component, data| ComponentChange {
+            entity,
+            component,
+            data,
+        };
+        let health = registry.id_of::<Health>().unwrap();
+        let velocity = registry.id_of::<Velocity>().unwrap();
+        let encode = |health: u32| {
+            bincode::serde::encode_to_vec(Health(health), bincode::config::standard()).unwrap()
+        };
+        let velocity_data =
+            bincode::serde::encode_to_vec(Velocity::default(), bincode::config::standard())
+                .unwrap();
+
+        apply_replication_frame(client.world_mut(), 2, vec![change(health, encode(20))]);
+        // Frame en retard, puis composant absent du client.
+        apply_replication_frame(client.world_mut(), 1, vec![change(health, encode(30))]);
+        apply_replication_frame(client.world_mut(), 3, vec![change(velocity, velocity_data)]);
+
+        let replicated = client.world().entity(client_entity(&client, entity));
+        assert_eq!(replicated.get::<Health>(), Some(&Health(20)));
+        assert!(replicated.get::<Velocity>().is_none());
+        assert_eq!(client.world().resource::<LastReplicationFrame>().0, Some(3));
+    }
+
+    #[test]
+    fn corrupt_component_is_ignored() {
+        let mut client = replication_app();
+        let server_entity = client.world_mut().spawn_empty().id();
+        let health = client
+            .world()
+            .resource::<ReplicationRegistry>()
+            .id_of::<Health>()
+            .unwrap();
+
+        apply_replication_update(
+            client.world_mut(),
+            ReplicationUpdate {
+                spawns: vec![server_entity],
+                changes: vec![ComponentChange {
+                    entity: server_entity,
+                    component: health,
+                    data: vec![0xff],
+                }],
+                ..Default::default()
+            },
+        );
+
+        let client_entity = client_entity(&client, server_entity);
+        assert!(client.world().get::<Health>(client_entity).is_none());
+    }
+}
@@ -1,6 +1,12 @@
+use crate::client::SessionToken;
+use crate::map::GameMap;
+use crate::player::PlayerInfo;
+use crate::replication::{ComponentChange, ReplicationUpdate};
+use bevy::ecs::entity::{EntityMapper, MapEntities};
 use bevy::prelude::{Component, Entity, Vec3};
 use bevy_renet::renet::{ChannelConfig, ClientId, SendType};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use std::time::Duration;
 
 /// Canal utilisé par le serveur pour envoyer des paquets au client.
@@ -18,17 +24,19 @@ pub enum ServerChannel {
 ///
 /// Ces messages sont sérialisés via `serde` et transmis sur les canaux définis
 /// dans `ServerChannel'.
-#[derive(Debug, Serialize, Deserialize, Component)]
+#[derive(Debug, Clone, Serialize, Deserialize, Component)]
 pub enum ServerMessages {
     /// Crée un joueur côté client.
     ///
     /// - `entity` : identifiant de l'entité côté serveur (permets le mapping).
     /// - `id` : identifiant unique du client ('ClientId').
     /// - `translation` : position initiale du joueur sous la forme `[x, y, z]'.
+    /// - `info` : nom et couleur du joueur, affichés par tous les clients.
     PlayerCreate {
         client_id: ClientId,
         position: Vec3,
         entity: Entity,
+        info: PlayerInfo,
     },
     /// Supprime un joueur côté client.
     ///
@@ -36,11 +44,201 @@ pub enum ServerMessages {
     PlayerRemove {
         client_id: ClientId,
     },
+    /// Jeton de session du joueur, envoyé à son seul client à la création du joueur.
+    ///
+    /// Le client le renvoie dans ses `ConnectUserData` pour récupérer son joueur
+    /// s'il se reconnecte pendant le délai de grâce.
+    Session {
+        token: SessionToken,
+    },
+    /// Annonce l'arrêt imminent du serveur.
+    ///
+    /// - `reason` : raison de l'arrêt, affichable au joueur.
+    /// - `restart_eta` : délai estimé avant le redémarrage du serveur, s'il est prévu.
+    ServerShutdown {
+        reason: String,
+        restart_eta: Option<Duration>,
+    },
+    /// Différence de l'état des composants répliqués (voir `ReplicationRegistry`).
+    ///
+    /// Les entités qu'elle contient sont converties à l'application par
+    /// `apply_replication_update`, et non par `map_entities`.
+    Replication {
+        update: ReplicationUpdate,
+    },
+    /// Valeurs récentes des composants répliqués sur le canal non fiable
+    /// (`ServerChannel::NetworkedEntities`), appliquées par `apply_replication_frame`.
+    ///
+    /// - `frame` : numéro croissant de la frame, pour ignorer les frames en retard.
+    /// - `changes` : composants modifiés lors des dernières frames.
+    ReplicationFrame {
+        frame: u64,
+        changes: Vec<ComponentChange>,
+    },
+    /// Informe le client de la raison de sa déconnexion imminente.
+    ///
+    /// Envoyé sur le canal fiable avant que le serveur ne coupe la connexion.
+    Disconnect {
+        reason: DisconnectReason,
+    },
+    /// Informe un client en attente de sa position dans la file du serveur plein.
+    ///
+    /// - `position` : position du client, à partir de 1.
+    /// - `total` : nombre de clients dans la file.
+    QueuePosition {
+        position: u32,
+        total: u32,
+    },
+    /// Message de chat validé par le serveur.
+    ///
+    /// - `client_id` : identifiant de l'auteur du message, `None` pour un message du serveur.
+    /// - `name` : nom de l'auteur, tiré de son `PlayerInfo` par le serveur.
+    /// - `text` : texte du message, nettoyé par le serveur.
+    /// - `team_only` : message réservé à l'équipe de l'auteur.
+    Chat {
+        client_id: Option<ClientId>,
+        name: String,
+        text: String,
+        team_only: bool,
+    },
+    /// Confirme au client qu'il regarde la partie en spectateur, sans joueur.
+    ///
+    /// Il reçoit la réplication complète du monde ; la création de son propre joueur
+    /// (`PlayerCreate`) met fin au mode spectateur.
+    Spectating,
+    /// Carte de la partie : l'arène et ses murs.
+    ///
+    /// Envoyée avant les joueurs à chaque client qui rejoint la partie ; le client
+    /// crée les murs avec leurs `StaticCollider`.
+    Map {
+        map: GameMap,
+    },
     Error {
         message: String,
     },
 }
 
+impl ServerMessages {
+    /// Entité serveur nouvellement créée par ce message, le cas échéant.
+    ///
+    /// Le client doit créer l'entité locale correspondante et l'enregistrer dans
+    /// sa `NetworkEntityMap` avant d'appeler `map_entities` sur le message.
+    pub fn spawned_entity(&self) -> Option<Entity> {
+        match self {
+            ServerMessages::PlayerCreate { entity, .. } => Some(*entity),
+            _ => None,
+        }
+    }
+}
+
+impl MapEntities for ServerMessages {
+    /// Remplace les identifiants d'entités serveur contenus dans le message.
+    fn map_entities<E: EntityMapper>(&mut self, entity_mapper: &mut E) {
+        match self {
+            ServerMessages::PlayerCreate { entity, .. } => {
+                *entity = entity_mapper.get_mapped(*entity);
+            }
+            ServerMessages::PlayerRemove { .. }
+            | ServerMessages::Session { .. }
+            | ServerMessages::Replication { .. }
+            | ServerMessages::ReplicationFrame { .. }
+            | ServerMessages::ServerShutdown { .. }
+            | ServerMessages::Disconnect { .. }
+            | ServerMessages::QueuePosition { .. }
+            | ServerMessages::Chat { .. }
+            | ServerMessages::Spectating
+            | ServerMessages::Map { .. }
+            | ServerMessages::Error { .. } => {}
+        }
+    }
+}
+
+/// Raison de jeu d'une déconnexion, transmise au client avant la coupure.
+///
+/// Complète la raison de transport fournie par `bevy_renet', qui ne dit pas
+/// pourquoi le serveur a décidé de déconnecter un joueur.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DisconnectReason {
+    /// Expulsé par un administrateur, avec un motif facultatif.
+    Kicked { message: Option<String> },
+    /// Banni du serveur, avec un motif facultatif.
+    Banned { message: Option<String> },
+    /// Le client ne figure pas sur la liste blanche du serveur.
+    NotWhitelisted,
+    /// Le serveur est protégé par un mot de passe que le client n'a pas fourni.
+    PasswordRequired,
+    /// Le mot de passe fourni par le client est incorrect.
+    WrongPassword,
+    /// Le serveur n'accepte plus de joueurs.
+    ServerFull,
+    /// Les versions du client et du serveur sont incompatibles.
+    VersionMismatch,
+    /// La connexion a expiré faute de paquets reçus.
+    Timeout,
+    /// Le serveur s'arrête.
+    Shutdown { message: String },
+    /// Le joueur est resté inactif trop longtemps.
+    Idle,
+    /// Le client a dépassé les limites de débit de ses canaux.
+    Flooding,
+    /// Le client a quitté le serveur (voir `ClientMessages::Leave`).
+    Left,
+    /// L'identifiant du client est réservé aux joueurs contrôlés par le serveur.
+    ReservedClientId,
+    /// Un joueur déconnecté de même identifiant attend sa reconnexion, et le client
+    /// n'a pas présenté son jeton de session.
+    SessionInUse,
+}
+
+impl DisconnectReason {
+    /// Indique si le client doit saisir (à nouveau) le mot de passe du serveur.
+    pub fn requires_password(&self) -> bool {
+        matches!(
+            self,
+            DisconnectReason::PasswordRequired | DisconnectReason::WrongPassword
+        )
+    }
+}
+
+impl fmt::Display for DisconnectReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DisconnectReason::Kicked {
+                message: Some(message),
+            } => {
+                write!(f, "Kicked from the server: {message}")
+            }
+            DisconnectReason::Kicked { message: None } => write!(f, "Kicked from the server"),
+            DisconnectReason::Banned {
+                message: Some(message),
+            } => {
+                write!(f, "Banned from the server: {message}")
+            }
+            DisconnectReason::Banned { message: None } => write!(f, "Banned from the server"),
+            DisconnectReason::NotWhitelisted => {
+                write!(f, "You are not on the server whitelist")
+            }
+            DisconnectReason::PasswordRequired => write!(f, "This server requires a password"),
+            DisconnectReason::WrongPassword => write!(f, "Wrong server password"),
+            DisconnectReason::ServerFull => write!(f, "The server is full"),
+            DisconnectReason::VersionMismatch => {
+                write!(f, "Client and server versions do not match")
+            }
+            DisconnectReason::Timeout => write!(f, "Connection timed out"),
+            DisconnectReason::Shutdown { message } => write!(f, "Server shut down: {message}"),
+            DisconnectReason::Idle => write!(f, "Disconnected for inactivity"),
+            DisconnectReason::Flooding => write!(f, "Disconnected for sending too many messages"),
+            DisconnectReason::Left => write!(f, "You left the server"),
+            DisconnectReason::ReservedClientId => {
+                write!(f, "This client id is reserved by the server")
+            }
+            DisconnectReason::SessionInUse => {
+                write!(f, "A player with this client id is waiting to reconnect")
+            }
+        }
+    }
+}
+
 impl From<ServerChannel> for u8 {
     /// Convertit un `ServerChannel` en identifiant de canal ('u8').
     ///
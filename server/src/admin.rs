@@ -0,0 +1,345 @@
+use bevy::prelude::Message;
+use bevy_renet::renet::ClientId;
+use std::time::Duration;
+
+/// Aide affichée par la commande `help`.
+pub const ADMIN_HELP: &str = "Commands: kick <id|name> [reason], \
+ban <id|name> [--id-only] [reason] (also bans the IP; an id-only ban is evaded by changing id), \
+unban <id>, say <message>, list, tp <id|name> <x> <y>, ai add [count] [profile], \
+ai remove <id|name|all>, ai profiles, shutdown [eta_secs] [reason], help";
+
+/// Joueur visé par une commande d'administration : par identifiant ou par nom.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlayerTarget {
+    /// Identifiant réseau du client.
+    Id(ClientId),
+    /// Nom affiché du joueur (comparé sans tenir compte de la casse).
+    Name(String),
+}
+
+impl PlayerTarget {
+    /// Interprète un argument numérique comme un identifiant, sinon comme un nom.
+    pub fn parse(arg: &str) -> Self {
+        arg.parse()
+            .map(PlayerTarget::Id)
+            .unwrap_or_else(|_| PlayerTarget::Name(arg.to_string()))
+    }
+}
+
+/// Commande d'administration du serveur.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AdminCommand {
+    /// Déconnecte un joueur, avec un motif facultatif.
+    Kick {
+        target: PlayerTarget,
+        reason: Option<String>,
+    },
+    /// Bannit un joueur (il ne pourra plus se connecter), avec un motif facultatif.
+    ///
+    /// Avec `ip` (par défaut, sauf option `--id-only`), son adresse IP est bannie elle
+    /// aussi : l'identifiant, choisi par le client, ne suffit pas à l'écarter.
+    Ban {
+        target: PlayerTarget,
+        ip: bool,
+        reason: Option<String>,
+    },
+    /// Lève le bannissement d'un client.
+    Unban { client_id: ClientId },
+    /// Diffuse un message du serveur à tous les joueurs.
+    Say { text: String },
+    /// Liste les joueurs du lobby.
+    List,
+    /// Téléporte un joueur à une position.
+    Tp {
+        target: PlayerTarget,
+        x: f32,
+        y: f32,
+    },
+    /// Ajoute des joueurs contrôlés par le serveur, du profil donné ou du profil
+    /// par défaut.
+    AddAi {
+        count: usize,
+        profile: Option<String>,
+    },
+    /// Retire un joueur contrôlé par le serveur, ou tous si `target` vaut `None`.
+    RemoveAi { target: Option<PlayerTarget> },
+    /// Liste les profils de joueurs IA.
+    AiProfiles,
+    /// Arrête proprement le serveur, en annonçant éventuellement un redémarrage.
+    Shutdown {
+        restart_eta: Option<Duration>,
+        reason: Option<String>,
+    },
+    /// Affiche l'aide.
+    Help,
+}
+
+impl AdminCommand {
+    /// Analyse une ligne de commande, par exemple `kick Bob spamming`.
+    ///
+    /// Retourne un message d'erreur affichable si la commande est inconnue ou
+    /// si ses arguments sont invalides.
+    pub fn parse(line: &str) -> Result<Self, String> {
+        let mut args = line.split_whitespace();
+        let Some(name) = args.next() else {
+            return Err("Empty command".to_string());
+        };
+        let rest = |args: std::str::SplitWhitespace| {
+            let rest = args.collect::<Vec<_>>().join(" ");
+            (!rest.is_empty()).then_some(rest)
+        };
+        let target = |arg: Option<&str>| {
+            arg.map(PlayerTarget::parse)
+                .ok_or_else(|| format!("Usage: {name} <id|name> ..."))
+        };
+
+        match name.to_lowercase().as_str() {
+            "kick" => Ok(AdminCommand::Kick {
+                target: target(args.next())?,
+                reason: rest(args),
+            }),
+            "ban" => {
+                let target = target(args.next())?;
+                let (flags, reason): (Vec<&str>, Vec<&str>) =
+                    args.partition(|arg| *arg == "--id-only");
+                Ok(AdminCommand::Ban {
+                    target,
+                    ip: flags.is_empty(),
+                    reason: (!reason.is_empty()).then(|| reason.join(" ")),
+                })
+            }
+            "unban" => args
+                .next()
+                .and_then(|client_id| client_id.parse().ok())
+                .map(|client_id| AdminCommand::Unban { client_id })
+                .ok_or_else(|| "Usage: unban <id>".to_string()),
+            "say" => rest(args)
+                .map(|text| AdminCommand::Say { text })
+                .ok_or_else(|| "Usage: say <message>".to_string()),
+            "list" => Ok(AdminCommand::List),
+            "tp" => {
+                let target = target(args.next())?;
+                let coordinate = |arg: Option<&str>| arg.and_then(|arg| arg.parse::<f32>().ok());
+                match (coordinate(args.next()), coordinate(args.next())) {
+                    (Some(x), Some(y)) if x.is_finite() && y.is_finite() => {
+                        Ok(AdminCommand::Tp { target, x, y })
+                    }
+                    _ => Err("Usage: tp <id|name> <x> <y>".to_string()),
+                }
+            }
+            "ai" => match args.next().map(str::to_lowercase).as_deref() {
+                Some("add") => {
+                    let mut count = 1;
+                    let mut profile = args.next();
+                    if let Some(arg) = profile
+                        && let Ok(parsed) = arg.parse()
+                    {
+                        count = parsed;
+                        profile = args.next();
+                    }
+                    Ok(AdminCommand::AddAi {
+                        count,
+                        profile: profile.map(str::to_string),
+                    })
+                }
+                Some("remove") => match args.next() {
+                    Some(arg) if arg.eq_ignore_ascii_case("all") => {
+                        Ok(AdminCommand::RemoveAi { target: None })
+                    }
+                    Some(arg) => Ok(AdminCommand::RemoveAi {
+                        target: Some(PlayerTarget::parse(arg)),
+                    }),
+                    None => Err("Usage: ai remove <id|name|all>".to_string()),
+                },
+                Some("profiles") => Ok(AdminCommand::AiProfiles),
+                _ => Err(
+                    "Usage: ai add [count] [profile], ai remove <id|name|all>, ai profiles"
+                        .to_string(),
+                ),
+            },
+            "shutdown" => {
+                let mut args = args.peekable();
+                let restart_eta = args
+                    .next_if(|arg| arg.parse::<u64>().is_ok())
+                    .and_then(|arg| arg.parse().ok())
+                    .map(Duration::from_secs);
+                let reason = args.collect::<Vec<_>>().join(" ");
+                Ok(AdminCommand::Shutdown {
+                    restart_eta,
+                    reason: (!reason.is_empty()).then_some(reason),
+                })
+            }
+            "help" => Ok(AdminCommand::Help),
+            _ => Err(format!("Unknown command '{name}'. {ADMIN_HELP}")),
+        }
+    }
+}
+
+/// Origine d'une commande d'administration, à qui la réponse est adressée.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AdminSource {
+    /// Console du serveur (entrée standard).
+    Console,
+    /// Client administrateur, via une commande de chat `/...`.
+    Client(ClientId),
+}
+
+/// Demande d'exécution d'une commande d'administration.
+#[derive(Message, Debug, Clone)]
+pub struct AdminCommandRequest {
+    pub source: AdminSource,
+    pub command: AdminCommand,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_kick_with_reason() {
+        assert_eq!(
+            AdminCommand::parse("KICK Bob stop spamming"),
+            Ok(AdminCommand::Kick {
+                target: PlayerTarget::Name("Bob".to_string()),
+                reason: Some("stop spamming".to_string()),
+            })
+        );
+        assert_eq!(
+            AdminCommand::parse("kick 42"),
+            Ok(AdminCommand::Kick {
+                target: PlayerTarget::Id(42),
+                reason: None,
+            })
+        );
+    }
+
+    #[test]
+    fn ban_targets_ip_unless_id_only() {
+        assert_eq!(
+            AdminCommand::parse("ban 42 cheating"),
+            Ok(AdminCommand::Ban {
+                target: PlayerTarget::Id(42),
+                ip: true,
+                reason: Some("cheating".to_string()),
+            })
+        );
+        assert_eq!(
+            AdminCommand::parse("ban Bob --id-only aim bot"),
+            Ok(AdminCommand::Ban {
+                target: PlayerTarget::Name("Bob".to_string()),
+                ip: false,
+                reason: Some("aim bot".to_string()),
+            })
+        );
+        assert_eq!(
+            AdminCommand::parse("ban 42 --id-only"),
+            Ok(AdminCommand::Ban {
+                target: PlayerTarget::Id(42),
+                ip: false,
+                reason: None,
+            })
+        );
+    }
+
+    #[test]
+    fn parses_other_commands() {
+        assert_eq!(
+            AdminCommand::parse("unban 42"),
+            Ok(AdminCommand::Unban { client_id: 42 })
+        );
+        assert_eq!(
+            AdminCommand::parse("say  hello   all"),
+            Ok(AdminCommand::Say {
+                text: "hello all".to_string()
+            })
+        );
+        assert_eq!(
+            AdminCommand::parse("tp 7 -10 2.5"),
+            Ok(AdminCommand::Tp {
+                target: PlayerTarget::Id(7),
+                x: -10.0,
+                y: 2.5,
+            })
+        );
+        assert_eq!(AdminCommand::parse("list"), Ok(AdminCommand::List));
+        assert_eq!(AdminCommand::parse("help"), Ok(AdminCommand::Help));
+    }
+
+    #[test]
+    fn parses_shutdown_with_optional_eta_and_reason() {
+        assert_eq!(
+            AdminCommand::parse("shutdown"),
+            Ok(AdminCommand::Shutdown {
+                restart_eta: None,
+                reason: None,
+            })
+        );
+        assert_eq!(
+            AdminCommand::parse("shutdown 60 update  in progress"),
+            Ok(AdminCommand::Shutdown {
+                restart_eta: Some(Duration::from_secs(60)),
+                reason: Some("update in progress".to_string()),
+            })
+        );
+        assert_eq!(
+            AdminCommand::parse("SHUTDOWN maintenance"),
+            Ok(AdminCommand::Shutdown {
+                restart_eta: None,
+                reason: Some("maintenance".to_string()),
+            })
+        );
+    }
+
+    #[test]
+    fn parses_ai_commands() {
+        assert_eq!(
+            AdminCommand::parse("ai add"),
+            Ok(AdminCommand::AddAi {
+                count: 1,
+                profile: None,
+            })
+        );
+        assert_eq!(
+            AdminCommand::parse("AI ADD 3 hard"),
+            Ok(AdminCommand::AddAi {
+                count: 3,
+                profile: Some("hard".to_string()),
+            })
+        );
+        assert_eq!(
+            AdminCommand::parse("ai add easy"),
+            Ok(AdminCommand::AddAi {
+                count: 1,
+                profile: Some("easy".to_string()),
+            })
+        );
+        assert_eq!(
+            AdminCommand::parse("ai remove all"),
+            Ok(AdminCommand::RemoveAi { target: None })
+        );
+        assert_eq!(
+            AdminCommand::parse("ai remove Bot"),
+            Ok(AdminCommand::RemoveAi {
+                target: Some(PlayerTarget::Name("Bot".to_string())),
+            })
+        );
+        assert_eq!(
+            AdminCommand::parse("ai profiles"),
+            Ok(AdminCommand::AiProfiles)
+        );
+        assert!(AdminCommand::parse("ai").is_err());
+        assert!(AdminCommand::parse("ai remove").is_err());
+        assert!(AdminCommand::parse("ai spawn").is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_commands() {
+        assert!(AdminCommand::parse("").is_err());
+        assert!(AdminCommand::parse("explode").is_err());
+        assert!(AdminCommand::parse("kick").is_err());
+        assert!(AdminCommand::parse("unban Bob").is_err());
+        assert!(AdminCommand::parse("say").is_err());
+        assert!(AdminCommand::parse("tp 7 1").is_err());
+        assert!(AdminCommand::parse("tp 7 NaN 1").is_err());
+    }
+}
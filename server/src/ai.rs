@@ -0,0 +1,266 @@
+use crate::navigation::NavPath;
+use bevy::prelude::{Message, Vec2};
+use bevy_renet::renet::ClientId;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Premier identifiant attribué aux joueurs contrôlés par le serveur.
+///
+/// Les identifiants des clients réels (horodatages en millisecondes) restent
+/// très en dessous de cette valeur.
+pub const AI_CLIENT_ID_BASE: ClientId = 1 << 63;
+
+/// Rayon, autour du centre de l'arène, de la zone parcourue par les joueurs IA qui
+/// errent ou fuient.
+pub const AI_WANDER_RADIUS: f32 = 500.0;
+
+/// Distance en deçà de laquelle un joueur IA qui poursuit sa cible s'arrête.
+pub const AI_CHASE_STOP_DISTANCE: f32 = 100.0;
+
+/// Distance à laquelle un joueur IA qui fuit cherche à s'éloigner de la menace.
+pub const AI_FLEE_DISTANCE: f32 = 300.0;
+
+/// Indique si un identifiant désigne un joueur contrôlé par le serveur.
+pub fn is_ai_client(client_id: &ClientId) -> bool {
+    *client_id >= AI_CLIENT_ID_BASE
+}
+
+/// Comportement d'un joueur IA.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AiBehavior {
+    /// Se déplace au hasard autour du centre de l'arène.
+    Wander,
+    /// Poursuit le joueur le plus proche.
+    Chase,
+    /// Fuit le joueur le plus proche.
+    Flee,
+}
+
+/// Profil de difficulté d'un joueur IA.
+///
+/// - `name` : nom du profil, utilisé par la commande `ai add`.
+/// - `behavior` : comportement du joueur.
+/// - `reaction_time` : délai entre deux décisions, en secondes.
+/// - `aim_error` : écart angulaire maximal de la destination choisie, en radians.
+/// - `speed` : fraction de la vitesse maximale utilisée, entre 0 et 1.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AiProfile {
+    pub name: String,
+    pub behavior: AiBehavior,
+    pub reaction_time: f32,
+    pub aim_error: f32,
+    pub speed: f32,
+}
+
+impl AiProfile {
+    /// Profils disponibles par défaut : `easy`, `normal` et `hard`.
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            AiProfile {
+                name: "easy".to_string(),
+                behavior: AiBehavior::Wander,
+                reaction_time: 1.0,
+                aim_error: 0.6,
+                speed: 0.6,
+            },
+            AiProfile {
+                name: "normal".to_string(),
+                behavior: AiBehavior::Chase,
+                reaction_time: 0.5,
+                aim_error: 0.3,
+                speed: 0.85,
+            },
+            AiProfile {
+                name: "hard".to_string(),
+                behavior: AiBehavior::Chase,
+                reaction_time: 0.15,
+                aim_error: 0.05,
+                speed: 1.0,
+            },
+        ]
+    }
+
+    /// Charge une liste de profils depuis un fichier RON.
+    ///
+    /// # Panique
+    ///
+    /// Panique si le fichier ne peut pas être lu ou interprété, ou s'il ne contient
+    /// aucun profil : le serveur ne démarre pas avec une configuration ignorée.
+    pub fn load_all(path: &Path) -> Vec<Self> {
+        let content = std::fs::read_to_string(path)
+            .unwrap_or_else(|err| panic!("Failed to read AI profiles {}: {err}", path.display()));
+        let profiles: Vec<Self> = ron::from_str(&content)
+            .unwrap_or_else(|err| panic!("Invalid AI profiles {}: {err}", path.display()));
+        if profiles.is_empty() {
+            panic!("{} contains no AI profile", path.display());
+        }
+        profiles
+    }
+
+    /// Destination choisie par le profil pour un joueur en `position`, ou `None`
+    /// s'il doit rester sur place.
+    ///
+    /// - `nearest` : position du joueur le plus proche, s'il y en a un.
+    ///
+    /// Un joueur qui erre choisit un point au hasard à moins de `AI_WANDER_RADIUS` du
+    /// centre ; un joueur qui fuit au-delà de ce rayon revient vers le centre. La
+    /// destination est déviée au hasard d'au plus `aim_error` autour du joueur.
+    pub fn choose_goal(&self, position: Vec2, nearest: Option<Vec2>) -> Option<Vec2> {
+        let goal = match (self.behavior, nearest) {
+            (AiBehavior::Chase, Some(target)) => {
+                if target.distance(position) < AI_CHASE_STOP_DISTANCE {
+                    return None;
+                }
+                target
+            }
+            (AiBehavior::Flee, Some(threat)) if position.length() <= AI_WANDER_RADIUS => {
+                let away = (position - threat)
+                    .normalize_or(Vec2::from_angle(fastrand::f32() * std::f32::consts::TAU));
+                position + away * AI_FLEE_DISTANCE
+            }
+            (AiBehavior::Flee, Some(_)) => Vec2::ZERO,
+            _ => {
+                Vec2::from_angle(fastrand::f32() * std::f32::consts::TAU)
+                    * fastrand::f32().sqrt()
+                    * AI_WANDER_RADIUS
+            }
+        };
+
+        let error = (fastrand::f32() * 2.0 - 1.0) * self.aim_error;
+        Some(position + Vec2::from_angle(error).rotate(goal - position))
+    }
+}
+
+/// Demande d'ajout ou de retrait de joueurs IA, émise par les commandes
+/// d'administration ou au démarrage du serveur.
+#[derive(Message, Debug, Clone)]
+pub enum AiRequest {
+    /// Ajoute `count` joueurs IA du profil nommé `profile`.
+    Add { count: usize, profile: String },
+    /// Retire un joueur IA, ou tous si `client_id` vaut `None`.
+    Remove { client_id: Option<ClientId> },
+}
+
+/// État d'un joueur IA.
+///
+/// - `profile` : profil de difficulté du joueur.
+/// - `sequence` : numéro de la dernière entrée produite.
+/// - `path` : chemin vers la destination choisie lors de la dernière décision.
+/// - `next_decision` : instant (temps écoulé depuis le démarrage, en secondes) de
+///   la prochaine décision.
+#[derive(Debug, Clone)]
+pub struct AiPlayer {
+    pub profile: AiProfile,
+    pub sequence: u32,
+    pub path: NavPath,
+    pub next_decision: f32,
+}
+
+impl AiPlayer {
+    /// Crée un joueur IA qui décidera de sa direction dès le prochain tick.
+    pub fn new(profile: AiProfile) -> Self {
+        Self {
+            profile,
+            sequence: 0,
+            path: NavPath::default(),
+            next_decision: 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(behavior: AiBehavior) -> AiProfile {
+        AiProfile {
+            name: "test".to_string(),
+            behavior,
+            reaction_time: 0.5,
+            aim_error: 0.0,
+            speed: 1.0,
+        }
+    }
+
+    fn profiles_file(name: &str, content: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("{name}_{}.ron", std::process::id()));
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    #[test]
+    fn only_high_ids_are_ai_clients() {
+        assert!(!is_ai_client(&1_700_000_000_000));
+        assert!(is_ai_client(&AI_CLIENT_ID_BASE));
+        assert!(is_ai_client(&(AI_CLIENT_ID_BASE + 3)));
+    }
+
+    #[test]
+    fn profiles_are_loaded_from_ron() {
+        let path = profiles_file(
+            "ai_profiles",
+            &ron::to_string(&AiProfile::defaults()).unwrap(),
+        );
+
+        let profiles = AiProfile::load_all(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(profiles, AiProfile::defaults());
+    }
+
+    #[test]
+    #[should_panic(expected = "contains no AI profile")]
+    fn empty_profile_list_is_rejected() {
+        let path = profiles_file("ai_profiles_empty", "[]");
+        let result = std::panic::catch_unwind(|| AiProfile::load_all(&path));
+        std::fs::remove_file(&path).unwrap();
+        std::panic::resume_unwind(result.unwrap_err());
+    }
+
+    #[test]
+    fn chasers_stop_near_their_target() {
+        let chase = profile(AiBehavior::Chase);
+        let target = Vec2::new(400.0, 0.0);
+
+        assert_eq!(chase.choose_goal(Vec2::ZERO, Some(target)), Some(target));
+        assert_eq!(chase.choose_goal(Vec2::new(350.0, 0.0), Some(target)), None);
+    }
+
+    #[test]
+    fn fleeing_players_move_away_then_come_back() {
+        let flee = profile(AiBehavior::Flee);
+
+        let goal = flee
+            .choose_goal(Vec2::new(100.0, 0.0), Some(Vec2::ZERO))
+            .unwrap();
+        assert!(goal.abs_diff_eq(Vec2::new(100.0 + AI_FLEE_DISTANCE, 0.0), 1e-3));
+
+        let far = Vec2::new(AI_WANDER_RADIUS + 50.0, 0.0);
+        assert_eq!(flee.choose_goal(far, Some(Vec2::ZERO)), Some(Vec2::ZERO));
+    }
+
+    #[test]
+    fn wanderers_stay_in_the_arena() {
+        for behavior in [AiBehavior::Wander, AiBehavior::Chase, AiBehavior::Flee] {
+            for _ in 0..100 {
+                let goal = profile(behavior).choose_goal(Vec2::ZERO, None).unwrap();
+                assert!(goal.length() <= AI_WANDER_RADIUS + 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn aim_error_keeps_the_distance() {
+        let chase = AiProfile {
+            aim_error: 0.5,
+            ..profile(AiBehavior::Chase)
+        };
+        let target = Vec2::new(400.0, 0.0);
+
+        for _ in 0..100 {
+            let goal = chase.choose_goal(Vec2::ZERO, Some(target)).unwrap();
+            assert!((goal.length() - 400.0).abs() < 1e-2);
+            assert!(goal.to_angle().abs() <= 0.5 + 1e-4);
+        }
+    }
+}
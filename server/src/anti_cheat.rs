@@ -0,0 +1,166 @@
+use game_core::projectile::FireCooldown;
+use std::fmt;
+
+/// Comportement suspect détecté dans les entrées d'un client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheatViolation {
+    /// Direction non finie ou de norme supérieure à 1.
+    MalformedInput,
+    /// Entrée dont le numéro de séquence n'est pas supérieur au précédent.
+    ReplayedInput,
+    /// Plus d'entrées que de ticks écoulés : manipulation du temps (speedhack).
+    Speedhack,
+    /// Tir plus rapproché du précédent que `FIRE_COOLDOWN_TICKS` entrées.
+    FireRate,
+}
+
+impl CheatViolation {
+    /// Points ajoutés au score de triche du client pour cette violation.
+    pub fn weight(self) -> f32 {
+        match self {
+            CheatViolation::MalformedInput => 2.0,
+            CheatViolation::ReplayedInput => 1.0,
+            CheatViolation::Speedhack => 1.0,
+            CheatViolation::FireRate => 1.0,
+        }
+    }
+}
+
+impl fmt::Display for CheatViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheatViolation::MalformedInput => write!(f, "malformed input"),
+            CheatViolation::ReplayedInput => write!(f, "replayed or out of order input"),
+            CheatViolation::Speedhack => write!(f, "more inputs than elapsed ticks"),
+            CheatViolation::FireRate => write!(f, "firing faster than the weapon allows"),
+        }
+    }
+}
+
+/// Suivi des entrées d'un client : crédit de ticks, dernière séquence acceptée et
+/// cadence de tir.
+///
+/// Chaque tick fixe du serveur accorde une entrée au client, dans la limite de
+/// `burst` entrées d'avance pour absorber la gigue du réseau. Un client qui envoie
+/// durablement plus d'une entrée par tick épuise son crédit.
+#[derive(Debug, Clone, Copy)]
+pub struct InputTracker {
+    credits: f32,
+    last_sequence: Option<u32>,
+    fire_cooldown: FireCooldown,
+}
+
+impl InputTracker {
+    /// Crée un suivi disposant d'un crédit plein.
+    pub fn new(burst: f32) -> Self {
+        Self {
+            credits: burst,
+            last_sequence: None,
+            fire_cooldown: FireCooldown::default(),
+        }
+    }
+
+    /// Accorde l'entrée d'un tick écoulé, sans dépasser `burst`.
+    pub fn tick(&mut self, burst: f32) {
+        self.credits = (self.credits + 1.0).min(burst);
+    }
+
+    /// Consomme le crédit d'une entrée de numéro `sequence`.
+    ///
+    /// Les numéros sont comparés modulo 2^32 : une entrée est plus récente si elle
+    /// suit la précédente de moins de 2^31 numéros, ce qui tolère le passage de
+    /// `u32::MAX` à 0.
+    ///
+    /// # Retourne
+    /// * `Result<(), CheatViolation>` - la violation constatée si l'entrée est rejouée
+    ///   ou si le client n'a plus de crédit ; l'entrée doit alors être ignorée.
+    pub fn accept(&mut self, sequence: u32) -> Result<(), CheatViolation> {
+        if self
+            .last_sequence
+            .is_some_and(|last| sequence.wrapping_sub(last) as i32 <= 0)
+        {
+            return Err(CheatViolation::ReplayedInput);
+        }
+        if self.credits < 1.0 {
+            return Err(CheatViolation::Speedhack);
+        }
+        self.credits -= 1.0;
+        self.last_sequence = Some(sequence);
+        Ok(())
+    }
+
+    /// Enregistre le tir de l'entrée `sequence`, déjà acceptée par `accept`.
+    ///
+    /// # Retourne
+    /// * `Result<(), CheatViolation>` - `FireRate` si le tir suit le précédent de moins
+    ///   de `FIRE_COOLDOWN_TICKS` entrées ; le tir doit alors être ignoré.
+    pub fn accept_fire(&mut self, sequence: u32) -> Result<(), CheatViolation> {
+        if self.fire_cooldown.fire(sequence) {
+            Ok(())
+        } else {
+            Err(CheatViolation::FireRate)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game_core::projectile::FIRE_COOLDOWN_TICKS;
+
+    #[test]
+    fn replayed_and_out_of_order_inputs_are_rejected() {
+        let mut tracker = InputTracker::new(8.0);
+
+        assert_eq!(tracker.accept(5), Ok(()));
+        assert_eq!(tracker.accept(5), Err(CheatViolation::ReplayedInput));
+        assert_eq!(tracker.accept(4), Err(CheatViolation::ReplayedInput));
+        assert_eq!(tracker.accept(7), Ok(()));
+    }
+
+    #[test]
+    fn credit_limits_inputs_to_elapsed_ticks() {
+        let burst = 3.0;
+        let mut tracker = InputTracker::new(burst);
+
+        for sequence in 0..3 {
+            assert_eq!(tracker.accept(sequence), Ok(()));
+        }
+        assert_eq!(tracker.accept(3), Err(CheatViolation::Speedhack));
+
+        tracker.tick(burst);
+        assert_eq!(tracker.accept(3), Ok(()));
+
+        // Le crédit ne dépasse jamais `burst`, même après une longue pause.
+        for _ in 0..100 {
+            tracker.tick(burst);
+        }
+        for sequence in 4..7 {
+            assert_eq!(tracker.accept(sequence), Ok(()));
+        }
+        assert_eq!(tracker.accept(7), Err(CheatViolation::Speedhack));
+    }
+
+    #[test]
+    fn sequence_wraps_around() {
+        let mut tracker = InputTracker::new(8.0);
+
+        assert_eq!(tracker.accept(u32::MAX - 1), Ok(()));
+        assert_eq!(tracker.accept(u32::MAX), Ok(()));
+        assert_eq!(tracker.accept(0), Ok(()));
+        assert_eq!(tracker.accept(1), Ok(()));
+        assert_eq!(tracker.accept(u32::MAX), Err(CheatViolation::ReplayedInput));
+    }
+
+    #[test]
+    fn fire_rate_is_limited() {
+        let mut tracker = InputTracker::new(8.0);
+
+        assert_eq!(tracker.accept_fire(10), Ok(()));
+        assert_eq!(
+            tracker.accept_fire(10 + FIRE_COOLDOWN_TICKS - 1),
+            Err(CheatViolation::FireRate)
+        );
+        assert_eq!(tracker.accept_fire(10 + FIRE_COOLDOWN_TICKS), Ok(()));
+    }
+}
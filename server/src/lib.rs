@@ -1,3 +1,10 @@
+pub mod admin;
+pub mod ai;
+pub mod anti_cheat;
+pub mod navigation;
+#[cfg(feature = "physics")]
+pub mod physics;
 pub mod plugin;
+pub mod rate_limit;
 pub mod resource;
 pub mod system;
@@ -1,7 +1,4 @@
-mod resource;
-mod system;
-
-use bevy::app::{App, PluginGroup, Startup};
+use bevy::app::{App, PluginGroup, Startup, TerminalCtrlCHandlerPlugin};
 use bevy::asset::AssetPlugin;
 use bevy::prelude::ImagePlugin;
 use bevy::utils::default;
@@ -17,8 +14,12 @@ use server::system::camera::spawn_camera;
 fn main() {
     let mut app = App::new();
 
+    // Le `ServerPlugin` installe son propre gestionnaire de Ctrl+C / SIGTERM pour un arrêt
+    // propre : celui de Bevy, qui quitte immédiatement, ne doit pas le précéder.
     app.add_plugins(
         DefaultPlugins
+            .build()
+            .disable::<TerminalCtrlCHandlerPlugin>()
             .set(WindowPlugin::default())
             .set(ImagePlugin::default_nearest())
             .set(AssetPlugin {
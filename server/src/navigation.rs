@@ -0,0 +1,600 @@
+use bevy::math::{IVec2, Vec2};
+use game_core::collision::ColliderShape;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, VecDeque};
+
+/// Côté d'une cellule de la `NavGrid` du serveur.
+pub const NAV_CELL_SIZE: f32 = 20.0;
+
+/// Distance à laquelle un point de passage intermédiaire est considéré atteint.
+pub const NAV_WAYPOINT_RADIUS: f32 = 10.0;
+
+/// Distance au dernier point d'un chemin en deçà de laquelle le joueur ralentit.
+pub const NAV_ARRIVAL_RADIUS: f32 = 40.0;
+
+/// Distance au dernier point d'un chemin en deçà de laquelle il est atteint.
+pub const NAV_ARRIVAL_TOLERANCE: f32 = 2.0;
+
+/// Les 8 voisins d'une cellule, avec le coût du déplacement (en cellules).
+const NEIGHBORS: [(IVec2, f32); 8] = [
+    (IVec2::new(1, 0), 1.0),
+    (IVec2::new(-1, 0), 1.0),
+    (IVec2::new(0, 1), 1.0),
+    (IVec2::new(0, -1), 1.0),
+    (IVec2::new(1, 1), std::f32::consts::SQRT_2),
+    (IVec2::new(1, -1), std::f32::consts::SQRT_2),
+    (IVec2::new(-1, 1), std::f32::consts::SQRT_2),
+    (IVec2::new(-1, -1), std::f32::consts::SQRT_2),
+];
+
+/// Grille de navigation : découpe l'arène en cellules praticables ou non.
+///
+/// Les obstacles sont élargis du rayon de l'agent : une cellule est praticable si
+/// un joueur centré sur elle ne chevauche ni un obstacle ni le bord de l'arène. Un
+/// chemin qui ne traverse que des cellules praticables est donc sûr pour le centre
+/// du joueur, à la résolution de la grille près.
+///
+/// Une grille vide (`NavGrid::default()`) ne contient aucune cellule.
+#[derive(Debug, Clone, Default)]
+pub struct NavGrid {
+    origin: Vec2,
+    cell_size: f32,
+    width: i32,
+    height: i32,
+    walkable: Vec<bool>,
+}
+
+impl NavGrid {
+    /// Construit la grille d'une arène centrée sur l'origine.
+    ///
+    /// - `half_size` : demi-dimensions de l'arène.
+    /// - `cell_size` : côté d'une cellule.
+    /// - `agent_radius` : rayon des joueurs qui suivront les chemins.
+    /// - `obstacles` : colliders statiques, donnés par leur centre et leur forme.
+    pub fn new<'a>(
+        half_size: Vec2,
+        cell_size: f32,
+        agent_radius: f32,
+        obstacles: impl IntoIterator<Item = (Vec2, &'a ColliderShape)>,
+    ) -> Self {
+        let width = (half_size.x * 2.0 / cell_size).ceil().max(0.0) as i32;
+        let height = (half_size.y * 2.0 / cell_size).ceil().max(0.0) as i32;
+        let mut grid = Self {
+            origin: -half_size,
+            cell_size,
+            width,
+            height,
+            walkable: Vec::with_capacity((width * height) as usize),
+        };
+
+        let inner = (half_size - Vec2::splat(agent_radius)).max(Vec2::ZERO);
+        for y in 0..height {
+            for x in 0..width {
+                let center = grid.cell_center(IVec2::new(x, y));
+                grid.walkable
+                    .push(center.x.abs() <= inner.x && center.y.abs() <= inner.y);
+            }
+        }
+
+        for (center, shape) in obstacles {
+            let half = shape.half_extents() + Vec2::splat(agent_radius);
+            let (Some(min), Some(max)) = (
+                grid.clamped_cell(center - half),
+                grid.clamped_cell(center + half),
+            ) else {
+                continue;
+            };
+            for y in min.y..=max.y {
+                for x in min.x..=max.x {
+                    let cell = IVec2::new(x, y);
+                    if shape
+                        .circle_pushout(center, grid.cell_center(cell), agent_radius)
+                        .is_some()
+                    {
+                        let index = grid.index(cell);
+                        grid.walkable[index] = false;
+                    }
+                }
+            }
+        }
+        grid
+    }
+
+    /// Indique si la grille ne contient aucune cellule.
+    pub fn is_empty(&self) -> bool {
+        self.walkable.is_empty()
+    }
+
+    /// Cellule contenant `position`, ou `None` hors de la grille.
+    pub fn cell_at(&self, position: Vec2) -> Option<IVec2> {
+        let cell = self.grid_coordinates(position).floor().as_ivec2();
+        self.contains(cell).then_some(cell)
+    }
+
+    /// Centre de `cell`.
+    pub fn cell_center(&self, cell: IVec2) -> Vec2 {
+        self.origin + (cell.as_vec2() + 0.5) * self.cell_size
+    }
+
+    /// Indique si `cell` existe et est praticable.
+    pub fn is_walkable(&self, cell: IVec2) -> bool {
+        self.contains(cell) && self.walkable[self.index(cell)]
+    }
+
+    /// Cellule praticable la plus proche de `position` (en anneaux de cellules
+    /// autour d'elle), ou `None` si la grille n'en contient aucune.
+    pub fn nearest_walkable(&self, position: Vec2) -> Option<IVec2> {
+        let start = self.clamped_cell(position)?;
+        let max_ring = self.width.max(self.height);
+        for ring in 0..=max_ring {
+            let nearest = (-ring..=ring)
+                .flat_map(|dy| (-ring..=ring).map(move |dx| IVec2::new(dx, dy)))
+                .filter(|offset| offset.x.abs() == ring || offset.y.abs() == ring)
+                .map(|offset| start + offset)
+                .filter(|cell| self.is_walkable(*cell))
+                .min_by(|a, b| {
+                    self.cell_center(*a)
+                        .distance_squared(position)
+                        .total_cmp(&self.cell_center(*b).distance_squared(position))
+                });
+            if nearest.is_some() {
+                return nearest;
+            }
+        }
+        None
+    }
+
+    /// Indique si le segment `from` → `to` ne traverse que des cellules praticables.
+    ///
+    /// Parcourt toutes les cellules traversées par le segment ; lorsqu'il passe
+    /// exactement par un coin, les deux cellules adjacentes doivent être praticables.
+    pub fn has_line_of_sight(&self, from: Vec2, to: Vec2) -> bool {
+        let start = self.grid_coordinates(from);
+        let end = self.grid_coordinates(to);
+        let mut cell = start.floor().as_ivec2();
+        let last = end.floor().as_ivec2();
+        if !self.is_walkable(cell) || !self.is_walkable(last) {
+            return false;
+        }
+
+        let delta = end - start;
+        let step = IVec2::new(delta.x.signum() as i32, delta.y.signum() as i32);
+        let axis = |start: f32, delta: f32, cell: i32| {
+            if delta > 0.0 {
+                ((cell as f32 + 1.0 - start) / delta, 1.0 / delta)
+            } else if delta < 0.0 {
+                ((start - cell as f32) / -delta, -1.0 / delta)
+            } else {
+                (f32::INFINITY, f32::INFINITY)
+            }
+        };
+        let (mut t_max_x, t_delta_x) = axis(start.x, delta.x, cell.x);
+        let (mut t_max_y, t_delta_y) = axis(start.y, delta.y, cell.y);
+
+        let steps = (last - cell).abs().element_sum();
+        for _ in 0..steps {
+            if cell == last {
+                break;
+            }
+            if (t_max_x - t_max_y).abs() <= 1e-6 {
+                if !self.is_walkable(cell + IVec2::new(step.x, 0))
+                    || !self.is_walkable(cell + IVec2::new(0, step.y))
+                {
+                    return false;
+                }
+                cell += step;
+                t_max_x += t_delta_x;
+                t_max_y += t_delta_y;
+            } else if t_max_x < t_max_y {
+                cell.x += step.x;
+                t_max_x += t_delta_x;
+            } else {
+                cell.y += step.y;
+                t_max_y += t_delta_y;
+            }
+            if !self.is_walkable(cell) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Chemin lissé de `from` vers `to`, sans le point de départ.
+    ///
+    /// Le chemin est calculé par A* sur les 8 voisins de chaque cellule, sans couper
+    /// les coins des obstacles, puis lissé en supprimant les points de passage
+    /// visibles depuis le précédent. Si `from` ou `to` tombe dans une cellule
+    /// impraticable, la cellule praticable la plus proche est utilisée à sa place.
+    ///
+    /// Retourne `None` si `from` et `to` ne sont pas reliés.
+    pub fn find_path(&self, from: Vec2, to: Vec2) -> Option<Vec<Vec2>> {
+        let start = self.snap(from)?;
+        let goal = self.snap(to)?;
+        let cells = self.astar(start, goal)?;
+
+        let goal_point = if self.cell_at(to) == Some(goal) {
+            to
+        } else {
+            self.cell_center(goal)
+        };
+        let mut points: Vec<Vec2> = cells
+            .iter()
+            .skip(1)
+            .map(|cell| self.cell_center(*cell))
+            .collect();
+        match points.last_mut() {
+            Some(last) => *last = goal_point,
+            None => points.push(goal_point),
+        }
+
+        if self.cell_at(from) == Some(start) {
+            return Some(self.smooth(from, points));
+        }
+        // Un départ impraticable rejoint d'abord le centre de sa cellule de substitution.
+        let center = self.cell_center(start);
+        let mut path = vec![center];
+        path.extend(self.smooth(center, points));
+        Some(path)
+    }
+
+    /// Cellules de `start` à `goal` (incluses) du plus court chemin, par A*.
+    fn astar(&self, start: IVec2, goal: IVec2) -> Option<Vec<IVec2>> {
+        let heuristic = |cell: IVec2| {
+            let d = (goal - cell).abs();
+            let (short, long) = (d.x.min(d.y) as f32, d.x.max(d.y) as f32);
+            long + (std::f32::consts::SQRT_2 - 1.0) * short
+        };
+
+        let mut costs = vec![f32::INFINITY; self.walkable.len()];
+        let mut came_from = vec![usize::MAX; self.walkable.len()];
+        let mut open = BinaryHeap::new();
+        costs[self.index(start)] = 0.0;
+        open.push(OpenCell {
+            estimate: heuristic(start),
+            cell: start,
+        });
+
+        while let Some(OpenCell { estimate, cell }) = open.pop() {
+            let index = self.index(cell);
+            if cell == goal {
+                let mut path = vec![cell];
+                let mut index = index;
+                while came_from[index] != usize::MAX {
+                    index = came_from[index];
+                    path.push(self.cell_of_index(index));
+                }
+                path.reverse();
+                return Some(path);
+            }
+            // Entrée périmée : la cellule a été atteinte depuis par un chemin plus court.
+            if estimate > costs[index] + heuristic(cell) {
+                continue;
+            }
+
+            for (offset, step_cost) in NEIGHBORS {
+                let next = cell + offset;
+                if !self.is_walkable(next) {
+                    continue;
+                }
+                // Un déplacement en diagonale ne coupe pas le coin d'un obstacle.
+                if offset.x != 0
+                    && offset.y != 0
+                    && (!self.is_walkable(cell + IVec2::new(offset.x, 0))
+                        || !self.is_walkable(cell + IVec2::new(0, offset.y)))
+                {
+                    continue;
+                }
+
+                let cost = costs[index] + step_cost;
+                let next_index = self.index(next);
+                if cost < costs[next_index] {
+                    costs[next_index] = cost;
+                    came_from[next_index] = index;
+                    open.push(OpenCell {
+                        estimate: cost + heuristic(next),
+                        cell: next,
+                    });
+                }
+            }
+        }
+        None
+    }
+
+    /// Supprime les points de passage visibles depuis le point précédent.
+    fn smooth(&self, from: Vec2, points: Vec<Vec2>) -> Vec<Vec2> {
+        let mut smoothed = Vec::new();
+        let mut anchor = from;
+        let mut next = 0;
+        while next < points.len() {
+            let mut farthest = points.len() - 1;
+            while farthest > next && !self.has_line_of_sight(anchor, points[farthest]) {
+                farthest -= 1;
+            }
+            anchor = points[farthest];
+            smoothed.push(anchor);
+            next = farthest + 1;
+        }
+        smoothed
+    }
+
+    /// Cellule praticable correspondant à `position` (voir `nearest_walkable`).
+    fn snap(&self, position: Vec2) -> Option<IVec2> {
+        self.cell_at(position)
+            .filter(|cell| self.is_walkable(*cell))
+            .or_else(|| self.nearest_walkable(position))
+    }
+
+    fn grid_coordinates(&self, position: Vec2) -> Vec2 {
+        (position - self.origin) / self.cell_size
+    }
+
+    /// Cellule de la grille la plus proche de `position`, ou `None` si la grille est vide.
+    fn clamped_cell(&self, position: Vec2) -> Option<IVec2> {
+        if self.is_empty() {
+            return None;
+        }
+        let cell = self.grid_coordinates(position).floor().as_ivec2();
+        Some(cell.clamp(IVec2::ZERO, IVec2::new(self.width - 1, self.height - 1)))
+    }
+
+    fn contains(&self, cell: IVec2) -> bool {
+        cell.x >= 0 && cell.y >= 0 && cell.x < self.width && cell.y < self.height
+    }
+
+    fn index(&self, cell: IVec2) -> usize {
+        (cell.y * self.width + cell.x) as usize
+    }
+
+    fn cell_of_index(&self, index: usize) -> IVec2 {
+        IVec2::new(index as i32 % self.width, index as i32 / self.width)
+    }
+}
+
+/// Cellule de la file de priorité de l'A*, ordonnée par estimation croissante.
+#[derive(Debug, PartialEq)]
+struct OpenCell {
+    estimate: f32,
+    cell: IVec2,
+}
+
+impl Eq for OpenCell {}
+
+impl Ord for OpenCell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.total_cmp(&self.estimate)
+    }
+}
+
+impl PartialOrd for OpenCell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Chemin suivi par un joueur, converti en direction de déplacement à chaque tick.
+#[derive(Debug, Clone, Default)]
+pub struct NavPath {
+    waypoints: VecDeque<Vec2>,
+}
+
+impl NavPath {
+    /// Chemin passant par `waypoints` (voir `NavGrid::find_path`).
+    pub fn new(waypoints: Vec<Vec2>) -> Self {
+        Self {
+            waypoints: waypoints.into(),
+        }
+    }
+
+    /// Indique si le chemin est terminé.
+    pub fn is_finished(&self) -> bool {
+        self.waypoints.is_empty()
+    }
+
+    /// Direction vers le prochain point de passage depuis `position`, de norme au plus 1.
+    ///
+    /// Les points atteints sont retirés du chemin. Le joueur ralentit à l'approche
+    /// du dernier point, puis s'arrête.
+    pub fn steer(&mut self, position: Vec2) -> Vec2 {
+        while self.waypoints.len() > 1
+            && self.waypoints[0].distance(position) <= NAV_WAYPOINT_RADIUS
+        {
+            self.waypoints.pop_front();
+        }
+        let Some(&next) = self.waypoints.front() else {
+            return Vec2::ZERO;
+        };
+
+        let offset = next - position;
+        let distance = offset.length();
+        if self.waypoints.len() > 1 {
+            return offset / distance;
+        }
+        if distance <= NAV_ARRIVAL_TOLERANCE {
+            self.waypoints.clear();
+            return Vec2::ZERO;
+        }
+        offset / distance * (distance / NAV_ARRIVAL_RADIUS).min(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HALF_SIZE: Vec2 = Vec2::new(500.0, 500.0);
+    const AGENT_RADIUS: f32 = 20.0;
+
+    /// Grille d'une carte dont les obstacles sont des rectangles `(centre, demi-dimensions)`.
+    fn grid_with_radius(obstacles: &[(Vec2, Vec2)], agent_radius: f32) -> NavGrid {
+        let shapes: Vec<(Vec2, ColliderShape)> = obstacles
+            .iter()
+            .map(|&(center, half_size)| (center, ColliderShape::Aabb { half_size }))
+            .collect();
+        NavGrid::new(
+            HALF_SIZE,
+            NAV_CELL_SIZE,
+            agent_radius,
+            shapes.iter().map(|(center, shape)| (*center, shape)),
+        )
+    }
+
+    fn grid(obstacles: &[(Vec2, Vec2)]) -> NavGrid {
+        grid_with_radius(obstacles, AGENT_RADIUS)
+    }
+
+    fn path_length(from: Vec2, path: &[Vec2]) -> f32 {
+        std::iter::once(from)
+            .chain(path.iter().copied())
+            .zip(path.iter().copied())
+            .map(|(a, b)| a.distance(b))
+            .sum()
+    }
+
+    /// Vérifie qu'un agent suivant le chemin ne chevauche aucun obstacle, à la
+    /// résolution de la grille près.
+    fn assert_path_avoids(from: Vec2, path: &[Vec2], obstacles: &[(Vec2, Vec2)]) {
+        let clearance = AGENT_RADIUS - NAV_CELL_SIZE / 2.0;
+        let mut previous = from;
+        for &point in path {
+            let samples = previous.distance(point).ceil() as usize;
+            for sample in 0..=samples {
+                let position = previous.lerp(point, sample as f32 / samples.max(1) as f32);
+                for &(center, half_size) in obstacles {
+                    assert_eq!(
+                        ColliderShape::Aabb { half_size }
+                            .circle_pushout(center, position, clearance),
+                        None,
+                        "segment {previous} -> {point} crosses obstacle at {center}"
+                    );
+                }
+            }
+            previous = point;
+        }
+    }
+
+    #[test]
+    fn open_map_path_is_a_straight_line() {
+        let grid = grid(&[]);
+        let (from, to) = (Vec2::new(-300.0, -200.0), Vec2::new(250.0, 310.0));
+
+        assert_eq!(grid.find_path(from, to), Some(vec![to]));
+    }
+
+    #[test]
+    fn path_goes_around_a_wall() {
+        let wall = (Vec2::ZERO, Vec2::new(20.0, 300.0));
+        let grid = grid(&[wall]);
+        let (from, to) = (Vec2::new(-200.0, 0.0), Vec2::new(200.0, 0.0));
+
+        assert!(!grid.has_line_of_sight(from, to));
+        let path = grid.find_path(from, to).expect("a path around the wall");
+
+        assert_eq!(path.last(), Some(&to));
+        assert_path_avoids(from, &path, &[wall]);
+        // Contourner le mur par l'une de ses extrémités : deux points de passage suffisent.
+        assert!(path.len() <= 3, "path not smoothed: {path:?}");
+        let around = 2.0 * Vec2::new(200.0, 340.0).length();
+        assert!(path_length(from, &path) < around * 1.1);
+    }
+
+    #[test]
+    fn path_follows_a_corridor() {
+        // Deux murs décalés forment un couloir en S.
+        let walls = [
+            (Vec2::new(-100.0, 100.0), Vec2::new(400.0, 20.0)),
+            (Vec2::new(100.0, -100.0), Vec2::new(400.0, 20.0)),
+        ];
+        let grid = grid(&walls);
+        let (from, to) = (Vec2::new(-300.0, -300.0), Vec2::new(300.0, 300.0));
+
+        let path = grid
+            .find_path(from, to)
+            .expect("a path through the corridor");
+
+        assert_eq!(path.last(), Some(&to));
+        assert_path_avoids(from, &path, &walls);
+        let mut previous = from;
+        for &point in &path {
+            assert!(grid.has_line_of_sight(previous, point));
+            previous = point;
+        }
+    }
+
+    #[test]
+    fn enclosed_goal_has_no_path() {
+        let room = [
+            (Vec2::new(0.0, 150.0), Vec2::new(170.0, 20.0)),
+            (Vec2::new(0.0, -150.0), Vec2::new(170.0, 20.0)),
+            (Vec2::new(150.0, 0.0), Vec2::new(20.0, 170.0)),
+            (Vec2::new(-150.0, 0.0), Vec2::new(20.0, 170.0)),
+        ];
+        let grid = grid(&room);
+
+        assert_eq!(grid.find_path(Vec2::new(-400.0, 0.0), Vec2::ZERO), None);
+        assert_eq!(grid.find_path(Vec2::ZERO, Vec2::new(-400.0, 0.0)), None);
+        assert!(grid.find_path(Vec2::ZERO, Vec2::new(40.0, 40.0)).is_some());
+    }
+
+    #[test]
+    fn diagonal_does_not_cut_corners() {
+        // Sans élargissement, deux obstacles se touchant par un coin ferment le passage.
+        let obstacles = [
+            (Vec2::new(-50.0, 50.0), Vec2::splat(50.0)),
+            (Vec2::new(50.0, -50.0), Vec2::splat(50.0)),
+        ];
+        let grid = grid_with_radius(&obstacles, 0.0);
+        let (from, to) = (Vec2::new(-50.0, -50.0), Vec2::new(50.0, 50.0));
+
+        assert!(!grid.has_line_of_sight(from, to));
+        let path = grid
+            .find_path(from, to)
+            .expect("a path around both obstacles");
+        assert!(path.len() > 1, "path cuts through the corner: {path:?}");
+    }
+
+    #[test]
+    fn blocked_goal_is_replaced_by_nearest_walkable_cell() {
+        let wall = (Vec2::new(200.0, 0.0), Vec2::new(40.0, 40.0));
+        let grid = grid(&[wall]);
+        let goal = Vec2::new(200.0, 0.0);
+
+        let path = grid.find_path(Vec2::new(-200.0, 0.0), goal).unwrap();
+        let last = *path.last().unwrap();
+
+        assert!(grid.is_walkable(grid.cell_at(last).unwrap()));
+        assert!(last.distance(goal) <= 40.0 + AGENT_RADIUS + NAV_CELL_SIZE);
+    }
+
+    #[test]
+    fn path_starting_inside_an_obstacle_leaves_it_first() {
+        let wall = (Vec2::ZERO, Vec2::new(40.0, 40.0));
+        let grid = grid(&[wall]);
+        let from = Vec2::new(45.0, 0.0);
+        let to = Vec2::new(300.0, 0.0);
+
+        let path = grid.find_path(from, to).unwrap();
+
+        assert!(grid.is_walkable(grid.cell_at(path[0]).unwrap()));
+        assert_eq!(path.last(), Some(&to));
+    }
+
+    #[test]
+    fn steering_follows_waypoints_and_stops() {
+        let mut path = NavPath::new(vec![Vec2::new(100.0, 0.0), Vec2::new(100.0, 100.0)]);
+        let mut position = Vec2::ZERO;
+
+        assert_eq!(path.steer(position), Vec2::X);
+        for _ in 0..1000 {
+            let direction = path.steer(position);
+            assert!(direction.length() <= 1.0 + 1e-5);
+            if path.is_finished() {
+                break;
+            }
+            position += direction * 5.0;
+        }
+
+        assert!(path.is_finished());
+        assert!(position.distance(Vec2::new(100.0, 100.0)) <= NAV_ARRIVAL_TOLERANCE);
+        assert_eq!(path.steer(position), Vec2::ZERO);
+    }
+}
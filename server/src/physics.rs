@@ -0,0 +1,326 @@
+use bevy::math::Vec2;
+use bevy::prelude::{Entity, Message};
+use game_core::collision::ColliderShape;
+use game_core::physics::{BodyKind, Velocity};
+use rapier2d::prelude::{ColliderBuilder, PhysicsWorld, RigidBodyBuilder, RigidBodyHandle, Vector};
+use std::collections::HashMap;
+
+/// Unités de jeu par mètre du moteur physique : les tolérances de rapier sont
+/// exprimées en mètres, les positions de la partie en pixels.
+pub const PHYSICS_LENGTH_UNIT: f32 = 100.0;
+
+/// Amortissement linéaire des corps dynamiques : le frottement du sol, vu de dessus.
+pub const BODY_LINEAR_DAMPING: f32 = 4.0;
+
+/// Amortissement angulaire des corps dynamiques.
+pub const BODY_ANGULAR_DAMPING: f32 = 4.0;
+
+/// Demi-épaisseur des bords fixes qui entourent l'arène.
+const ARENA_BORDER_HALF_THICKNESS: f32 = 500.0;
+
+/// Projette un corps dynamique : `velocity` s'ajoute à sa vitesse, quelle que soit
+/// sa masse.
+///
+/// Sans effet sur les joueurs, déplacés uniquement par leurs entrées.
+#[derive(Debug, Clone, Copy, Message)]
+pub struct Knockback {
+    pub entity: Entity,
+    pub velocity: Vec2,
+}
+
+/// État d'un corps après un pas de simulation.
+///
+/// - `position` : centre du corps.
+/// - `rotation` : angle autour de l'axe Z, en radians.
+/// - `velocity` : vitesses linéaire et angulaire du corps.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BodyState {
+    pub position: Vec2,
+    pub rotation: f32,
+    pub velocity: Velocity,
+}
+
+/// Simulation rapier des entités du serveur, vue de dessus et sans gravité.
+///
+/// Chaque entité simulée a un corps rapier : les `PhysicsBody` gardent leur type,
+/// les murs sont fixes et les joueurs sont des corps cinématiques qui poussent les
+/// corps dynamiques sans jamais être poussés. L'arène est bordée de corps fixes.
+pub struct PhysicsSimulation {
+    world: PhysicsWorld,
+    bodies: HashMap<Entity, RigidBodyHandle>,
+}
+
+impl Default for PhysicsSimulation {
+    /// Simulation vide, sans bords d'arène.
+    fn default() -> Self {
+        let mut world = PhysicsWorld {
+            gravity: Vector::ZERO,
+            ..PhysicsWorld::default()
+        };
+        world.integration_parameters.length_unit = PHYSICS_LENGTH_UNIT;
+        Self {
+            world,
+            bodies: HashMap::new(),
+        }
+    }
+}
+
+impl PhysicsSimulation {
+    /// Simulation d'une arène de demi-dimensions `arena_half_size`, centrée sur
+    /// l'origine.
+    pub fn new(arena_half_size: Vec2) -> Self {
+        let mut simulation = Self::default();
+        let thickness = ARENA_BORDER_HALF_THICKNESS;
+        let horizontal = Vec2::new(arena_half_size.x + 2.0 * thickness, thickness);
+        let vertical = Vec2::new(thickness, arena_half_size.y + 2.0 * thickness);
+        for (center, half_size) in [
+            (Vec2::new(0.0, arena_half_size.y + thickness), horizontal),
+            (Vec2::new(0.0, -arena_half_size.y - thickness), horizontal),
+            (Vec2::new(arena_half_size.x + thickness, 0.0), vertical),
+            (Vec2::new(-arena_half_size.x - thickness, 0.0), vertical),
+        ] {
+            simulation.world.insert(
+                RigidBodyBuilder::fixed().translation(vector(center)),
+                ColliderBuilder::cuboid(half_size.x, half_size.y),
+            );
+        }
+        simulation
+    }
+
+    /// Nombre d'entités simulées.
+    pub fn len(&self) -> usize {
+        self.bodies.len()
+    }
+
+    /// Indique si aucune entité n'est simulée.
+    pub fn is_empty(&self) -> bool {
+        self.bodies.is_empty()
+    }
+
+    /// Indique si `entity` est simulée.
+    pub fn contains(&self, entity: Entity) -> bool {
+        self.bodies.contains_key(&entity)
+    }
+
+    /// Simule `entity`, un corps de type `kind` et de forme `shape` centré en
+    /// `position`, tourné de `rotation` radians. Remplace son corps précédent.
+    ///
+    /// Retourne `false`, sans rien simuler, si rapier refuse le polygone de `shape`.
+    pub fn insert_body(
+        &mut self,
+        entity: Entity,
+        kind: BodyKind,
+        shape: &ColliderShape,
+        position: Vec2,
+        rotation: f32,
+    ) -> bool {
+        let collider = match shape {
+            ColliderShape::Aabb { half_size } => {
+                Some(ColliderBuilder::cuboid(half_size.x, half_size.y))
+            }
+            ColliderShape::Polygon { points } => {
+                ColliderBuilder::convex_polyline(points.iter().copied().map(vector).collect())
+            }
+        };
+        let Some(collider) = collider else {
+            return false;
+        };
+        let body = match kind {
+            BodyKind::Dynamic => RigidBodyBuilder::dynamic()
+                .linear_damping(BODY_LINEAR_DAMPING)
+                .angular_damping(BODY_ANGULAR_DAMPING),
+            BodyKind::Fixed => RigidBodyBuilder::fixed(),
+        };
+        self.insert(
+            entity,
+            body.translation(vector(position)).rotation(rotation),
+            collider,
+        );
+        true
+    }
+
+    /// Simule le joueur `entity`, un disque de rayon `radius` centré en `position`.
+    pub fn insert_player(&mut self, entity: Entity, position: Vec2, radius: f32) {
+        self.insert(
+            entity,
+            RigidBodyBuilder::kinematic_position_based().translation(vector(position)),
+            ColliderBuilder::ball(radius),
+        );
+    }
+
+    /// Retire `entity` de la simulation.
+    pub fn remove(&mut self, entity: Entity) {
+        if let Some(handle) = self.bodies.remove(&entity) {
+            self.world.remove_body(handle);
+        }
+    }
+
+    /// Déplace le joueur `entity` vers `position` au prochain pas : les corps
+    /// dynamiques sur son chemin sont poussés.
+    pub fn move_player(&mut self, entity: Entity, position: Vec2) {
+        if let Some(body) = self
+            .bodies
+            .get(&entity)
+            .and_then(|handle| self.world.bodies.get_mut(*handle))
+        {
+            body.set_next_kinematic_translation(vector(position));
+        }
+    }
+
+    /// Ajoute `velocity` à la vitesse du corps dynamique `entity`.
+    ///
+    /// Retourne `false` si `entity` n'est pas un corps dynamique simulé.
+    pub fn knockback(&mut self, entity: Entity, velocity: Vec2) -> bool {
+        let Some(body) = self
+            .bodies
+            .get(&entity)
+            .and_then(|handle| self.world.bodies.get_mut(*handle))
+            .filter(|body| body.is_dynamic())
+        else {
+            return false;
+        };
+        body.set_linvel(body.linvel() + vector(velocity), true);
+        true
+    }
+
+    /// Avance la simulation de `delta_secs` secondes.
+    pub fn step(&mut self, delta_secs: f32) {
+        self.world.integration_parameters.dt = delta_secs;
+        self.world.step();
+    }
+
+    /// État du corps de `entity`, si elle est simulée.
+    pub fn body_state(&self, entity: Entity) -> Option<BodyState> {
+        let body = self.world.bodies.get(*self.bodies.get(&entity)?)?;
+        Some(BodyState {
+            position: vec2(body.translation()),
+            rotation: body.rotation().angle(),
+            velocity: Velocity {
+                linear: vec2(body.linvel()),
+                angular: body.angvel(),
+            },
+        })
+    }
+
+    fn insert(&mut self, entity: Entity, body: RigidBodyBuilder, collider: ColliderBuilder) {
+        self.remove(entity);
+        let (handle, _) = self.world.insert(body, collider);
+        self.bodies.insert(entity, handle);
+    }
+}
+
+/// Convertit un vecteur de Bevy en vecteur de rapier, qui n'utilise pas la même
+/// version de `glam`.
+fn vector(v: Vec2) -> Vector {
+    Vector::new(v.x, v.y)
+}
+
+/// Convertit un vecteur de rapier en vecteur de Bevy.
+fn vec2(v: Vector) -> Vec2 {
+    Vec2::new(v.x, v.y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STEP: f32 = 1.0 / 64.0;
+
+    fn crate_shape() -> ColliderShape {
+        ColliderShape::Aabb {
+            half_size: Vec2::splat(30.0),
+        }
+    }
+
+    #[test]
+    fn player_pushes_dynamic_body() {
+        let mut simulation = PhysicsSimulation::new(Vec2::new(1000.0, 700.0));
+        let crate_entity = Entity::from_raw_u32(1).unwrap();
+        let player = Entity::from_raw_u32(2).unwrap();
+        simulation.insert_body(
+            crate_entity,
+            BodyKind::Dynamic,
+            &crate_shape(),
+            Vec2::ZERO,
+            0.0,
+        );
+        simulation.insert_player(player, Vec2::new(-100.0, 0.0), 40.0);
+
+        for tick in 1..=64 {
+            simulation.move_player(player, Vec2::new(-100.0 + tick as f32 * 3.0, 0.0));
+            simulation.step(STEP);
+        }
+
+        let state = simulation.body_state(crate_entity).unwrap();
+        assert!(state.position.x > 80.0, "{state:?}");
+        assert!(state.position.y.abs() < 5.0, "{state:?}");
+    }
+
+    #[test]
+    fn knockback_only_moves_dynamic_bodies() {
+        let mut simulation = PhysicsSimulation::new(Vec2::new(1000.0, 700.0));
+        let crate_entity = Entity::from_raw_u32(1).unwrap();
+        let pillar = Entity::from_raw_u32(2).unwrap();
+        let player = Entity::from_raw_u32(3).unwrap();
+        simulation.insert_body(
+            crate_entity,
+            BodyKind::Dynamic,
+            &crate_shape(),
+            Vec2::ZERO,
+            0.0,
+        );
+        simulation.insert_body(
+            pillar,
+            BodyKind::Fixed,
+            &crate_shape(),
+            Vec2::new(0.0, 300.0),
+            0.0,
+        );
+        simulation.insert_player(player, Vec2::new(0.0, -300.0), 40.0);
+
+        assert!(simulation.knockback(crate_entity, Vec2::new(200.0, 0.0)));
+        assert!(!simulation.knockback(pillar, Vec2::new(200.0, 0.0)));
+        assert!(!simulation.knockback(player, Vec2::new(200.0, 0.0)));
+        simulation.step(STEP);
+
+        let state = simulation.body_state(crate_entity).unwrap();
+        assert!(state.velocity.linear.x > 150.0, "{state:?}");
+        assert!(state.position.x > 0.0, "{state:?}");
+        let pillar = simulation.body_state(pillar).unwrap();
+        assert_eq!(pillar.position, Vec2::new(0.0, 300.0));
+    }
+
+    #[test]
+    fn arena_borders_stop_bodies() {
+        let mut simulation = PhysicsSimulation::new(Vec2::new(200.0, 200.0));
+        let crate_entity = Entity::from_raw_u32(1).unwrap();
+        simulation.insert_body(
+            crate_entity,
+            BodyKind::Dynamic,
+            &crate_shape(),
+            Vec2::ZERO,
+            0.0,
+        );
+
+        simulation.knockback(crate_entity, Vec2::new(3000.0, 0.0));
+        for _ in 0..128 {
+            simulation.step(STEP);
+        }
+
+        let state = simulation.body_state(crate_entity).unwrap();
+        assert!(state.position.x <= 171.0, "{state:?}");
+    }
+
+    #[test]
+    fn removed_entities_are_not_simulated() {
+        let mut simulation = PhysicsSimulation::new(Vec2::new(1000.0, 700.0));
+        let player = Entity::from_raw_u32(1).unwrap();
+        simulation.insert_player(player, Vec2::ZERO, 40.0);
+        assert!(simulation.contains(player));
+
+        simulation.remove(player);
+
+        assert!(simulation.is_empty());
+        assert!(simulation.body_state(player).is_none());
+    }
+}
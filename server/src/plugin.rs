@@ -1,2 +1,4 @@
 pub mod game_plugin;
+#[cfg(feature = "physics")]
+pub mod physics_plugin;
 pub mod server_plugin;
@@ -1,6 +1,23 @@
-use crate::system::game_event::on_game_event;
-use bevy::prelude::{App, Plugin, Update};
+use crate::ai::AiRequest;
+#[cfg(feature = "physics")]
+use crate::plugin::physics_plugin::PhysicsPlugin;
+use crate::resource::{AiPlayers, CheatScores, InputTrackers, Navigation, PlayerActivity};
+use crate::system::ai::{drive_ai_players, on_ai_request, request_initial_ai_players};
+use crate::system::game_event::{
+    expire_disconnected_players, kick_idle_players, on_client_leave, on_game_event,
+    update_waiting_queue,
+};
+use crate::system::map::spawn_map;
+use crate::system::movement::{
+    decay_cheat_scores, grant_input_ticks, move_players, resolve_collisions, validate_player_inputs,
+};
+use crate::system::navigation::update_navigation_grid;
+use crate::system::projectile::{move_projectiles, spawn_projectiles};
+use crate::system::spectator::on_client_spectate;
+use bevy::prelude::IntoScheduleConfigs;
+use bevy::prelude::{App, FixedUpdate, Plugin, Startup, Update};
 use game_core::event::game_event::GameEvent;
+use game_core::event::server_event::ShotFired;
 
 pub struct GamePlugin;
 
@@ -8,6 +25,44 @@ impl Plugin for GamePlugin {
     fn build(&self, app: &mut App) {
         app.add_message::<GameEvent>();
 
-        app.add_systems(Update, on_game_event);
+        app.add_systems(
+            Update,
+            (
+                on_game_event,
+                on_client_spectate,
+                on_client_leave,
+                expire_disconnected_players,
+                update_waiting_queue,
+                kick_idle_players,
+            )
+                .chain(),
+        );
+
+        app.insert_resource(InputTrackers::default());
+        app.insert_resource(CheatScores::default());
+        app.insert_resource(PlayerActivity::default());
+        app.add_systems(
+            FixedUpdate,
+            (
+                grant_input_ticks,
+                (move_players, resolve_collisions, move_projectiles).chain(),
+            ),
+        );
+
+        app.insert_resource(Navigation::default());
+        app.add_systems(Startup, spawn_map);
+        app.add_systems(Update, update_navigation_grid);
+        #[cfg(feature = "physics")]
+        app.add_plugins(PhysicsPlugin);
+
+        app.add_message::<AiRequest>();
+        app.insert_resource(AiPlayers::default());
+        app.add_systems(Startup, request_initial_ai_players);
+        app.add_systems(Update, on_ai_request.after(on_game_event));
+        app.add_systems(FixedUpdate, drive_ai_players.after(grant_input_ticks));
+        app.add_systems(Update, (validate_player_inputs, decay_cheat_scores));
+
+        app.add_message::<ShotFired>();
+        app.add_systems(Update, spawn_projectiles.after(validate_player_inputs));
     }
 }
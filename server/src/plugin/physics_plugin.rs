@@ -0,0 +1,31 @@
+use crate::physics::Knockback;
+use crate::resource::Physics;
+use crate::system::movement::{resolve_collisions, separate_players_from_bodies};
+use crate::system::physics::{setup_physics, step_physics, sync_physics_bodies};
+use bevy::prelude::IntoScheduleConfigs;
+use bevy::prelude::{App, FixedUpdate, Plugin, Startup};
+
+/// Simulation physique des caisses et autres `PhysicsBody` (feature `physics`).
+///
+/// Chaque pas de `FixedUpdate` suit la résolution des collisions des joueurs, qui
+/// poussent ainsi les corps dynamiques depuis leur position définitive ; les
+/// joueurs sont ensuite repoussés hors des corps qu'ils n'ont pas pu déplacer.
+pub struct PhysicsPlugin;
+
+impl Plugin for PhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_message::<Knockback>();
+        app.insert_resource(Physics::default());
+        app.add_systems(Startup, setup_physics);
+        app.add_systems(
+            FixedUpdate,
+            (
+                sync_physics_bodies,
+                step_physics,
+                separate_players_from_bodies,
+            )
+                .chain()
+                .after(resolve_collisions),
+        );
+    }
+}
@@ -1,11 +1,33 @@
-use crate::resource::ServerLobby;
+use crate::admin::AdminCommandRequest;
+use crate::resource::{
+    AccessList, ChatRateLimiter, ClientRateLimits, PendingDisconnects, ReplayRecorder,
+    ReplicationState, ServerLobby, ServerMetrics, ServerSettings, ServerTick, ShutdownSignal,
+    ShutdownState, WaitingQueue,
+};
+use crate::system::access_list::persist_access_list;
+use crate::system::admin::{execute_admin_commands, read_admin_console, spawn_admin_console};
+use crate::system::chat::on_client_chat;
+use crate::system::client_message::receive_client_messages;
+use crate::system::disconnect::{on_disconnect_client, process_pending_disconnects};
+use crate::system::game_event::on_game_event;
+use crate::system::replay::{advance_server_tick, start_replay_recording, write_replay};
+use crate::system::replication::replicate_components;
 use crate::system::server_event::on_server_event;
-use bevy::app::{App, Plugin, Update};
+use crate::system::shutdown::{
+    finish_shutdown, install_signal_handler, on_shutdown_request, on_shutdown_signal,
+};
+use bevy::app::{App, FixedUpdate, Plugin, PostUpdate, PreUpdate, Startup, Update};
+use bevy::prelude::IntoScheduleConfigs;
 use bevy_renet::netcode::{
     NetcodeServerPlugin, NetcodeServerTransport, ServerAuthentication, ServerConfig,
 };
 use bevy_renet::renet::RenetServer;
+use bevy_renet::{RenetReceive, RenetSend};
+use game_core::event::server_event::{
+    ClientMessageReceived, DisconnectClient, PersistState, PlayerInputReceived, ShutdownRequest,
+};
 use game_core::network::{connection_config, get_current_time, get_socket, PROTOCOL_ID};
+use game_core::replication::ReplicationPlugin;
 
 pub struct ServerPlugin;
 
@@ -14,12 +36,38 @@ impl Plugin for ServerPlugin {
         app.add_plugins(NetcodeServerPlugin);
 
         build_server_transport(app);
+        build_shutdown(app);
+
+        app.add_plugins(ReplicationPlugin);
+        app.insert_resource(ReplicationState::default());
+        app.add_systems(PostUpdate, replicate_components.before(RenetSend));
+        build_replay(app);
+
+        app.add_message::<DisconnectClient>();
+        app.insert_resource(PendingDisconnects::default());
 
-        app.add_systems(Update, on_server_event);
+        app.add_message::<ClientMessageReceived>();
+        app.add_message::<PlayerInputReceived>();
+        app.insert_resource(ClientRateLimits::default());
+        app.insert_resource(ServerMetrics::default());
+        app.add_systems(PreUpdate, receive_client_messages.after(RenetReceive));
+
+        app.insert_resource(ChatRateLimiter::default());
+        build_admin(app);
+
+        // Les `PlayerCreate` doivent partir dans la même frame que la création du
+        // joueur, avant la réplication de son entité (`PostUpdate`).
+        app.add_systems(Update, on_server_event.after(on_game_event));
+        app.add_systems(Update, on_client_chat);
+        app.add_systems(
+            Update,
+            (on_disconnect_client, process_pending_disconnects).chain(),
+        );
     }
 }
 
 fn build_server_transport(app: &mut App) {
+    let settings = ServerSettings::from_env();
     let server = RenetServer::new(connection_config());
     let public_addr = "127.0.0.1:5000"
         .parse()
@@ -29,7 +77,7 @@ fn build_server_transport(app: &mut App) {
 
     let server_config = ServerConfig {
         current_time,
-        max_clients: 64,
+        max_clients: settings.max_clients,
         protocol_id: PROTOCOL_ID,
         public_addresses: vec![public_addr],
         authentication: ServerAuthentication::Unsecure,
@@ -40,4 +88,50 @@ fn build_server_transport(app: &mut App) {
     app.insert_resource(server);
     app.insert_resource(transport);
     app.insert_resource(ServerLobby::default());
+    app.insert_resource(WaitingQueue::default());
+    app.insert_resource(settings);
+}
+
+fn build_shutdown(app: &mut App) {
+    let signal = ShutdownSignal::default();
+    install_signal_handler(&signal);
+
+    app.add_message::<ShutdownRequest>();
+    app.add_message::<PersistState>();
+    app.insert_resource(signal);
+    app.insert_resource(ShutdownState::default());
+
+    app.add_systems(
+        Update,
+        (on_shutdown_signal, on_shutdown_request, finish_shutdown).chain(),
+    );
+}
+
+fn build_admin(app: &mut App) {
+    app.add_message::<AdminCommandRequest>();
+    let access_list = AccessList::load(&app.world().resource::<ServerSettings>().access_list_path);
+    app.insert_resource(access_list);
+    app.insert_resource(spawn_admin_console());
+
+    app.add_systems(
+        Update,
+        (read_admin_console, execute_admin_commands)
+            .chain()
+            .after(on_client_chat),
+    );
+    app.add_systems(
+        Update,
+        persist_access_list
+            .after(execute_admin_commands)
+            .after(finish_shutdown),
+    );
+}
+
+fn build_replay(app: &mut App) {
+    app.insert_resource(ServerTick::default());
+    app.insert_resource(ReplayRecorder::default());
+
+    app.add_systems(FixedUpdate, advance_server_tick);
+    app.add_systems(Startup, start_replay_recording);
+    app.add_systems(PostUpdate, write_replay.after(replicate_components));
 }
@@ -0,0 +1,164 @@
+/// Réaction du serveur lorsqu'un client dépasse la limite de débit d'un canal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FloodResponse {
+    /// Ignore silencieusement les messages excédentaires.
+    Drop,
+    /// Ignore les messages excédentaires et journalise un avertissement.
+    Warn,
+    /// Ignore les messages excédentaires et déconnecte le client.
+    Disconnect,
+}
+
+/// Limites de débit d'un canal client.
+///
+/// - `messages_per_second` / `message_burst` : débit soutenu et rafale maximale
+///   en nombre de messages.
+/// - `bytes_per_second` / `byte_burst` : débit soutenu et rafale maximale en octets.
+/// - `response` : réaction en cas de dépassement.
+#[derive(Debug, Clone, Copy)]
+pub struct ChannelLimits {
+    pub messages_per_second: f32,
+    pub message_burst: f32,
+    pub bytes_per_second: f32,
+    pub byte_burst: f32,
+    pub response: FloodResponse,
+}
+
+/// Seau à jetons : autorise une rafale de `capacity` unités, puis un débit
+/// soutenu de `refill_per_second` unités par seconde.
+#[derive(Debug, Clone, Copy)]
+pub struct TokenBucket {
+    capacity: f32,
+    refill_per_second: f32,
+    tokens: f32,
+}
+
+impl TokenBucket {
+    /// Crée un seau plein.
+    pub fn new(capacity: f32, refill_per_second: f32) -> Self {
+        Self {
+            capacity,
+            refill_per_second,
+            tokens: capacity,
+        }
+    }
+
+    /// Remplit le seau pour la durée écoulée, sans dépasser sa capacité.
+    pub fn refill(&mut self, elapsed_secs: f32) {
+        self.tokens = (self.tokens + elapsed_secs * self.refill_per_second).min(self.capacity);
+    }
+
+    /// Consomme `amount` jetons s'ils sont disponibles.
+    ///
+    /// # Retourne
+    /// * `bool` - `false` si le seau ne contient pas assez de jetons.
+    pub fn try_take(&mut self, amount: f32) -> bool {
+        if self.tokens < amount {
+            return false;
+        }
+        self.tokens -= amount;
+        true
+    }
+}
+
+/// Seaux d'un canal client : un pour le nombre de messages, un pour les octets.
+#[derive(Debug, Clone, Copy)]
+pub struct ChannelBuckets {
+    messages: TokenBucket,
+    bytes: TokenBucket,
+}
+
+impl ChannelBuckets {
+    /// Crée des seaux pleins pour les limites données.
+    pub fn new(limits: &ChannelLimits) -> Self {
+        Self {
+            messages: TokenBucket::new(limits.message_burst, limits.messages_per_second),
+            bytes: TokenBucket::new(limits.byte_burst, limits.bytes_per_second),
+        }
+    }
+
+    /// Remplit les seaux pour la durée écoulée.
+    pub fn refill(&mut self, elapsed_secs: f32) {
+        self.messages.refill(elapsed_secs);
+        self.bytes.refill(elapsed_secs);
+    }
+
+    /// Consomme les jetons d'un message de `size` octets.
+    ///
+    /// # Retourne
+    /// * `bool` - `false` si le message dépasse la limite de messages ou d'octets ;
+    ///   aucun jeton n'est alors consommé.
+    pub fn try_accept(&mut self, size: usize) -> bool {
+        let size = size as f32;
+        if self.messages.tokens < 1.0 || self.bytes.tokens < size {
+            return false;
+        }
+        self.messages.try_take(1.0) && self.bytes.try_take(size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limits() -> ChannelLimits {
+        ChannelLimits {
+            messages_per_second: 2.0,
+            message_burst: 4.0,
+            bytes_per_second: 100.0,
+            byte_burst: 200.0,
+            response: FloodResponse::Drop,
+        }
+    }
+
+    #[test]
+    fn bucket_allows_burst_then_refills() {
+        let mut bucket = TokenBucket::new(3.0, 1.0);
+
+        assert!(bucket.try_take(1.0));
+        assert!(bucket.try_take(2.0));
+        assert!(!bucket.try_take(1.0));
+
+        bucket.refill(0.5);
+        assert!(!bucket.try_take(1.0));
+        bucket.refill(0.5);
+        assert!(bucket.try_take(1.0));
+    }
+
+    #[test]
+    fn bucket_refill_is_capped() {
+        let mut bucket = TokenBucket::new(2.0, 10.0);
+        bucket.refill(60.0);
+
+        assert!(bucket.try_take(2.0));
+        assert!(!bucket.try_take(0.5));
+    }
+
+    #[test]
+    fn channel_limits_message_count() {
+        let mut buckets = ChannelBuckets::new(&limits());
+
+        for _ in 0..4 {
+            assert!(buckets.try_accept(1));
+        }
+        assert!(!buckets.try_accept(1));
+
+        buckets.refill(0.5);
+        assert!(buckets.try_accept(1));
+        assert!(!buckets.try_accept(1));
+    }
+
+    #[test]
+    fn oversized_message_consumes_nothing() {
+        let mut buckets = ChannelBuckets::new(&limits());
+
+        assert!(!buckets.try_accept(201));
+        assert_eq!(buckets.messages.tokens, 4.0);
+        assert_eq!(buckets.bytes.tokens, 200.0);
+
+        assert!(buckets.try_accept(150));
+        assert!(!buckets.try_accept(100));
+        buckets.refill(0.5);
+        assert!(buckets.try_accept(100));
+    }
+}
@@ -1,22 +1,273 @@
+use crate::ai::{is_ai_client, AiPlayer, AiProfile};
+use crate::anti_cheat::InputTracker;
+use crate::navigation::NavGrid;
+#[cfg(feature = "physics")]
+use crate::physics::PhysicsSimulation;
+use crate::rate_limit::{ChannelBuckets, ChannelLimits, FloodResponse, TokenBucket};
+use bevy::log::error;
 use bevy::prelude::{Entity, Resource};
 use bevy_renet::renet::ClientId;
-use std::collections::HashMap;
+use game_core::client::{verify_session_token, ClientChannel, SessionToken};
+use game_core::map::GameMap;
+use game_core::network::serialize_server_message;
+use game_core::player::{Team, TEAM_COUNT};
+use game_core::replay::{ReplayMessage, ReplayRecipient, ReplayWriter};
+use game_core::replication::{ComponentChange, ReplicationId, ReplicationUpdate};
+use game_core::server::{DisconnectReason, ServerMessages};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Fichier par défaut de l'`AccessList`, relatif au répertoire de lancement.
+pub const DEFAULT_ACCESS_LIST_PATH: &str = "access_list.ron";
+
+/// Paramètres de fonctionnement du serveur.
+///
+/// - `reconnect_grace_period` : durée pendant laquelle l'entité d'un joueur déconnecté
+///   est conservée afin qu'il puisse la récupérer en se reconnectant.
+/// - `shutdown_flush_timeout` : durée maximale laissée aux canaux fiables pour se vider
+///   lors d'un arrêt propre.
+/// - `persist_on_shutdown` : demande l'écriture de l'état persistant avant l'arrêt.
+/// - `disconnect_flush_timeout` : durée maximale laissée au message de déconnexion
+///   pour être acquitté avant de couper la connexion.
+/// - `chat_rate_limit` / `chat_rate_window` : rafale maximale de messages de chat
+///   acceptés par joueur, regagnée en `chat_rate_window` (voir `ChatRateLimiter`).
+/// - `admins` : clients autorisés à envoyer des commandes d'administration via le chat,
+///   s'ils se sont connectés avec l'`admin_password`.
+/// - `admin_password` : mot de passe d'administration ; sans lui, seule la console
+///   accepte les commandes d'administration.
+/// - `access_list_path` : fichier de la liste des bannis et de la liste blanche.
+/// - `password` : mot de passe exigé des clients, si le serveur est privé.
+/// - `max_clients` : nombre maximal de connexions acceptées par le transport.
+/// - `max_players` : nombre maximal de joueurs dans la partie, inférieur à `max_clients` ;
+///   les clients au-delà attendent dans la `WaitingQueue`.
+/// - `queue_update_interval` : intervalle d'envoi de leur position aux clients en attente.
+/// - `command_limits` / `input_limits` : limites de débit des canaux `Command` et `Input`
+///   de chaque client.
+/// - `input_tick_burst` : nombre d'entrées d'avance tolérées sur les ticks écoulés,
+///   pour absorber la gigue du réseau (voir `InputTracker`).
+/// - `cheat_score_threshold` : score de triche au-delà duquel un client est signalé.
+/// - `cheat_score_decay` : points de score de triche oubliés par seconde.
+/// - `replay_dir` : répertoire des replays de partie ; `None` désactive l'enregistrement.
+/// - `replay_keyframe_interval` : intervalle entre deux états complets d'un replay.
+/// - `ai_profiles` : profils de difficulté des joueurs IA ; le premier est utilisé
+///   par défaut.
+/// - `ai_players` : nombre de joueurs IA ajoutés au démarrage.
+/// - `max_ai_players` : nombre maximal de joueurs IA ; ils ne comptent pas dans
+///   `max_players`.
+/// - `map` : carte de la partie.
+/// - `idle_timeout` : durée sans déplacement ni tir au-delà de laquelle un joueur est
+///   déconnecté (`DisconnectReason::Idle`) ; `None` (par défaut) désactive l'expulsion.
+#[derive(Debug, Resource)]
+pub struct ServerSettings {
+    /// Délai de grâce avant la suppression définitive d'un joueur déconnecté.
+    pub reconnect_grace_period: Duration,
+    /// Durée maximale d'attente de l'acquittement des messages fiables à l'arrêt.
+    pub shutdown_flush_timeout: Duration,
+    /// Indique si l'état persistant doit être sauvegardé à l'arrêt.
+    pub persist_on_shutdown: bool,
+    /// Durée maximale d'attente de l'acquittement de la raison de déconnexion.
+    pub disconnect_flush_timeout: Duration,
+    /// Rafale maximale de messages de chat par joueur.
+    pub chat_rate_limit: usize,
+    /// Durée au bout de laquelle la rafale de chat est regagnée.
+    pub chat_rate_window: Duration,
+    /// Identifiants des clients administrateurs.
+    pub admins: HashSet<ClientId>,
+    /// Mot de passe exigé des administrateurs.
+    pub admin_password: Option<String>,
+    /// Chemin du fichier `AccessList`.
+    pub access_list_path: PathBuf,
+    /// Mot de passe du serveur ; `None` pour un serveur public.
+    pub password: Option<String>,
+    /// Nombre maximal de connexions simultanées (joueurs et file d'attente).
+    pub max_clients: usize,
+    /// Nombre maximal de joueurs dans la partie.
+    pub max_players: usize,
+    /// Intervalle d'envoi de leur position aux clients en attente.
+    pub queue_update_interval: Duration,
+    /// Limites de débit du canal `ClientChannel::Command`.
+    pub command_limits: ChannelLimits,
+    /// Limites de débit du canal `ClientChannel::Input`.
+    pub input_limits: ChannelLimits,
+    /// Entrées d'avance tolérées sur les ticks écoulés.
+    pub input_tick_burst: f32,
+    /// Score de triche à partir duquel un client est signalé.
+    pub cheat_score_threshold: f32,
+    /// Décroissance du score de triche, en points par seconde.
+    pub cheat_score_decay: f32,
+    /// Répertoire d'enregistrement des replays.
+    pub replay_dir: Option<PathBuf>,
+    /// Intervalle entre deux états complets (keyframes) d'un replay.
+    pub replay_keyframe_interval: Duration,
+    /// Profils de difficulté des joueurs IA.
+    pub ai_profiles: Vec<AiProfile>,
+    /// Nombre de joueurs IA ajoutés au démarrage.
+    pub ai_players: usize,
+    /// Nombre maximal de joueurs IA.
+    pub max_ai_players: usize,
+    /// Carte de la partie.
+    pub map: GameMap,
+    /// Délai d'inactivité avant l'expulsion d'un joueur.
+    pub idle_timeout: Option<Duration>,
+}
+
+impl Default for ServerSettings {
+    fn default() -> Self {
+        Self {
+            reconnect_grace_period: Duration::from_secs(10),
+            shutdown_flush_timeout: Duration::from_secs(2),
+            persist_on_shutdown: true,
+            disconnect_flush_timeout: Duration::from_secs(1),
+            chat_rate_limit: 5,
+            chat_rate_window: Duration::from_secs(5),
+            admins: HashSet::new(),
+            admin_password: None,
+            access_list_path: PathBuf::from(DEFAULT_ACCESS_LIST_PATH),
+            password: None,
+            max_clients: 64,
+            max_players: 32,
+            queue_update_interval: Duration::from_secs(5),
+            command_limits: ChannelLimits {
+                messages_per_second: 10.0,
+                message_burst: 20.0,
+                bytes_per_second: 8.0 * 1024.0,
+                byte_burst: 16.0 * 1024.0,
+                response: FloodResponse::Warn,
+            },
+            input_limits: ChannelLimits {
+                messages_per_second: 120.0,
+                message_burst: 240.0,
+                bytes_per_second: 32.0 * 1024.0,
+                byte_burst: 64.0 * 1024.0,
+                response: FloodResponse::Disconnect,
+            },
+            input_tick_burst: 8.0,
+            cheat_score_threshold: 20.0,
+            cheat_score_decay: 0.5,
+            replay_dir: None,
+            replay_keyframe_interval: Duration::from_secs(10),
+            ai_profiles: AiProfile::defaults(),
+            ai_players: 0,
+            max_ai_players: 16,
+            map: GameMap::default(),
+            idle_timeout: None,
+        }
+    }
+}
+
+impl ServerSettings {
+    /// Construit les paramètres par défaut, complétés par les variables d'environnement
+    /// `SERVER_ADMINS` (identifiants de clients séparés par des virgules),
+    /// `SERVER_ADMIN_PASSWORD` (mot de passe des administrateurs, ignoré s'il est vide),
+    /// `SERVER_ACCESS_LIST` (chemin du fichier `AccessList`), `SERVER_PASSWORD`
+    /// (mot de passe du serveur, ignoré s'il est vide), `SERVER_MAX_CLIENTS`,
+    /// `SERVER_MAX_PLAYERS` (plafonné à `max_clients`),
+    /// `SERVER_REPLAY_DIR` (active l'enregistrement des replays dans ce répertoire),
+    /// `SERVER_AI_PLAYERS` (joueurs IA ajoutés au démarrage) et `SERVER_AI_PROFILES`
+    /// (fichier RON des profils de joueurs IA, voir `AiProfile::load_all`),
+    /// `SERVER_MAP` (fichier RON de la carte, voir `GameMap::load`) et
+    /// `SERVER_IDLE_TIMEOUT` (secondes d'inactivité avant expulsion, désactivée par
+    /// défaut ou avec 0).
+    /// Les valeurs mal formées sont ignorées, sauf un fichier de profils ou de carte
+    /// invalide.
+    pub fn from_env() -> Self {
+        let defaults = Self::default();
+        let max_clients = std::env::var("SERVER_MAX_CLIENTS")
+            .ok()
+            .and_then(|max_clients| max_clients.trim().parse().ok())
+            .filter(|max_clients| *max_clients > 0)
+            .unwrap_or(defaults.max_clients);
+        Self {
+            admins: std::env::var("SERVER_ADMINS")
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(|client_id| client_id.trim().parse().ok())
+                .collect(),
+            admin_password: std::env::var("SERVER_ADMIN_PASSWORD")
+                .ok()
+                .filter(|password| !password.is_empty()),
+            access_list_path: std::env::var_os("SERVER_ACCESS_LIST")
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from(DEFAULT_ACCESS_LIST_PATH)),
+            password: std::env::var("SERVER_PASSWORD")
+                .ok()
+                .filter(|password| !password.is_empty()),
+            max_clients,
+            max_players: std::env::var("SERVER_MAX_PLAYERS")
+                .ok()
+                .and_then(|max_players| max_players.trim().parse().ok())
+                .unwrap_or(defaults.max_players)
+                .min(max_clients),
+            replay_dir: std::env::var_os("SERVER_REPLAY_DIR")
+                .filter(|dir| !dir.is_empty())
+                .map(PathBuf::from),
+            ai_profiles: std::env::var_os("SERVER_AI_PROFILES")
+                .filter(|path| !path.is_empty())
+                .map_or(defaults.ai_profiles, |path| {
+                    AiProfile::load_all(Path::new(&path))
+                }),
+            ai_players: std::env::var("SERVER_AI_PLAYERS")
+                .ok()
+                .and_then(|ai_players| ai_players.trim().parse().ok())
+                .map_or(defaults.ai_players, |ai_players: usize| {
+                    ai_players.min(defaults.max_ai_players)
+                }),
+            map: std::env::var_os("SERVER_MAP")
+                .filter(|path| !path.is_empty())
+                .map_or(defaults.map, |path| GameMap::load(Path::new(&path))),
+            idle_timeout: std::env::var("SERVER_IDLE_TIMEOUT")
+                .ok()
+                .and_then(|secs| secs.trim().parse().ok())
+                .map_or(defaults.idle_timeout, |secs: u64| {
+                    (secs > 0).then(|| Duration::from_secs(secs))
+                }),
+            ..defaults
+        }
+    }
+}
 
 /// Ressource du serveur représentant le lobby.
 ///
 /// Contient la table d'association des clients connectés vers leur entité Bevy.
-/// - `players` : mappe chaque `ClientId` (identifiant réseau) à l'Entity` correspondante.
+/// - `players` : mappe chaque `ClientId` (identifiant réseau) à l'`Entity` correspondante.
+/// - `disconnected` : joueurs déconnectés en attente de reconnexion, avec l'instant
+///   (temps écoulé depuis le démarrage) de leur déconnexion.
+/// - `spectators` : clients connectés qui regardent la partie sans joueur ; ils ne
+///   comptent pas dans `ServerSettings::max_players`.
+/// - `teams` : équipe attribuée à chaque joueur (voir `assign_team`).
+/// - `synced` : clients connectés ayant déjà reçu la carte et les joueurs du lobby
+///   (voir `mark_synced`).
+/// - `sessions` : jeton de session de chaque joueur, exigé pour récupérer son entité
+///   après une déconnexion (voir `reclaim_player`).
+///
 /// Cette ressource est insérée dans l'App pour suivre les joueurs connectés.
 #[derive(Debug, Default, Resource)]
 pub struct ServerLobby {
     /// Mappe l'identifiant réseau d'un client à son entité Bevy.
     pub players: HashMap<ClientId, Entity>,
+    /// Mappe l'identifiant réseau d'un joueur déconnecté à l'instant de sa déconnexion.
+    pub disconnected: HashMap<ClientId, Duration>,
+    /// Identifiants des spectateurs.
+    pub spectators: HashSet<ClientId>,
+    /// Mappe l'identifiant réseau d'un joueur à son équipe.
+    pub teams: HashMap<ClientId, Team>,
+    /// Identifiants des clients ayant reçu l'état du lobby.
+    pub synced: HashSet<ClientId>,
+    /// Mappe l'identifiant réseau d'un joueur à son jeton de session.
+    pub sessions: HashMap<ClientId, SessionToken>,
 }
 
 impl ServerLobby {
     /// Ajoute un joueur au `ServerLobby`.
     ///
-    /// Associe l'`Entity` Bevy fourni à l'`ClientId`.
+    /// Associe l'`Entity` Bevy fourni à l'`ClientId`. Un spectateur qui devient
+    /// joueur cesse d'être spectateur.
     ///
     /// # Arguments
     ///
@@ -24,17 +275,55 @@ impl ServerLobby {
     /// * `entity` - Entité Bevy correspondant au joueur.
     pub fn add_player(&mut self, client_id: &ClientId, entity: Entity) {
         self.players.insert(*client_id, entity);
+        self.spectators.remove(client_id);
     }
 
     /// Supprime un joueur du `ServerLobby`.
     ///
-    /// Retire l'association de l'`Entity` Bevy pour l'`ClientId` donné.
+    /// Retire l'association de l'`Entity` Bevy pour l'`ClientId` donné,
+    /// ainsi que son équipe, son jeton de session et son éventuel état de déconnexion.
     ///
     /// # Arguments
     ///
     /// * `client_id` - Identifiant réseau du client à retirer.
     pub fn remove_player(&mut self, client_id: &ClientId) {
         self.players.remove(client_id);
+        self.disconnected.remove(client_id);
+        self.teams.remove(client_id);
+        self.sessions.remove(client_id);
+    }
+
+    /// Tire au hasard un nouveau jeton de session pour un joueur et le retourne.
+    ///
+    /// Le jeton est tiré du générateur aléatoire du système : il ne doit pas pouvoir
+    /// être deviné. En cas d'échec, l'erreur est journalisée et le joueur n'a pas de
+    /// jeton : il ne pourra pas récupérer son entité après une déconnexion.
+    pub fn open_session(&mut self, client_id: &ClientId) -> Option<SessionToken> {
+        let mut token = SessionToken::default();
+        if let Err(err) = getrandom::fill(&mut token) {
+            error!("Failed to generate a session token: {err}");
+            self.sessions.remove(client_id);
+            return None;
+        }
+        self.sessions.insert(*client_id, token);
+        Some(token)
+    }
+
+    /// Jeton de session d'un joueur, le cas échéant.
+    pub fn session_token(&self, client_id: &ClientId) -> Option<SessionToken> {
+        self.sessions.get(client_id).copied()
+    }
+
+    /// Attribue à un joueur l'équipe la moins nombreuse (la plus petite en cas
+    /// d'égalité) et la retourne.
+    pub fn assign_team(&mut self, client_id: &ClientId) -> Team {
+        self.teams.remove(client_id);
+        let team = (0..TEAM_COUNT)
+            .map(Team)
+            .min_by_key(|team| self.teams.values().filter(|other| *other == team).count())
+            .unwrap_or(Team(0));
+        self.teams.insert(*client_id, team);
+        team
     }
 
     /// Récupère l'entité Bevy associée à un `ClientId`.
@@ -47,4 +336,969 @@ impl ServerLobby {
     pub fn get_player(&self, client_id: &ClientId) -> Option<&Entity> {
         self.players.get(client_id)
     }
+
+    /// Marque un joueur comme déconnecté sans supprimer son entité.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - Identifiant réseau du client déconnecté.
+    /// * `now` - Temps écoulé depuis le démarrage du serveur.
+    pub fn mark_disconnected(&mut self, client_id: &ClientId, now: Duration) {
+        if self.players.contains_key(client_id) {
+            self.disconnected.insert(*client_id, now);
+        }
+    }
+
+    /// Indique si un joueur est actuellement en attente de reconnexion.
+    pub fn is_disconnected(&self, client_id: &ClientId) -> bool {
+        self.disconnected.contains_key(client_id)
+    }
+
+    /// Nombre de joueurs humains connectés du lobby, seuls comptés dans
+    /// `ServerSettings::max_players`.
+    ///
+    /// Un joueur en attente de reconnexion libère sa place ; s'il revient, il
+    /// récupère son entité même si la partie est pleine entre-temps.
+    pub fn human_count(&self) -> usize {
+        self.players
+            .keys()
+            .filter(|client_id| !is_ai_client(client_id) && !self.is_disconnected(client_id))
+            .count()
+    }
+
+    /// Note qu'un client a reçu la carte et les joueurs du lobby.
+    ///
+    /// Les messages suivants (création, suppression de joueurs, réplication) le
+    /// tiennent à jour : l'état ne lui est renvoyé qu'après une déconnexion (voir
+    /// `forget_client`), qui lui fait perdre son état local.
+    ///
+    /// # Retourne
+    /// * `bool` - `true` si le client n'avait pas encore reçu l'état du lobby.
+    pub fn mark_synced(&mut self, client_id: &ClientId) -> bool {
+        self.synced.insert(*client_id)
+    }
+
+    /// Oublie un client déconnecté : il n'est plus spectateur et devra recevoir
+    /// l'état du lobby à sa reconnexion.
+    pub fn forget_client(&mut self, client_id: &ClientId) {
+        self.spectators.remove(client_id);
+        self.synced.remove(client_id);
+    }
+
+    /// Ajoute un spectateur.
+    pub fn add_spectator(&mut self, client_id: &ClientId) {
+        self.spectators.insert(*client_id);
+    }
+
+    /// Retire un spectateur.
+    ///
+    /// # Retourne
+    /// * `bool` - `true` si le client était spectateur.
+    pub fn remove_spectator(&mut self, client_id: &ClientId) -> bool {
+        self.spectators.remove(client_id)
+    }
+
+    /// Indique si un client regarde la partie en spectateur.
+    pub fn is_spectator(&self, client_id: &ClientId) -> bool {
+        self.spectators.contains(client_id)
+    }
+
+    /// Rend son entité à un joueur déconnecté qui se reconnecte.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - Identifiant réseau du client qui se reconnecte.
+    /// * `token` - Jeton de session présenté par le client (voir `open_session`).
+    ///
+    /// # Retourne
+    /// * `Ok(Option<Entity>)` - Entité conservée si le joueur était en attente de
+    ///   reconnexion, `None` sinon.
+    /// * `Err(DisconnectReason::SessionInUse)` - Un joueur de même identifiant attend
+    ///   sa reconnexion, mais le client ne présente pas son jeton de session.
+    pub fn reclaim_player(
+        &mut self,
+        client_id: &ClientId,
+        token: Option<&SessionToken>,
+    ) -> Result<Option<Entity>, DisconnectReason> {
+        if !self.is_disconnected(client_id) {
+            return Ok(None);
+        }
+        let valid = match (self.sessions.get(client_id), token) {
+            (Some(expected), Some(token)) => verify_session_token(expected, token),
+            _ => false,
+        };
+        if !valid {
+            return Err(DisconnectReason::SessionInUse);
+        }
+
+        self.disconnected.remove(client_id);
+        Ok(self.players.get(client_id).copied())
+    }
+
+    /// Liste les joueurs déconnectés dont le délai de grâce est écoulé.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - Temps écoulé depuis le démarrage du serveur.
+    /// * `grace_period` - Délai de grâce accordé pour la reconnexion.
+    pub fn expired_players(&self, now: Duration, grace_period: Duration) -> Vec<ClientId> {
+        self.disconnected
+            .iter()
+            .filter(|(_, since)| now.saturating_sub(**since) >= grace_period)
+            .map(|(client_id, _)| *client_id)
+            .collect()
+    }
+}
+
+/// File d'attente des clients connectés alors que la partie est pleine.
+///
+/// - `clients` : clients en attente, du premier au dernier arrivé.
+/// - `next_update` : instant (temps écoulé depuis le démarrage) du prochain envoi
+///   de leur position aux clients en attente.
+#[derive(Debug, Default, Resource)]
+pub struct WaitingQueue {
+    pub clients: VecDeque<ClientId>,
+    pub next_update: Duration,
+}
+
+impl WaitingQueue {
+    /// Ajoute un client en fin de file.
+    ///
+    /// # Retourne
+    /// * `usize` - Position du client dans la file, à partir de 1.
+    pub fn push(&mut self, client_id: &ClientId) -> usize {
+        if let Some(position) = self.position(client_id) {
+            return position;
+        }
+        self.clients.push_back(*client_id);
+        self.clients.len()
+    }
+
+    /// Retire un client de la file, par exemple lorsqu'il se déconnecte.
+    pub fn remove(&mut self, client_id: &ClientId) -> bool {
+        let Some(index) = self.clients.iter().position(|queued| queued == client_id) else {
+            return false;
+        };
+        self.clients.remove(index);
+        true
+    }
+
+    /// Position d'un client dans la file, à partir de 1.
+    pub fn position(&self, client_id: &ClientId) -> Option<usize> {
+        self.clients
+            .iter()
+            .position(|queued| queued == client_id)
+            .map(|index| index + 1)
+    }
+}
+
+/// Clients dont la déconnexion a été demandée, en attente de l'acquittement
+/// de leur raison de déconnexion.
+///
+/// Associe chaque `ClientId` à l'instant (temps écoulé depuis le démarrage)
+/// au-delà duquel la connexion est coupée même sans acquittement.
+#[derive(Debug, Default, Resource)]
+pub struct PendingDisconnects(pub HashMap<ClientId, Duration>);
+
+/// Seaux à jetons des canaux de chaque client connecté.
+#[derive(Debug, Default, Resource)]
+pub struct ClientRateLimits {
+    pub clients: HashMap<ClientId, HashMap<ClientChannel, ChannelBuckets>>,
+}
+
+/// Compteurs du trafic reçu des clients.
+///
+/// - `messages_received` / `bytes_received` : messages acceptés.
+/// - `messages_dropped` : messages ignorés pour dépassement des limites de débit.
+/// - `violations` : nombre de messages ignorés par client connecté ; oublié à sa
+///   déconnexion, les identifiants étant choisis par les clients.
+#[derive(Debug, Default, Resource)]
+pub struct ServerMetrics {
+    pub messages_received: u64,
+    pub bytes_received: u64,
+    pub messages_dropped: u64,
+    pub violations: HashMap<ClientId, u64>,
+}
+
+/// Joueurs contrôlés par le serveur (voir `AiPlayer`).
+///
+/// - `players` : état de chaque joueur IA, par identifiant synthétique.
+/// - `next_index` : numéro du prochain joueur IA ; son identifiant vaut
+///   `AI_CLIENT_ID_BASE + next_index`.
+#[derive(Debug, Default, Resource)]
+pub struct AiPlayers {
+    pub players: HashMap<ClientId, AiPlayer>,
+    pub next_index: u64,
+}
+
+/// Suivi des entrées de déplacement de chaque client connecté (voir `InputTracker`).
+#[derive(Debug, Default, Resource)]
+pub struct InputTrackers {
+    pub clients: HashMap<ClientId, InputTracker>,
+}
+
+/// Dernière activité (déplacement ou tir) de chaque joueur humain, en temps écoulé
+/// depuis le démarrage du serveur.
+#[derive(Debug, Default, Resource)]
+pub struct PlayerActivity {
+    pub last_active: HashMap<ClientId, Duration>,
+}
+
+/// Score de triche de chaque client.
+///
+/// Chaque comportement suspect ajoute des points au score du client ; le score
+/// décroît avec le temps, de sorte que seules des violations répétées le font
+/// dépasser `ServerSettings::cheat_score_threshold`.
+#[derive(Debug, Default, Resource)]
+pub struct CheatScores {
+    pub scores: HashMap<ClientId, f32>,
+}
+
+impl CheatScores {
+    /// Ajoute `points` au score d'un client.
+    ///
+    /// # Retourne
+    /// * `(f32, bool)` - le nouveau score, et `true` s'il vient de franchir `threshold`.
+    pub fn flag(&mut self, client_id: &ClientId, points: f32, threshold: f32) -> (f32, bool) {
+        let score = self.scores.entry(*client_id).or_default();
+        let previous = *score;
+        *score += points;
+        (*score, previous < threshold && *score >= threshold)
+    }
+
+    /// Retire `points` à tous les scores ; les scores tombés à zéro sont oubliés.
+    pub fn decay(&mut self, points: f32) {
+        self.scores.retain(|_, score| {
+            *score -= points;
+            *score > 0.0
+        });
+    }
+}
+
+/// Limite de débit du chat : seau à jetons de chaque client (voir `TokenBucket`),
+/// avec l'instant de son dernier remplissage.
+#[derive(Debug, Default, Resource)]
+pub struct ChatRateLimiter {
+    pub buckets: HashMap<ClientId, (TokenBucket, Duration)>,
+}
+
+impl ChatRateLimiter {
+    /// Accepte un message si le client n'a pas dépassé sa limite.
+    ///
+    /// Le client dispose d'une rafale de `limit` messages, regagnée en `window`.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - Identifiant réseau de l'auteur.
+    /// * `now` - Temps écoulé depuis le démarrage du serveur.
+    /// * `limit` - Nombre maximal de messages sur la fenêtre.
+    /// * `window` - Durée au bout de laquelle la rafale est entièrement regagnée.
+    ///
+    /// # Retourne
+    /// * `bool` - `true` si le message est accepté.
+    pub fn try_send(
+        &mut self,
+        client_id: &ClientId,
+        now: Duration,
+        limit: usize,
+        window: Duration,
+    ) -> bool {
+        let (bucket, refilled) = self.buckets.entry(*client_id).or_insert_with(|| {
+            let limit = limit as f32;
+            (
+                TokenBucket::new(limit, limit / window.as_secs_f32().max(f32::EPSILON)),
+                now,
+            )
+        });
+        bucket.refill(now.saturating_sub(*refilled).as_secs_f32());
+        *refilled = now;
+        bucket.try_take(1.0)
+    }
+}
+
+/// Bannissement d'un client.
+///
+/// - `ip` : adresse IP du client au moment du bannissement, bannie elle aussi sauf si
+///   l'administrateur demande le contraire (`ban <joueur> --id-only`) ou si le client
+///   n'est pas connecté.
+/// - `reason` : motif transmis au client lorsqu'il tente de se connecter.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Ban {
+    #[serde(default)]
+    pub ip: Option<IpAddr>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// Contrôle d'accès au serveur : liste des bannis et liste blanche facultative.
+///
+/// Sauvegardée au format RON dans `ServerSettings::access_list_path` à chaque
+/// modification et à la réception de `PersistState`, et rechargée au démarrage.
+/// Un client refusé est déconnecté dès sa connexion, sans être ajouté au lobby.
+///
+/// - `bans` : clients bannis, par identifiant (et par IP lorsqu'elle est connue).
+///   L'identifiant étant choisi par le client (`ServerAuthentication::Unsecure`), un
+///   bannissement sans IP se contourne en changeant d'identifiant.
+/// - `whitelist_enabled` : n'accepte que les clients de `whitelist`.
+/// - `whitelist` : identifiants des clients autorisés lorsque la liste blanche est active.
+#[derive(Debug, Default, Clone, Resource, Serialize, Deserialize)]
+pub struct AccessList {
+    #[serde(default)]
+    pub bans: HashMap<ClientId, Ban>,
+    #[serde(default)]
+    pub whitelist_enabled: bool,
+    #[serde(default)]
+    pub whitelist: HashSet<ClientId>,
+}
+
+impl AccessList {
+    /// Charge la liste depuis un fichier RON. Un fichier absent donne une liste vide.
+    ///
+    /// # Panique
+    ///
+    /// Panique si le fichier existe mais ne peut pas être lu ou interprété : démarrer
+    /// avec une liste vide lèverait silencieusement tous les bannissements.
+    pub fn load(path: &Path) -> Self {
+        match std::fs::read_to_string(path) {
+            Ok(content) => ron::from_str(&content)
+                .unwrap_or_else(|err| panic!("Invalid access list {}: {err}", path.display())),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(err) => panic!("Failed to read access list {}: {err}", path.display()),
+        }
+    }
+
+    /// Enregistre la liste au format RON, via un fichier temporaire renommé
+    /// pour ne jamais laisser un fichier tronqué.
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let content = ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())
+            .map_err(|err| err.to_string())?;
+        let tmp_path = path.with_extension("ron.tmp");
+        std::fs::write(&tmp_path, content).map_err(|err| err.to_string())?;
+        std::fs::rename(&tmp_path, path).map_err(|err| err.to_string())
+    }
+
+    /// Bannit un client, et l'adresse IP `ip` si elle est fournie.
+    ///
+    /// Sans IP, le bannissement se contourne en se connectant sous un autre identifiant.
+    pub fn ban(&mut self, client_id: &ClientId, ip: Option<IpAddr>, reason: Option<String>) {
+        self.bans.insert(*client_id, Ban { ip, reason });
+    }
+
+    /// Lève le bannissement d'un client et de l'adresse IP associée.
+    ///
+    /// # Retourne
+    /// * `bool` - `true` si le client était banni.
+    pub fn unban(&mut self, client_id: &ClientId) -> bool {
+        self.bans.remove(client_id).is_some()
+    }
+
+    /// Vérifie qu'un client peut se connecter.
+    ///
+    /// # Arguments
+    ///
+    /// * `client_id` - Identifiant réseau du client.
+    /// * `ip` - Adresse IP du client, si connue.
+    ///
+    /// # Retourne
+    /// * `Result<(), DisconnectReason>` - La raison du refus si le client est banni
+    ///   (par identifiant ou par IP) ou absent de la liste blanche active.
+    pub fn check(&self, client_id: &ClientId, ip: Option<IpAddr>) -> Result<(), DisconnectReason> {
+        let ban = self
+            .bans
+            .get(client_id)
+            .or_else(|| ip.and_then(|ip| self.bans.values().find(|ban| ban.ip == Some(ip))));
+        if let Some(ban) = ban {
+            return Err(DisconnectReason::Banned {
+                message: ban.reason.clone(),
+            });
+        }
+
+        if self.whitelist_enabled && !self.whitelist.contains(client_id) {
+            return Err(DisconnectReason::NotWhitelisted);
+        }
+        Ok(())
+    }
+}
+
+/// Lignes saisies sur l'entrée standard du serveur, transmises par le thread
+/// de lecture de la console d'administration.
+#[derive(Debug, Resource)]
+pub struct AdminConsole(pub Mutex<Receiver<String>>);
+
+/// Étape de la séquence d'arrêt du serveur.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ShutdownPhase {
+    /// Le serveur fonctionne normalement.
+    #[default]
+    Running,
+    /// Les clients ont été prévenus ; on attend que les canaux fiables se vident.
+    ///
+    /// * `deadline` - Instant (temps écoulé depuis le démarrage) au-delà duquel
+    ///   l'arrêt est forcé.
+    Draining { deadline: Duration },
+    /// Les clients sont déconnectés et la sortie de l'application est demandée.
+    Stopped,
+}
+
+/// État courant de la séquence d'arrêt du serveur.
+#[derive(Debug, Default, Resource)]
+pub struct ShutdownState {
+    pub phase: ShutdownPhase,
+}
+
+/// Drapeau levé par le gestionnaire de signaux (Ctrl+C, SIGTERM).
+///
+/// Partagé avec le thread du gestionnaire de signaux, puis relevé par
+/// un système qui émet une `ShutdownRequest`.
+#[derive(Debug, Default, Clone, Resource)]
+pub struct ShutdownSignal(pub Arc<AtomicBool>);
+
+impl ShutdownSignal {
+    /// Lève le drapeau d'arrêt.
+    pub fn raise(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Consomme le drapeau : retourne `true` une seule fois après qu'il a été levé.
+    pub fn take(&self) -> bool {
+        self.0.swap(false, Ordering::SeqCst)
+    }
+}
+
+/// Valeurs sérialisées des composants répliqués d'une entité, par `ReplicationId`.
+pub type ReplicatedComponents = HashMap<ReplicationId, Vec<u8>>;
+
+/// Nombre de frames pendant lesquelles la dernière valeur d'un composant non fiable
+/// est renvoyée : la perte d'un paquet ne fige pas le client sur une valeur périmée.
+pub const UNRELIABLE_REPEAT_FRAMES: u64 = 8;
+
+/// Dernier état répliqué envoyé aux clients.
+///
+/// - `sent` : valeurs sérialisées des composants de chaque entité `Replicated`.
+/// - `clients` : clients ayant déjà reçu l'état complet, auxquels seules les
+///   différences sont envoyées.
+/// - `frame` : numéro de la dernière `ServerMessages::ReplicationFrame`.
+/// - `recent` : frame de la dernière modification de chaque composant non fiable.
+#[derive(Debug, Default, Resource)]
+pub struct ReplicationState {
+    /// Composants envoyés pour chaque entité répliquée.
+    pub sent: HashMap<Entity, ReplicatedComponents>,
+    /// Clients synchronisés.
+    pub clients: HashSet<ClientId>,
+    /// Numéro de la dernière frame non fiable.
+    pub frame: u64,
+    /// Composants non fiables modifiés lors des dernières frames.
+    pub recent: HashMap<(Entity, ReplicationId), u64>,
+}
+
+impl ReplicationState {
+    /// Calcule la différence entre l'état envoyé et l'état courant.
+    ///
+    /// # Arguments
+    ///
+    /// * `current` - Composants sérialisés de chaque entité répliquée.
+    /// * `order` - Entités de `current` dans l'ordre de la hiérarchie (parents d'abord).
+    pub fn diff(
+        &self,
+        current: &HashMap<Entity, ReplicatedComponents>,
+        order: &[Entity],
+    ) -> ReplicationUpdate {
+        let mut update = ReplicationUpdate::default();
+
+        for entity in order {
+            let Some(components) = current.get(entity) else {
+                continue;
+            };
+            let sent = self.sent.get(entity);
+            if sent.is_none() {
+                update.spawns.push(*entity);
+            }
+
+            for (component, data) in components {
+                if sent.and_then(|sent| sent.get(component)) != Some(data) {
+                    update.changes.push(ComponentChange {
+                        entity: *entity,
+                        component: *component,
+                        data: data.clone(),
+                    });
+                }
+            }
+
+            if let Some(sent) = sent {
+                update.removals.extend(
+                    sent.keys()
+                        .filter(|component| !components.contains_key(component))
+                        .map(|component| (*entity, *component)),
+                );
+            }
+        }
+
+        update.despawns.extend(
+            self.sent
+                .keys()
+                .filter(|entity| !current.contains_key(entity)),
+        );
+
+        update
+    }
+
+    /// Retire de `update` les modifications des composants non fiables déjà connus
+    /// des clients, et retourne la frame non fiable suivante.
+    ///
+    /// La frame contient la valeur courante des composants non fiables modifiés lors
+    /// des `UNRELIABLE_REPEAT_FRAMES` dernières frames. L'apparition d'un composant
+    /// reste dans `update`, envoyée sur le canal fiable.
+    ///
+    /// # Arguments
+    ///
+    /// * `update` - Différence calculée par `diff` avant la mise à jour de `sent`.
+    /// * `current` - Composants sérialisés de chaque entité répliquée.
+    /// * `unreliable` - Composants envoyés sur le canal non fiable.
+    ///
+    /// # Retourne
+    /// * `(u64, Vec<ComponentChange>)` - Numéro de la frame et ses modifications.
+    pub fn split_unreliable(
+        &mut self,
+        update: &mut ReplicationUpdate,
+        current: &HashMap<Entity, ReplicatedComponents>,
+        unreliable: &HashSet<ReplicationId>,
+    ) -> (u64, Vec<ComponentChange>) {
+        self.frame += 1;
+        let frame = self.frame;
+        let (sent, recent) = (&self.sent, &mut self.recent);
+
+        update.changes.retain(|change| {
+            let known = sent
+                .get(&change.entity)
+                .is_some_and(|sent| sent.contains_key(&change.component));
+            if known && unreliable.contains(&change.component) {
+                recent.insert((change.entity, change.component), frame);
+                return false;
+            }
+            true
+        });
+
+        let mut changes = Vec::new();
+        recent.retain(|(entity, component), changed| {
+            let Some(data) = current
+                .get(entity)
+                .and_then(|components| components.get(component))
+            else {
+                return false;
+            };
+            if frame - *changed >= UNRELIABLE_REPEAT_FRAMES {
+                return false;
+            }
+            changes.push(ComponentChange {
+                entity: *entity,
+                component: *component,
+                data: data.clone(),
+            });
+            true
+        });
+        (frame, changes)
+    }
+
+    /// Construit l'état complet, envoyé aux clients nouvellement connectés.
+    ///
+    /// # Arguments
+    ///
+    /// * `current` - Composants sérialisés de chaque entité répliquée.
+    /// * `order` - Entités de `current` dans l'ordre de la hiérarchie (parents d'abord).
+    pub fn full(
+        current: &HashMap<Entity, ReplicatedComponents>,
+        order: &[Entity],
+    ) -> ReplicationUpdate {
+        ReplicationState::default().diff(current, order)
+    }
+}
+
+/// Tick du serveur : nombre de pas de `FixedUpdate` exécutés depuis le démarrage.
+#[derive(Debug, Default, Clone, Copy, Resource)]
+pub struct ServerTick(pub u64);
+
+/// Enregistreur du replay de la partie (voir `game_core::replay`).
+///
+/// Les fonctions d'envoi de `server_event` confient chaque `ServerMessages` envoyé à
+/// l'enregistreur via `record` ; ils sont écrits une fois par frame, avec le tick courant, par
+/// `write_replay`. Sans fichier ouvert (`writer` vide), l'enregistrement est désactivé
+/// et `record` ne fait rien.
+///
+/// - `pending` : messages de la frame courante, pas encore écrits.
+/// - `keyframe` : état complet de la frame courante, s'il est dû.
+/// - `next_keyframe` / `keyframe_interval` : tick du prochain état complet et
+///   nombre de ticks entre deux états complets.
+#[derive(Default, Resource)]
+pub struct ReplayRecorder {
+    pub writer: Option<ReplayWriter>,
+    pub pending: Vec<ReplayMessage>,
+    pub keyframe: Option<Vec<Vec<u8>>>,
+    pub next_keyframe: u64,
+    pub keyframe_interval: u64,
+}
+
+impl ReplayRecorder {
+    /// Indique si un replay est en cours d'enregistrement.
+    pub fn is_recording(&self) -> bool {
+        self.writer.is_some()
+    }
+
+    /// Enregistre un message envoyé par le serveur.
+    pub fn record(&mut self, recipient: ReplayRecipient, message: &ServerMessages) {
+        if !self.is_recording() {
+            return;
+        }
+        self.pending.push(ReplayMessage {
+            recipient,
+            payload: serialize_server_message(message),
+        });
+    }
+
+    /// Indique si un état complet doit être enregistré au tick `tick`.
+    pub fn keyframe_due(&self, tick: u64) -> bool {
+        self.is_recording() && tick >= self.next_keyframe
+    }
+}
+
+/// Grille de navigation des joueurs IA, construite à partir des `StaticCollider`
+/// de la carte (voir `update_navigation_grid`).
+///
+/// Vide jusqu'à la création des murs de la carte.
+#[derive(Debug, Default, Resource)]
+pub struct Navigation {
+    pub grid: NavGrid,
+}
+
+/// Simulation physique du serveur (voir `PhysicsSimulation`), créée au démarrage
+/// pour l'arène de la carte par `setup_physics`.
+#[cfg(feature = "physics")]
+#[derive(Default, Resource)]
+pub struct Physics {
+    pub simulation: PhysicsSimulation,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::AI_CLIENT_ID_BASE;
+    use bevy::prelude::World;
+
+    fn replicated(components: &[(ReplicationId, u8)]) -> ReplicatedComponents {
+        components
+            .iter()
+            .map(|(id, value)| (*id, vec![*value]))
+            .collect()
+    }
+
+    fn ip(last: u8) -> Option<IpAddr> {
+        Some(IpAddr::from([192, 168, 0, last]))
+    }
+
+    #[test]
+    fn access_list_accepts_unknown_clients() {
+        let access_list = AccessList::default();
+
+        assert_eq!(access_list.check(&1, ip(1)), Ok(()));
+        assert_eq!(access_list.check(&1, None), Ok(()));
+    }
+
+    #[test]
+    fn ban_and_unban_by_client_id() {
+        let mut access_list = AccessList::default();
+        access_list.ban(&1, None, Some("cheating".to_string()));
+
+        assert_eq!(
+            access_list.check(&1, ip(1)),
+            Err(DisconnectReason::Banned {
+                message: Some("cheating".to_string())
+            })
+        );
+        // Sans bannissement d'IP, un autre client à la même adresse est accepté.
+        assert_eq!(access_list.check(&2, ip(1)), Ok(()));
+
+        assert!(access_list.unban(&1));
+        assert!(!access_list.unban(&1));
+        assert_eq!(access_list.check(&1, ip(1)), Ok(()));
+    }
+
+    #[test]
+    fn ip_ban_rejects_other_clients_at_the_same_address() {
+        let mut access_list = AccessList::default();
+        access_list.ban(&1, ip(1), None);
+
+        assert_eq!(
+            access_list.check(&2, ip(1)),
+            Err(DisconnectReason::Banned { message: None })
+        );
+        assert_eq!(access_list.check(&2, ip(2)), Ok(()));
+        assert_eq!(access_list.check(&2, None), Ok(()));
+
+        access_list.unban(&1);
+        assert_eq!(access_list.check(&2, ip(1)), Ok(()));
+    }
+
+    #[test]
+    fn whitelist_only_applies_when_enabled() {
+        let mut access_list = AccessList {
+            whitelist: HashSet::from([1]),
+            ..Default::default()
+        };
+        assert_eq!(access_list.check(&2, None), Ok(()));
+
+        access_list.whitelist_enabled = true;
+        assert_eq!(access_list.check(&1, None), Ok(()));
+        assert_eq!(
+            access_list.check(&2, None),
+            Err(DisconnectReason::NotWhitelisted)
+        );
+
+        // Le bannissement l'emporte sur la liste blanche.
+        access_list.ban(&1, None, None);
+        assert!(matches!(
+            access_list.check(&1, None),
+            Err(DisconnectReason::Banned { .. })
+        ));
+    }
+
+    #[test]
+    fn access_list_round_trips_through_ron() {
+        let mut access_list = AccessList {
+            whitelist_enabled: true,
+            whitelist: HashSet::from([1, 2]),
+            ..Default::default()
+        };
+        access_list.ban(&3, ip(3), Some("spam".to_string()));
+        let path = std::env::temp_dir().join(format!("access_list_{}.ron", std::process::id()));
+
+        access_list.save(&path).unwrap();
+        let loaded = AccessList::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.bans, access_list.bans);
+        assert!(loaded.whitelist_enabled);
+        assert_eq!(loaded.whitelist, access_list.whitelist);
+        assert!(AccessList::load(&path).bans.is_empty());
+    }
+
+    #[test]
+    fn human_count_ignores_ai_and_disconnected_players() {
+        let mut world = World::new();
+        let mut lobby = ServerLobby::default();
+        for client_id in [1, 2, AI_CLIENT_ID_BASE] {
+            lobby.add_player(&client_id, world.spawn_empty().id());
+        }
+        assert_eq!(lobby.human_count(), 2);
+
+        lobby.mark_disconnected(&2, Duration::ZERO);
+        assert_eq!(lobby.human_count(), 1);
+
+        let token = lobby.open_session(&2);
+        assert!(lobby.reclaim_player(&2, token.as_ref()).unwrap().is_some());
+        assert_eq!(lobby.human_count(), 2);
+    }
+
+    #[test]
+    fn reclaim_requires_the_session_token() {
+        let mut world = World::new();
+        let mut lobby = ServerLobby::default();
+        let entity = world.spawn_empty().id();
+        lobby.add_player(&1, entity);
+        let token = lobby.open_session(&1).unwrap();
+
+        // Un client sans joueur en attente n'a rien à récupérer.
+        assert_eq!(lobby.reclaim_player(&1, None), Ok(None));
+        assert_eq!(lobby.reclaim_player(&2, None), Ok(None));
+
+        lobby.mark_disconnected(&1, Duration::ZERO);
+        let mut forged = token;
+        forged[0] ^= 1;
+        assert_eq!(
+            lobby.reclaim_player(&1, None),
+            Err(DisconnectReason::SessionInUse)
+        );
+        assert_eq!(
+            lobby.reclaim_player(&1, Some(&forged)),
+            Err(DisconnectReason::SessionInUse)
+        );
+        assert!(lobby.is_disconnected(&1));
+
+        assert_eq!(lobby.reclaim_player(&1, Some(&token)), Ok(Some(entity)));
+        assert!(!lobby.is_disconnected(&1));
+    }
+
+    #[test]
+    fn expired_players_outlive_the_grace_period() {
+        let mut world = World::new();
+        let mut lobby = ServerLobby::default();
+        for client_id in [1, 2, 3] {
+            lobby.add_player(&client_id, world.spawn_empty().id());
+        }
+        let grace_period = Duration::from_secs(10);
+
+        lobby.mark_disconnected(&1, Duration::from_secs(0));
+        lobby.mark_disconnected(&2, Duration::from_secs(5));
+        // Un client sans joueur n'est pas mis en attente de reconnexion.
+        lobby.mark_disconnected(&4, Duration::from_secs(0));
+
+        assert!(lobby
+            .expired_players(Duration::from_secs(9), grace_period)
+            .is_empty());
+        assert_eq!(
+            lobby.expired_players(Duration::from_secs(10), grace_period),
+            vec![1]
+        );
+        let mut expired = lobby.expired_players(Duration::from_secs(15), grace_period);
+        expired.sort();
+        assert_eq!(expired, vec![1, 2]);
+    }
+
+    #[test]
+    fn chat_limit_regains_messages_over_the_window() {
+        let mut limiter = ChatRateLimiter::default();
+        let window = Duration::from_secs(5);
+        let send = |limiter: &mut ChatRateLimiter, client_id, secs| {
+            limiter.try_send(&client_id, Duration::from_secs_f32(secs), 5, window)
+        };
+
+        for _ in 0..5 {
+            assert!(send(&mut limiter, 1, 0.0));
+        }
+        assert!(!send(&mut limiter, 1, 0.0));
+        assert!(send(&mut limiter, 2, 0.0));
+
+        // Un message est regagné par seconde.
+        assert!(send(&mut limiter, 1, 1.0));
+        assert!(!send(&mut limiter, 1, 1.5));
+        assert!(send(&mut limiter, 1, 10.0));
+    }
+
+    #[test]
+    fn teams_are_balanced() {
+        let mut lobby = ServerLobby::default();
+
+        assert_eq!(lobby.assign_team(&1), Team(0));
+        assert_eq!(lobby.assign_team(&2), Team(1));
+        assert_eq!(lobby.assign_team(&3), Team(0));
+
+        lobby.remove_player(&2);
+        assert_eq!(lobby.assign_team(&4), Team(1));
+    }
+
+    #[test]
+    fn lobby_is_synced_once_per_connection() {
+        let mut lobby = ServerLobby::default();
+        lobby.add_spectator(&1);
+
+        assert!(lobby.mark_synced(&1));
+        assert!(!lobby.mark_synced(&1));
+
+        lobby.forget_client(&1);
+        assert!(!lobby.is_spectator(&1));
+        assert!(lobby.mark_synced(&1));
+    }
+
+    #[test]
+    fn full_state_spawns_every_entity_with_all_components() {
+        let mut world = World::new();
+        let parent = world.spawn_empty().id();
+        let child = world.spawn_empty().id();
+        let current = HashMap::from([
+            (parent, replicated(&[(0, 1)])),
+            (child, replicated(&[(0, 2), (1, 3)])),
+        ]);
+
+        let update = ReplicationState::full(&current, &[parent, child]);
+
+        assert_eq!(update.spawns, vec![parent, child]);
+        assert_eq!(update.changes.len(), 3);
+        assert_eq!(update.changes[0].entity, parent);
+        assert!(update.removals.is_empty());
+        assert!(update.despawns.is_empty());
+    }
+
+    #[test]
+    fn diff_only_contains_changes_since_last_send() {
+        let mut world = World::new();
+        let kept = world.spawn_empty().id();
+        let spawned = world.spawn_empty().id();
+        let despawned = world.spawn_empty().id();
+        let state = ReplicationState {
+            sent: HashMap::from([
+                (kept, replicated(&[(0, 1), (1, 1), (2, 1)])),
+                (despawned, replicated(&[(0, 1)])),
+            ]),
+            ..Default::default()
+        };
+        let current = HashMap::from([
+            (kept, replicated(&[(0, 1), (1, 2)])),
+            (spawned, replicated(&[(0, 5)])),
+        ]);
+
+        let update = state.diff(&current, &[kept, spawned]);
+
+        assert_eq!(update.spawns, vec![spawned]);
+        let changes: Vec<_> = update
+            .changes
+            .iter()
+            .map(|change| (change.entity, change.component, change.data.clone()))
+            .collect();
+        assert_eq!(changes, vec![(kept, 1, vec![2]), (spawned, 0, vec![5])]);
+        assert_eq!(update.removals, vec![(kept, 2)]);
+        assert_eq!(update.despawns, vec![despawned]);
+    }
+
+    #[test]
+    fn unreliable_changes_are_repeated_then_dropped() {
+        let mut world = World::new();
+        let moving = world.spawn_empty().id();
+        let spawned = world.spawn_empty().id();
+        let mut state = ReplicationState {
+            sent: HashMap::from([(moving, replicated(&[(0, 1), (1, 1)]))]),
+            ..Default::default()
+        };
+        let unreliable = HashSet::from([1]);
+        let current = HashMap::from([
+            (moving, replicated(&[(0, 2), (1, 2)])),
+            (spawned, replicated(&[(1, 1)])),
+        ]);
+
+        let mut update = state.diff(&current, &[moving, spawned]);
+        let (frame, changes) = state.split_unreliable(&mut update, &current, &unreliable);
+        state.sent = current.clone();
+
+        // L'apparition d'un composant non fiable reste sur le canal fiable.
+        let reliable: Vec<_> = update
+            .changes
+            .iter()
+            .map(|change| (change.entity, change.component))
+            .collect();
+        assert_eq!(reliable, vec![(moving, 0), (spawned, 1)]);
+        assert_eq!(frame, 1);
+        assert_eq!(changes.len(), 1);
+        assert_eq!((changes[0].entity, changes[0].component), (moving, 1));
+
+        for frame in 2..=UNRELIABLE_REPEAT_FRAMES + 1 {
+            let mut update = state.diff(&current, &[moving, spawned]);
+            let (sent_frame, changes) = state.split_unreliable(&mut update, &current, &unreliable);
+            assert!(update.is_empty());
+            assert_eq!(sent_frame, frame);
+            assert_eq!(
+                changes.len(),
+                usize::from(frame <= UNRELIABLE_REPEAT_FRAMES)
+            );
+        }
+    }
+
+    #[test]
+    fn diff_of_unchanged_state_is_empty() {
+        let mut world = World::new();
+        let entity = world.spawn_empty().id();
+        let current = HashMap::from([(entity, replicated(&[(0, 1)]))]);
+        let state = ReplicationState {
+            sent: current.clone(),
+            ..Default::default()
+        };
+
+        assert!(state.diff(&current, &[entity]).is_empty());
+    }
 }
@@ -1,3 +1,19 @@
+pub mod access_list;
+pub mod admin;
+pub mod ai;
 pub mod camera;
+pub mod chat;
+pub mod client_message;
+pub mod disconnect;
 pub mod game_event;
+pub mod map;
+pub mod movement;
+pub mod navigation;
+#[cfg(feature = "physics")]
+pub mod physics;
+pub mod projectile;
+pub mod replay;
+pub mod replication;
 pub mod server_event;
+pub mod shutdown;
+pub mod spectator;
@@ -0,0 +1,29 @@
+use crate::resource::{AccessList, ServerSettings};
+use bevy::log::{error, info};
+use bevy::prelude::{DetectChanges, MessageReader, Res};
+use game_core::event::server_event::PersistState;
+
+/// Sauvegarde l'`AccessList` lorsqu'elle est modifiée (bannissement, levée de
+/// bannissement) ou à la réception de `PersistState`.
+pub fn persist_access_list(
+    access_list: Res<AccessList>,
+    settings: Res<ServerSettings>,
+    mut persist_reader: MessageReader<PersistState>,
+) {
+    let persist_requested = persist_reader.read().count() > 0;
+    let modified = access_list.is_changed() && !access_list.is_added();
+    if !persist_requested && !modified {
+        return;
+    }
+
+    match access_list.save(&settings.access_list_path) {
+        Ok(()) => info!(
+            "Access list saved to {}",
+            settings.access_list_path.display()
+        ),
+        Err(err) => error!(
+            "Failed to save access list to {}: {err}",
+            settings.access_list_path.display()
+        ),
+    }
+}
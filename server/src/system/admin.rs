@@ -0,0 +1,354 @@
+use crate::admin::{AdminCommand, AdminCommandRequest, AdminSource, PlayerTarget, ADMIN_HELP};
+use crate::ai::{is_ai_client, AiRequest};
+use crate::resource::{
+    AccessList, AdminConsole, AiPlayers, ReplayRecorder, ServerLobby, ServerSettings,
+};
+use crate::system::server_event::{broadcast_server_message, send_server_message_to_client};
+use bevy::log::{error, info, warn};
+use bevy::prelude::{MessageReader, MessageWriter, Query, Res, ResMut, Transform};
+use bevy_renet::netcode::NetcodeServerTransport;
+use bevy_renet::renet::{ClientId, RenetServer};
+use game_core::event::server_event::{DisconnectClient, ShutdownRequest};
+use game_core::player::PlayerInfo;
+use game_core::server::{DisconnectReason, ServerMessages};
+use std::io::BufRead;
+use std::sync::mpsc;
+use std::sync::Mutex;
+
+/// Nom affiché pour les messages de chat émis par le serveur.
+pub const SERVER_CHAT_NAME: &str = "Server";
+
+/// Raison d'arrêt transmise aux clients quand la commande `shutdown` n'en précise pas.
+const DEFAULT_SHUTDOWN_REASON: &str = "Server stopped by an administrator";
+
+/// Lance le thread lisant les commandes d'administration sur l'entrée standard.
+///
+/// Chaque ligne lue est transmise à `read_admin_console` via la ressource retournée.
+pub fn spawn_admin_console() -> AdminConsole {
+    let (sender, receiver) = mpsc::channel();
+    let spawned = std::thread::Builder::new()
+        .name("admin-console".to_string())
+        .spawn(move || {
+            for line in std::io::stdin().lock().lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+    if let Err(err) = spawned {
+        error!("Failed to start the admin console: {err}");
+    }
+    AdminConsole(Mutex::new(receiver))
+}
+
+/// Analyse les lignes saisies dans la console et émet les commandes correspondantes.
+pub fn read_admin_console(
+    console: Res<AdminConsole>,
+    mut command_writer: MessageWriter<AdminCommandRequest>,
+) {
+    let Ok(receiver) = console.0.lock() else {
+        return;
+    };
+
+    for line in receiver.try_iter() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        match AdminCommand::parse(line) {
+            Ok(command) => {
+                command_writer.write(AdminCommandRequest {
+                    source: AdminSource::Console,
+                    command,
+                });
+            }
+            Err(err) => warn!("{err}"),
+        }
+    }
+}
+
+/// Exécute les commandes d'administration, venant de la console ou d'un client
+/// administrateur, et adresse le résultat à leur auteur.
+#[allow(clippy::too_many_arguments)]
+pub fn execute_admin_commands(
+    mut command_reader: MessageReader<AdminCommandRequest>,
+    mut disconnect_writer: MessageWriter<DisconnectClient>,
+    mut ai_writer: MessageWriter<AiRequest>,
+    mut shutdown_writer: MessageWriter<ShutdownRequest>,
+    mut server: ResMut<RenetServer>,
+    mut access_list: ResMut<AccessList>,
+    mut recorder: ResMut<ReplayRecorder>,
+    transport: Res<NetcodeServerTransport>,
+    settings: Res<ServerSettings>,
+    ai_players: Res<AiPlayers>,
+    lobby: Res<ServerLobby>,
+    mut players: Query<(&PlayerInfo, &mut Transform)>,
+) {
+    for AdminCommandRequest { source, command } in command_reader.read() {
+        let reply = run_admin_command(
+            command,
+            &mut server,
+            &mut access_list,
+            &mut recorder,
+            &transport,
+            &settings,
+            &ai_players,
+            &lobby,
+            &mut players,
+            &mut disconnect_writer,
+            &mut ai_writer,
+            &mut shutdown_writer,
+        )
+        .unwrap_or_else(|err| err);
+
+        match source {
+            AdminSource::Console => info!("{reply}"),
+            AdminSource::Client(client_id) => {
+                let message = ServerMessages::Chat {
+                    client_id: None,
+                    name: SERVER_CHAT_NAME.to_string(),
+                    text: reply,
+                    team_only: false,
+                };
+                send_server_message_to_client(client_id, &message, &mut server, &mut recorder);
+            }
+        }
+    }
+}
+
+/// Exécute une commande d'administration.
+///
+/// Retourne la réponse à adresser à l'auteur de la commande, ou le message d'erreur.
+#[allow(clippy::too_many_arguments)]
+fn run_admin_command(
+    command: &AdminCommand,
+    server: &mut RenetServer,
+    access_list: &mut AccessList,
+    recorder: &mut ReplayRecorder,
+    transport: &NetcodeServerTransport,
+    settings: &ServerSettings,
+    ai_players: &AiPlayers,
+    lobby: &ServerLobby,
+    players: &mut Query<(&PlayerInfo, &mut Transform)>,
+    disconnect_writer: &mut MessageWriter<DisconnectClient>,
+    ai_writer: &mut MessageWriter<AiRequest>,
+    shutdown_writer: &mut MessageWriter<ShutdownRequest>,
+) -> Result<String, String> {
+    match command {
+        AdminCommand::Kick { target, reason } => {
+            let client_id = resolve_target(target, lobby, players)?;
+            if !server.is_connected(client_id) {
+                return Err(format!("Client {client_id} is not connected"));
+            }
+
+            disconnect_writer.write(DisconnectClient {
+                client_id,
+                reason: DisconnectReason::Kicked {
+                    message: reason.clone(),
+                },
+            });
+            Ok(format!("Kicked client {client_id}"))
+        }
+        AdminCommand::Ban {
+            target,
+            ip: ban_ip,
+            reason,
+        } => {
+            let client_id = resolve_target(target, lobby, players)?;
+            let ip = ban_ip
+                .then(|| transport.client_addr(client_id).map(|addr| addr.ip()))
+                .flatten();
+            access_list.ban(&client_id, ip, reason.clone());
+            if server.is_connected(client_id) {
+                disconnect_writer.write(DisconnectClient {
+                    client_id,
+                    reason: DisconnectReason::Banned {
+                        message: reason.clone(),
+                    },
+                });
+            }
+            Ok(match ip {
+                Some(ip) => format!("Banned client {client_id} ({ip})"),
+                None if *ban_ip => format!(
+                    "Banned client {client_id} by id only: it is not connected, its IP is unknown"
+                ),
+                None => format!("Banned client {client_id} by id only"),
+            })
+        }
+        AdminCommand::Unban { client_id } => {
+            if access_list.unban(client_id) {
+                Ok(format!("Unbanned client {client_id}"))
+            } else {
+                Err(format!("Client {client_id} is not banned"))
+            }
+        }
+        AdminCommand::Say { text } => {
+            let message = ServerMessages::Chat {
+                client_id: None,
+                name: SERVER_CHAT_NAME.to_string(),
+                text: text.clone(),
+                team_only: false,
+            };
+            broadcast_server_message(server, recorder, &message);
+            Ok(format!("Said: {text}"))
+        }
+        AdminCommand::List => Ok(list_players(lobby, players)),
+        AdminCommand::Tp { target, x, y } => {
+            let client_id = resolve_target(target, lobby, players)?;
+            let (_, mut transform) = lobby
+                .get_player(&client_id)
+                .and_then(|entity| players.get_mut(*entity).ok())
+                .ok_or_else(|| format!("Client {client_id} has no player"))?;
+
+            transform.translation.x = *x;
+            transform.translation.y = *y;
+            Ok(format!("Teleported client {client_id} to ({x}, {y})"))
+        }
+        AdminCommand::AddAi { count, profile } => {
+            let profile = match profile {
+                Some(name) => settings
+                    .ai_profiles
+                    .iter()
+                    .find(|profile| profile.name.eq_ignore_ascii_case(name))
+                    .ok_or_else(|| format!("Unknown AI profile '{name}'"))?,
+                None => settings
+                    .ai_profiles
+                    .first()
+                    .ok_or_else(|| "No AI profile is configured".to_string())?,
+            };
+            let available = settings
+                .max_ai_players
+                .saturating_sub(ai_players.players.len());
+            if *count == 0 || available == 0 {
+                return Err(format!(
+                    "Cannot add AI players ({} of {} already playing)",
+                    ai_players.players.len(),
+                    settings.max_ai_players
+                ));
+            }
+
+            let count = (*count).min(available);
+            ai_writer.write(AiRequest::Add {
+                count,
+                profile: profile.name.clone(),
+            });
+            Ok(format!("Adding {count} {} AI player(s)", profile.name))
+        }
+        AdminCommand::RemoveAi { target } => {
+            let Some(target) = target else {
+                if ai_players.players.is_empty() {
+                    return Err("No AI players".to_string());
+                }
+                ai_writer.write(AiRequest::Remove { client_id: None });
+                return Ok(format!(
+                    "Removing {} AI player(s)",
+                    ai_players.players.len()
+                ));
+            };
+
+            let client_id = resolve_target(target, lobby, players)?;
+            if !ai_players.players.contains_key(&client_id) {
+                return Err(format!("Client {client_id} is not an AI player"));
+            }
+            ai_writer.write(AiRequest::Remove {
+                client_id: Some(client_id),
+            });
+            Ok(format!("Removing AI player {client_id}"))
+        }
+        AdminCommand::AiProfiles => Ok(settings
+            .ai_profiles
+            .iter()
+            .map(|profile| {
+                format!(
+                    "{}: {:?}, reaction {:.2}s, aim error {:.2} rad, speed {:.0}%",
+                    profile.name,
+                    profile.behavior,
+                    profile.reaction_time,
+                    profile.aim_error,
+                    profile.speed * 100.0
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")),
+        AdminCommand::Shutdown {
+            restart_eta,
+            reason,
+        } => {
+            let reason = reason
+                .clone()
+                .unwrap_or_else(|| DEFAULT_SHUTDOWN_REASON.to_string());
+            shutdown_writer.write(ShutdownRequest {
+                reason: reason.clone(),
+                restart_eta: *restart_eta,
+            });
+            Ok(format!("Shutting down: {reason}"))
+        }
+        AdminCommand::Help => Ok(ADMIN_HELP.to_string()),
+    }
+}
+
+/// Retrouve l'identifiant du joueur visé.
+///
+/// Un identifiant est accepté tel quel (un client hors ligne peut être banni) ;
+/// un nom doit désigner un et un seul joueur du lobby.
+fn resolve_target(
+    target: &PlayerTarget,
+    lobby: &ServerLobby,
+    players: &Query<(&PlayerInfo, &mut Transform)>,
+) -> Result<ClientId, String> {
+    let name = match target {
+        PlayerTarget::Id(client_id) => return Ok(*client_id),
+        PlayerTarget::Name(name) => name,
+    };
+
+    let matches: Vec<ClientId> = lobby
+        .players
+        .iter()
+        .filter(|(_, entity)| {
+            players
+                .get(**entity)
+                .is_ok_and(|(info, _)| info.name.eq_ignore_ascii_case(name))
+        })
+        .map(|(client_id, _)| *client_id)
+        .collect();
+
+    match matches.as_slice() {
+        [client_id] => Ok(*client_id),
+        [] => Err(format!("No player named '{name}'")),
+        _ => Err(format!("Several players are named '{name}', use their id")),
+    }
+}
+
+/// Décrit les joueurs du lobby, un par ligne.
+fn list_players(lobby: &ServerLobby, players: &Query<(&PlayerInfo, &mut Transform)>) -> String {
+    let mut lines: Vec<String> = lobby
+        .players
+        .iter()
+        .filter_map(|(client_id, entity)| {
+            let (info, transform) = players.get(*entity).ok()?;
+            let status = if lobby.is_disconnected(client_id) {
+                " (disconnected)"
+            } else if is_ai_client(client_id) {
+                " (AI)"
+            } else {
+                ""
+            };
+            Some(format!(
+                "{client_id} {} at ({:.0}, {:.0}){status}",
+                info.name, transform.translation.x, transform.translation.y
+            ))
+        })
+        .collect();
+    lines.sort();
+
+    if lines.is_empty() {
+        "No players".to_string()
+    } else {
+        format!("{} player(s):\n{}", lines.len(), lines.join("\n"))
+    }
+}
@@ -0,0 +1,180 @@
+use crate::ai::{AiPlayer, AiRequest, AI_CLIENT_ID_BASE};
+use crate::navigation::NavPath;
+use crate::resource::{AiPlayers, Navigation, ServerLobby, ServerSettings};
+use crate::system::game_event::create_player;
+use bevy::asset::Assets;
+use bevy::log::{info, warn};
+use bevy::mesh::Mesh;
+use bevy::prelude::{
+    ColorMaterial, Commands, MessageReader, MessageWriter, Query, Res, ResMut, Time, Transform,
+};
+use bevy_renet::renet::ClientId;
+use game_core::client::{ConnectUserData, PlayerInput};
+use game_core::event::game_event::GameEvent;
+use game_core::event::server_event::PlayerInputReceived;
+
+/// Demande au démarrage les `ServerSettings::ai_players` joueurs IA du profil
+/// par défaut.
+pub fn request_initial_ai_players(
+    settings: Res<ServerSettings>,
+    mut ai_writer: MessageWriter<AiRequest>,
+) {
+    let Some(profile) = settings.ai_profiles.first() else {
+        return;
+    };
+    if settings.ai_players > 0 {
+        ai_writer.write(AiRequest::Add {
+            count: settings.ai_players,
+            profile: profile.name.clone(),
+        });
+    }
+}
+
+/// Ajoute ou retire des joueurs IA (voir `AiRequest`).
+///
+/// Un joueur IA est créé comme un joueur humain admis dans la partie, avec un
+/// identifiant synthétique à partir de `AI_CLIENT_ID_BASE` : les clients le
+/// reçoivent par un `PlayerCreate` ordinaire.
+#[allow(clippy::too_many_arguments)]
+pub fn on_ai_request(
+    mut ai_reader: MessageReader<AiRequest>,
+    mut game_event_writer: MessageWriter<GameEvent>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut lobby: ResMut<ServerLobby>,
+    mut ai_players: ResMut<AiPlayers>,
+    settings: Res<ServerSettings>,
+) {
+    for request in ai_reader.read() {
+        match request {
+            AiRequest::Add { count, profile } => {
+                let Some(profile) = settings
+                    .ai_profiles
+                    .iter()
+                    .find(|candidate| candidate.name.eq_ignore_ascii_case(profile))
+                else {
+                    warn!("Unknown AI profile '{profile}'");
+                    continue;
+                };
+
+                let available = settings
+                    .max_ai_players
+                    .saturating_sub(ai_players.players.len());
+                for _ in 0..(*count).min(available) {
+                    let index = ai_players.next_index;
+                    ai_players.next_index += 1;
+                    let client_id = AI_CLIENT_ID_BASE + index;
+                    let user_data = ConnectUserData {
+                        name: format!("AI {} ({})", index + 1, profile.name),
+                        color: None,
+                        password_hash: None,
+                        admin_password_hash: None,
+                        session_token: None,
+                        spectator: false,
+                    };
+
+                    info!("Adding AI player {client_id} ({})", profile.name);
+                    create_player(
+                        &client_id,
+                        &user_data,
+                        &mut commands,
+                        &mut meshes,
+                        &mut materials,
+                        &mut lobby,
+                        &mut game_event_writer,
+                    );
+                    ai_players
+                        .players
+                        .insert(client_id, AiPlayer::new(profile.clone()));
+                }
+            }
+            AiRequest::Remove { client_id } => {
+                let client_ids: Vec<ClientId> = match client_id {
+                    Some(client_id) => vec![*client_id],
+                    None => ai_players.players.keys().copied().collect(),
+                };
+
+                for client_id in client_ids {
+                    if ai_players.players.remove(&client_id).is_none() {
+                        continue;
+                    }
+                    info!("Removing AI player {client_id}");
+                    if let Some(entity) = lobby.get_player(&client_id).copied() {
+                        commands.entity(entity).despawn();
+                        lobby.remove_player(&client_id);
+                        game_event_writer.write(GameEvent::PlayerRemoved { client_id });
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Produit l'entrée de chaque joueur IA pour ce tick fixe.
+///
+/// À chaque décision, le joueur choisit une destination (voir
+/// `AiProfile::choose_goal`) et la rejoint par un chemin de la `NavGrid` ; entre
+/// deux décisions, il suit ce chemin. Tant que la grille n'est pas construite, il
+/// se dirige en ligne droite.
+///
+/// Les entrées suivent le même chemin que celles des clients
+/// (`PlayerInputReceived`, puis `validate_player_inputs`) : un joueur IA se
+/// déplace exactement comme un joueur humain.
+pub fn drive_ai_players(
+    time: Res<Time>,
+    lobby: Res<ServerLobby>,
+    navigation: Res<Navigation>,
+    mut ai_players: ResMut<AiPlayers>,
+    transforms: Query<&Transform>,
+    mut input_writer: MessageWriter<PlayerInputReceived>,
+) {
+    let now = time.elapsed_secs();
+    let position = |client_id: &ClientId| {
+        lobby
+            .get_player(client_id)
+            .and_then(|entity| transforms.get(*entity).ok())
+            .map(|transform| transform.translation.truncate())
+    };
+
+    for (client_id, ai_player) in ai_players.players.iter_mut() {
+        let Some(own_position) = position(client_id) else {
+            continue;
+        };
+
+        if now >= ai_player.next_decision {
+            let nearest = lobby
+                .players
+                .keys()
+                .filter(|other| *other != client_id)
+                .filter_map(&position)
+                .min_by(|a, b| {
+                    a.distance_squared(own_position)
+                        .total_cmp(&b.distance_squared(own_position))
+                });
+            let goal = ai_player.profile.choose_goal(own_position, nearest);
+            ai_player.path = match goal {
+                Some(goal) if navigation.grid.is_empty() => NavPath::new(vec![goal]),
+                Some(goal) => navigation
+                    .grid
+                    .find_path(own_position, goal)
+                    .map(NavPath::new)
+                    .unwrap_or_default(),
+                None => NavPath::default(),
+            };
+            ai_player.next_decision = now + ai_player.profile.reaction_time.max(0.0);
+        }
+
+        let direction =
+            ai_player.path.steer(own_position) * ai_player.profile.speed.clamp(0.0, 1.0);
+        ai_player.sequence = ai_player.sequence.wrapping_add(1);
+        input_writer.write(PlayerInputReceived {
+            client_id: *client_id,
+            input: PlayerInput {
+                sequence: ai_player.sequence,
+                direction: direction.to_array(),
+                fire: None,
+            },
+        });
+    }
+}
@@ -0,0 +1,251 @@
+use crate::admin::{AdminCommand, AdminCommandRequest, AdminSource};
+use crate::resource::{ChatRateLimiter, ReplayRecorder, ServerLobby, ServerSettings};
+use crate::system::server_event::{broadcast_server_message, send_server_message_to_client};
+use bevy::prelude::{info, MessageReader, MessageWriter, Query, Res, ResMut, Time};
+use bevy_renet::netcode::NetcodeServerTransport;
+use bevy_renet::renet::{ClientId, RenetServer};
+use game_core::chat::{sanitize_chat_text, MAX_CHAT_MESSAGE_LEN};
+use game_core::client::{verify_password, ClientMessages, ConnectUserData};
+use game_core::event::server_event::ClientMessageReceived;
+use game_core::player::{sanitize_player_name, PlayerInfo, Team};
+use game_core::server::ServerMessages;
+
+/// Traite les messages de chat reçus des clients (voir `ClientMessageReceived`).
+///
+/// Chaque message est validé (longueur, débit) puis signé du nom du `PlayerInfo`
+/// de son auteur avant d'être diffusé à tous les clients, ou aux seuls membres
+/// de son équipe. Un spectateur, sans équipe, ne peut écrire qu'à tous ; il signe
+/// du nom transmis à sa connexion. Un message refusé est signalé à son auteur par
+/// `ServerMessages::Error`.
+///
+/// Un message commençant par `/` est une commande d'administration, acceptée
+/// uniquement des administrateurs authentifiés (voir `is_admin`).
+#[allow(clippy::too_many_arguments)]
+pub fn on_client_chat(
+    mut server: ResMut<RenetServer>,
+    lobby: Res<ServerLobby>,
+    settings: Res<ServerSettings>,
+    time: Res<Time>,
+    mut limiter: ResMut<ChatRateLimiter>,
+    mut recorder: ResMut<ReplayRecorder>,
+    transport: Res<NetcodeServerTransport>,
+    players: Query<(&PlayerInfo, Option<&Team>)>,
+    mut message_reader: MessageReader<ClientMessageReceived>,
+    mut command_writer: MessageWriter<AdminCommandRequest>,
+) {
+    limiter
+        .buckets
+        .retain(|client_id, _| server.is_connected(*client_id));
+
+    for ClientMessageReceived { client_id, message } in message_reader.read() {
+        let client_id = *client_id;
+        let ClientMessages::Chat { text, team_only } = message else {
+            continue;
+        };
+        let team_only = *team_only;
+
+        let (name, team) = match lobby
+            .get_player(&client_id)
+            .and_then(|entity| players.get(*entity).ok())
+        {
+            Some((info, team)) => (info.name.clone(), team.copied()),
+            None if lobby.is_spectator(&client_id) => {
+                (spectator_name(&client_id, &transport), None)
+            }
+            None => continue,
+        };
+
+        let Some(text) = sanitize_chat_text(text) else {
+            reject_chat(
+                &client_id,
+                format!("Chat messages must be 1 to {MAX_CHAT_MESSAGE_LEN} characters long"),
+                &mut server,
+                &mut recorder,
+            );
+            continue;
+        };
+
+        if !limiter.try_send(
+            &client_id,
+            time.elapsed(),
+            settings.chat_rate_limit,
+            settings.chat_rate_window,
+        ) {
+            reject_chat(
+                &client_id,
+                "You are sending chat messages too fast".to_string(),
+                &mut server,
+                &mut recorder,
+            );
+            continue;
+        }
+
+        if let Some(command) = text.strip_prefix('/') {
+            let user_data = transport
+                .user_data(client_id)
+                .and_then(|user_data| ConnectUserData::from_user_data(&user_data));
+            if !is_admin(&client_id, user_data.as_ref(), &settings) {
+                reject_chat(
+                    &client_id,
+                    "You are not allowed to use admin commands".to_string(),
+                    &mut server,
+                    &mut recorder,
+                );
+                continue;
+            }
+
+            info!("[admin] {name} ({client_id}): {command}");
+            match AdminCommand::parse(command) {
+                Ok(command) => {
+                    command_writer.write(AdminCommandRequest {
+                        source: AdminSource::Client(client_id),
+                        command,
+                    });
+                }
+                Err(err) => reject_chat(&client_id, err, &mut server, &mut recorder),
+            }
+            continue;
+        }
+
+        if team_only && lobby.is_spectator(&client_id) {
+            reject_chat(
+                &client_id,
+                "Spectators cannot use team chat".to_string(),
+                &mut server,
+                &mut recorder,
+            );
+            continue;
+        }
+
+        info!("[chat] {name} ({client_id}): {text}");
+        let chat = ServerMessages::Chat {
+            client_id: Some(client_id),
+            name,
+            text,
+            team_only,
+        };
+
+        if !team_only {
+            broadcast_server_message(&mut server, &mut recorder, &chat);
+            continue;
+        }
+
+        let recipients: Vec<ClientId> = lobby
+            .players
+            .iter()
+            .filter(|(recipient, _)| server.is_connected(**recipient))
+            .filter(|(recipient, entity)| {
+                **recipient == client_id
+                    || team.is_some_and(|team| {
+                        players
+                            .get(**entity)
+                            .is_ok_and(|(_, other)| other == Some(&team))
+                    })
+            })
+            .map(|(recipient, _)| *recipient)
+            .collect();
+
+        for recipient in recipients {
+            send_server_message_to_client(&recipient, &chat, &mut server, &mut recorder);
+        }
+    }
+}
+
+/// Nom d'un spectateur, lu dans les `user_data` de sa connexion
+/// (voir `sanitize_player_name`).
+fn spectator_name(client_id: &ClientId, transport: &NetcodeServerTransport) -> String {
+    let name = transport
+        .user_data(*client_id)
+        .and_then(|user_data| ConnectUserData::from_user_data(&user_data))
+        .map(|user_data| user_data.name)
+        .unwrap_or_default();
+    sanitize_player_name(&name, client_id)
+}
+
+/// Indique si le client peut envoyer des commandes d'administration.
+///
+/// L'identifiant, choisi par le client, ne suffit pas : il doit figurer dans
+/// `ServerSettings::admins` et le client doit avoir transmis à sa connexion
+/// l'empreinte de l'`admin_password`.
+fn is_admin(
+    client_id: &ClientId,
+    user_data: Option<&ConnectUserData>,
+    settings: &ServerSettings,
+) -> bool {
+    let (Some(password), Some(hash)) = (
+        &settings.admin_password,
+        user_data.and_then(|user_data| user_data.admin_password_hash.as_ref()),
+    ) else {
+        return false;
+    };
+    settings.admins.contains(client_id) && verify_password(password, *client_id, hash)
+}
+
+fn reject_chat(
+    client_id: &ClientId,
+    message: String,
+    server: &mut RenetServer,
+    recorder: &mut ReplayRecorder,
+) {
+    let message = ServerMessages::Error { message };
+    send_server_message_to_client(client_id, &message, server, recorder);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game_core::client::hash_password;
+
+    fn admin_settings() -> ServerSettings {
+        ServerSettings {
+            admins: [42].into(),
+            admin_password: Some("root".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn with_admin_password(password: &str, client_id: ClientId) -> ConnectUserData {
+        ConnectUserData {
+            admin_password_hash: Some(hash_password(password, client_id)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn admins_must_send_the_admin_password() {
+        let settings = admin_settings();
+
+        assert!(is_admin(
+            &42,
+            Some(&with_admin_password("root", 42)),
+            &settings
+        ));
+        assert!(!is_admin(
+            &42,
+            Some(&with_admin_password("guess", 42)),
+            &settings
+        ));
+        assert!(!is_admin(&42, Some(&ConnectUserData::default()), &settings));
+        assert!(!is_admin(&42, None, &settings));
+    }
+
+    #[test]
+    fn admin_password_is_bound_to_whitelisted_ids() {
+        let settings = admin_settings();
+        assert!(!is_admin(
+            &7,
+            Some(&with_admin_password("root", 7)),
+            &settings
+        ));
+
+        let without_password = ServerSettings {
+            admin_password: None,
+            ..admin_settings()
+        };
+        assert!(!is_admin(
+            &42,
+            Some(&with_admin_password("root", 42)),
+            &without_password
+        ));
+    }
+}
@@ -0,0 +1,100 @@
+use crate::rate_limit::{ChannelBuckets, FloodResponse};
+use crate::resource::{ClientRateLimits, ServerMetrics, ServerSettings};
+use bevy::log::warn;
+use bevy::prelude::{MessageWriter, Res, ResMut, Time};
+use bevy_renet::renet::RenetServer;
+use game_core::client::ClientChannel;
+use game_core::event::server_event::{
+    ClientMessageReceived, DisconnectClient, PlayerInputReceived,
+};
+use game_core::network::{deserialize_client_message, deserialize_player_input};
+use game_core::server::DisconnectReason;
+
+/// Reçoit les messages de tous les canaux clients.
+///
+/// Chaque canal de chaque client est limité par un seau à jetons sur le nombre de
+/// messages et sur les octets (voir `ServerSettings::command_limits` et
+/// `ServerSettings::input_limits`). Les canaux sont vidés à chaque frame : un client
+/// qui inonde le serveur voit ses messages excédentaires ignorés au lieu de remplir
+/// la mémoire du canal. Les dépassements sont comptés dans `ServerMetrics` et
+/// traités selon la `FloodResponse` configurée.
+///
+/// Les messages acceptés du canal `Command` sont émis en `ClientMessageReceived`,
+/// ceux du canal `Input` en `PlayerInputReceived`.
+#[allow(clippy::too_many_arguments)]
+pub fn receive_client_messages(
+    mut server: ResMut<RenetServer>,
+    mut rate_limits: ResMut<ClientRateLimits>,
+    mut metrics: ResMut<ServerMetrics>,
+    settings: Res<ServerSettings>,
+    time: Res<Time>,
+    mut message_writer: MessageWriter<ClientMessageReceived>,
+    mut input_writer: MessageWriter<PlayerInputReceived>,
+    mut disconnect_writer: MessageWriter<DisconnectClient>,
+) {
+    rate_limits
+        .clients
+        .retain(|client_id, _| server.is_connected(*client_id));
+    metrics
+        .violations
+        .retain(|client_id, _| server.is_connected(*client_id));
+
+    let channels = [
+        (ClientChannel::Command, &settings.command_limits),
+        (ClientChannel::Input, &settings.input_limits),
+    ];
+
+    for client_id in server.clients_id() {
+        let client_buckets = rate_limits.clients.entry(client_id).or_default();
+
+        for (channel, limits) in channels {
+            let buckets = client_buckets
+                .entry(channel)
+                .or_insert_with(|| ChannelBuckets::new(limits));
+            buckets.refill(time.delta_secs());
+
+            let mut dropped = 0;
+            while let Some(payload) = server.receive_message(client_id, channel) {
+                if !buckets.try_accept(payload.len()) {
+                    dropped += 1;
+                    continue;
+                }
+                metrics.messages_received += 1;
+                metrics.bytes_received += payload.len() as u64;
+
+                match channel {
+                    ClientChannel::Command => {
+                        if let Some(message) = deserialize_client_message(&payload) {
+                            message_writer.write(ClientMessageReceived { client_id, message });
+                        }
+                    }
+                    ClientChannel::Input => {
+                        if let Some(input) = deserialize_player_input(&payload) {
+                            input_writer.write(PlayerInputReceived { client_id, input });
+                        }
+                    }
+                }
+            }
+
+            if dropped == 0 {
+                continue;
+            }
+            metrics.messages_dropped += dropped;
+            *metrics.violations.entry(client_id).or_default() += dropped;
+
+            match limits.response {
+                FloodResponse::Drop => {}
+                FloodResponse::Warn => {
+                    warn!("Client {client_id} exceeded the {channel:?} rate limit, dropped {dropped} message(s)");
+                }
+                FloodResponse::Disconnect => {
+                    warn!("Client {client_id} flooded the {channel:?} channel, disconnecting");
+                    disconnect_writer.write(DisconnectClient {
+                        client_id,
+                        reason: DisconnectReason::Flooding,
+                    });
+                }
+            }
+        }
+    }
+}
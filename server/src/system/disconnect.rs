@@ -0,0 +1,72 @@
+use crate::resource::{PendingDisconnects, ReplayRecorder, ServerLobby, ServerSettings};
+use crate::system::server_event::{is_server_messages_flushed, send_server_message_to_client};
+use bevy::log::info;
+use bevy::prelude::{Commands, MessageReader, MessageWriter, Res, ResMut, Time};
+use bevy_renet::renet::RenetServer;
+use game_core::event::game_event::GameEvent;
+use game_core::event::server_event::DisconnectClient;
+use game_core::server::ServerMessages;
+
+/// Envoie au client la raison de sa déconnexion et planifie la coupure de la connexion.
+///
+/// Le joueur du client est supprimé immédiatement : une déconnexion décidée par le
+/// serveur (expulsion, bannissement, inactivité, ...) ne laisse pas de délai de
+/// reconnexion pour récupérer son entité.
+#[allow(clippy::too_many_arguments)]
+pub fn on_disconnect_client(
+    mut disconnect_reader: MessageReader<DisconnectClient>,
+    mut game_event_writer: MessageWriter<GameEvent>,
+    mut commands: Commands,
+    mut lobby: ResMut<ServerLobby>,
+    mut pending: ResMut<PendingDisconnects>,
+    mut server: ResMut<RenetServer>,
+    mut recorder: ResMut<ReplayRecorder>,
+    settings: Res<ServerSettings>,
+    time: Res<Time>,
+) {
+    for DisconnectClient { client_id, reason } in disconnect_reader.read() {
+        if !server.is_connected(*client_id) || pending.0.contains_key(client_id) {
+            continue;
+        }
+
+        info!("Disconnecting client {client_id}: {reason}");
+        let message = ServerMessages::Disconnect {
+            reason: reason.clone(),
+        };
+        send_server_message_to_client(client_id, &message, &mut server, &mut recorder);
+
+        if let Some(entity) = lobby.get_player(client_id).copied() {
+            commands.entity(entity).despawn();
+            lobby.remove_player(client_id);
+            game_event_writer.write(GameEvent::PlayerRemoved {
+                client_id: *client_id,
+            });
+        }
+
+        pending.0.insert(
+            *client_id,
+            time.elapsed() + settings.disconnect_flush_timeout,
+        );
+    }
+}
+
+/// Coupe la connexion des clients dont la raison de déconnexion a été acquittée
+/// ou dont le délai d'attente est écoulé.
+pub fn process_pending_disconnects(
+    mut pending: ResMut<PendingDisconnects>,
+    mut server: ResMut<RenetServer>,
+    time: Res<Time>,
+) {
+    let now = time.elapsed();
+    pending.0.retain(|client_id, deadline| {
+        if !server.is_connected(*client_id) {
+            return false;
+        }
+        if now < *deadline && !is_server_messages_flushed(&server, *client_id) {
+            return true;
+        }
+
+        server.disconnect(*client_id);
+        false
+    });
+}
@@ -1,12 +1,27 @@
-use crate::resource::ServerLobby;
+use crate::ai::is_ai_client;
+use crate::resource::{AccessList, PlayerActivity, ServerLobby, ServerSettings, WaitingQueue};
 use bevy::asset::Assets;
 use bevy::log::info;
 use bevy::mesh::Mesh;
-use bevy::prelude::{ColorMaterial, Commands, MessageReader, MessageWriter, ResMut, Vec3};
-use bevy_renet::renet::{ClientId, ServerEvent};
+use bevy::prelude::{
+    ColorMaterial, Commands, MessageReader, MessageWriter, Query, Res, ResMut, Time, Transform,
+    Vec3,
+};
+use bevy_renet::netcode::NetcodeServerTransport;
+use bevy_renet::renet::{ClientId, RenetServer, ServerEvent};
+use game_core::client::ClientMessages;
+use game_core::client::{verify_password, ConnectUserData};
 use game_core::event::game_event::GameEvent;
-use game_core::player::spawn_player;
+use game_core::event::server_event::{ClientMessageReceived, DisconnectClient};
+use game_core::network::PROTOCOL_VERSION;
+use game_core::player::{
+    default_player_color, sanitize_player_name, spawn_player, Health, MoveDirection, PlayerInfo,
+};
+use game_core::replication::Replicated;
+use game_core::server::DisconnectReason;
+use std::net::IpAddr;
 
+#[allow(clippy::too_many_arguments)]
 pub fn on_game_event(
     mut server_event_reader: MessageReader<ServerEvent>,
     mut game_event_writer: MessageWriter<GameEvent>,
@@ -14,40 +29,578 @@ pub fn on_game_event(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<ColorMaterial>>,
     mut lobby: ResMut<ServerLobby>,
+    transforms: Query<&Transform>,
+    transport: Res<NetcodeServerTransport>,
+    time: Res<Time>,
+    access_list: Res<AccessList>,
+    settings: Res<ServerSettings>,
+    mut queue: ResMut<WaitingQueue>,
+    mut disconnect_writer: MessageWriter<DisconnectClient>,
 ) {
     for event in server_event_reader.read() {
         match event {
             ServerEvent::ClientConnected { client_id } => {
-                let position = Vec3::new(fastrand::f32() * 800.0 - 400.0, 0.0, 0.0);
+                let ip = transport.client_addr(*client_id).map(|addr| addr.ip());
+                let admission = connect_user_data(client_id, &transport).and_then(|user_data| {
+                    check_admission(client_id, &user_data, ip, &access_list, &settings)?;
+                    let reclaimed =
+                        lobby.reclaim_player(client_id, user_data.session_token.as_ref())?;
+                    Ok((user_data, reclaimed))
+                });
+                let (user_data, reclaimed) = match admission {
+                    Ok(admitted) => admitted,
+                    Err(reason) => {
+                        info!("Rejecting client {client_id}: {reason}");
+                        disconnect_writer.write(DisconnectClient {
+                            client_id: *client_id,
+                            reason,
+                        });
+                        continue;
+                    }
+                };
+
+                // Le joueur retrouve son entité et son `PlayerInfo` inchangés : les
+                // `user_data` de la reconnexion (nom, couleur, spectateur) sont ignorées.
+                if let Some(entity) = reclaimed {
+                    let position = transforms
+                        .get(entity)
+                        .map(|transform| transform.translation)
+                        .unwrap_or_default();
+                    info!("Client {client_id} reconnected, reclaiming {entity}");
+
+                    game_event_writer.write(GameEvent::PlayerReconnected {
+                        client_id: *client_id,
+                        entity,
+                        position,
+                    });
+                    continue;
+                }
 
-                let entity = spawn_player(
+                if user_data.spectator {
+                    info!("Client {client_id} joined as a spectator");
+                    lobby.add_spectator(client_id);
+                    game_event_writer.write(GameEvent::SpectatorJoined {
+                        client_id: *client_id,
+                    });
+                    continue;
+                }
+
+                if lobby.human_count() >= settings.max_players {
+                    let position = queue.push(client_id);
+                    info!("Server full, client {client_id} queued at position {position}");
+                    game_event_writer.write(GameEvent::PlayerQueued {
+                        client_id: *client_id,
+                        position,
+                        total: queue.clients.len(),
+                    });
+                    continue;
+                }
+
+                create_player(
                     client_id,
-                    position,
+                    &user_data,
                     &mut commands,
                     &mut meshes,
                     &mut materials,
+                    &mut lobby,
+                    &mut game_event_writer,
                 );
-
-                game_event_writer.write(GameEvent::PlayerCreated {
-                    client_id: *client_id,
-                    entity,
-                    position,
-                });
             }
-            ServerEvent::ClientDisconnected { client_id, .. } => {
-                despawn_player(client_id, &mut commands, &mut lobby);
-
-                game_event_writer.write(GameEvent::PlayerRemoved {
-                    client_id: *client_id,
-                });
+            ServerEvent::ClientDisconnected { client_id, reason } => {
+                info!("Client {client_id} disconnected: {reason}");
+                queue.remove(client_id);
+                lobby.forget_client(client_id);
+                lobby.mark_disconnected(client_id, time.elapsed());
             }
         }
     }
 }
 
+/// Admet les clients en attente dès qu'une place se libère dans le `ServerLobby`,
+/// et rappelle périodiquement leur position aux clients restés dans la file.
+#[allow(clippy::too_many_arguments)]
+pub fn update_waiting_queue(
+    mut game_event_writer: MessageWriter<GameEvent>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut lobby: ResMut<ServerLobby>,
+    mut queue: ResMut<WaitingQueue>,
+    transport: Res<NetcodeServerTransport>,
+    settings: Res<ServerSettings>,
+    time: Res<Time>,
+) {
+    let mut admitted = false;
+    while lobby.human_count() < settings.max_players {
+        let Some(client_id) = queue.clients.pop_front() else {
+            break;
+        };
+        info!("Admitting queued client {client_id}");
+        let user_data = connect_user_data(&client_id, &transport).unwrap_or_default();
+        create_player(
+            &client_id,
+            &user_data,
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            &mut lobby,
+            &mut game_event_writer,
+        );
+        admitted = true;
+    }
+
+    let now = time.elapsed();
+    if !admitted && now < queue.next_update {
+        return;
+    }
+    queue.next_update = now + settings.queue_update_interval;
+
+    let total = queue.clients.len();
+    for (index, client_id) in queue.clients.iter().enumerate() {
+        game_event_writer.write(GameEvent::PlayerQueued {
+            client_id: *client_id,
+            position: index + 1,
+            total,
+        });
+    }
+}
+
+/// Retire du serveur les clients qui le quittent volontairement (voir
+/// `ClientMessages::Leave`).
+///
+/// Contrairement à une perte de connexion, le client est déconnecté par le serveur
+/// (`DisconnectReason::Left`) : son joueur est supprimé immédiatement par
+/// `on_disconnect_client`, et sa place est libérée sans attendre le délai de
+/// reconnexion.
+pub fn on_client_leave(
+    mut message_reader: MessageReader<ClientMessageReceived>,
+    mut disconnect_writer: MessageWriter<DisconnectClient>,
+    mut lobby: ResMut<ServerLobby>,
+    mut queue: ResMut<WaitingQueue>,
+) {
+    for ClientMessageReceived { client_id, message } in message_reader.read() {
+        let ClientMessages::Leave = message else {
+            continue;
+        };
+
+        info!("Client {client_id} left the server");
+        queue.remove(client_id);
+        lobby.remove_spectator(client_id);
+        disconnect_writer.write(DisconnectClient {
+            client_id: *client_id,
+            reason: DisconnectReason::Left,
+        });
+    }
+}
+
+/// Supprime définitivement les joueurs déconnectés dont le délai de grâce est écoulé.
+pub fn expire_disconnected_players(
+    mut game_event_writer: MessageWriter<GameEvent>,
+    mut commands: Commands,
+    mut lobby: ResMut<ServerLobby>,
+    settings: Res<ServerSettings>,
+    time: Res<Time>,
+) {
+    for client_id in lobby.expired_players(time.elapsed(), settings.reconnect_grace_period) {
+        despawn_player(&client_id, &mut commands, &mut lobby);
+        lobby.remove_player(&client_id);
+
+        game_event_writer.write(GameEvent::PlayerRemoved { client_id });
+    }
+}
+
+/// Déconnecte les joueurs humains restés inactifs (voir `PlayerActivity`) plus de
+/// `ServerSettings::idle_timeout`.
+///
+/// Le délai d'un joueur part de son admission, puis de son dernier déplacement ou
+/// tir. Les joueurs IA, les spectateurs et les joueurs déconnectés ne sont pas concernés.
+pub fn kick_idle_players(
+    server: Res<RenetServer>,
+    lobby: Res<ServerLobby>,
+    settings: Res<ServerSettings>,
+    time: Res<Time>,
+    mut activity: ResMut<PlayerActivity>,
+    mut disconnect_writer: MessageWriter<DisconnectClient>,
+) {
+    let playing = |client_id: &ClientId| {
+        lobby.players.contains_key(client_id)
+            && !lobby.is_disconnected(client_id)
+            && !is_ai_client(client_id)
+            && server.is_connected(*client_id)
+    };
+    activity
+        .last_active
+        .retain(|client_id, _| playing(client_id));
+    let Some(idle_timeout) = settings.idle_timeout else {
+        return;
+    };
+
+    let now = time.elapsed();
+    for client_id in lobby.players.keys().filter(|client_id| playing(client_id)) {
+        let last_active = *activity.last_active.entry(*client_id).or_insert(now);
+        if now.saturating_sub(last_active) >= idle_timeout {
+            disconnect_writer.write(DisconnectClient {
+                client_id: *client_id,
+                reason: DisconnectReason::Idle,
+            });
+        }
+    }
+}
+
 fn despawn_player(client_id: &ClientId, commands: &mut Commands, lobby: &mut ResMut<ServerLobby>) {
-    info!("Client {client_id} disconnected");
-    if let Some(entity) = lobby.get_player(&client_id) {
+    info!("Client {client_id} did not reconnect in time");
+    if let Some(entity) = lobby.get_player(client_id) {
         commands.entity(*entity).despawn();
     }
 }
+
+/// Crée le joueur d'un client admis dans la partie (ou d'un joueur IA), dans
+/// l'équipe la moins nombreuse, et l'ajoute au `ServerLobby`.
+pub fn create_player(
+    client_id: &ClientId,
+    user_data: &ConnectUserData,
+    commands: &mut Commands,
+    meshes: &mut ResMut<Assets<Mesh>>,
+    materials: &mut ResMut<Assets<ColorMaterial>>,
+    lobby: &mut ServerLobby,
+    game_event_writer: &mut MessageWriter<GameEvent>,
+) {
+    let position = spawn_position();
+    let info = player_info_from_user_data(client_id, user_data);
+
+    let entity = spawn_player(info.clone(), position, commands, meshes, materials);
+    let team = lobby.assign_team(client_id);
+    commands.entity(entity).insert((
+        Replicated,
+        MoveDirection::default(),
+        Health::default(),
+        team,
+    ));
+    lobby.add_player(client_id, entity);
+    lobby.open_session(client_id);
+
+    game_event_writer.write(GameEvent::PlayerCreated {
+        client_id: *client_id,
+        entity,
+        position,
+        info,
+    });
+}
+
+/// Position d'apparition d'un joueur, tirée au hasard sur la ligne d'apparition.
+pub fn spawn_position() -> Vec3 {
+    Vec3::new(fastrand::f32() * 800.0 - 400.0, 0.0, 0.0)
+}
+
+/// Décode les `user_data` de la connexion d'un client.
+///
+/// Des données absentes ou invalides donnent des `ConnectUserData` par défaut.
+///
+/// # Retourne
+/// * `Result<ConnectUserData, DisconnectReason>` - `VersionMismatch` si le client
+///   n'utilise pas le `PROTOCOL_VERSION` du serveur.
+fn connect_user_data(
+    client_id: &ClientId,
+    transport: &NetcodeServerTransport,
+) -> Result<ConnectUserData, DisconnectReason> {
+    let Some(user_data) = transport.user_data(*client_id) else {
+        return Ok(ConnectUserData::default());
+    };
+    if ConnectUserData::protocol_version(&user_data) != PROTOCOL_VERSION {
+        return Err(DisconnectReason::VersionMismatch);
+    }
+    Ok(ConnectUserData::from_user_data(&user_data).unwrap_or_default())
+}
+
+/// Vérifie qu'un client d'adresse `ip` peut rejoindre la partie : d'un identifiant
+/// qui n'est pas réservé aux joueurs IA (voir `is_ai_client`), ni banni ni hors de
+/// la liste blanche (voir `AccessList`), et muni du bon mot de passe si le serveur
+/// en exige un.
+fn check_admission(
+    client_id: &ClientId,
+    user_data: &ConnectUserData,
+    ip: Option<IpAddr>,
+    access_list: &AccessList,
+    settings: &ServerSettings,
+) -> Result<(), DisconnectReason> {
+    if is_ai_client(client_id) {
+        return Err(DisconnectReason::ReservedClientId);
+    }
+    access_list.check(client_id, ip)?;
+
+    let Some(password) = &settings.password else {
+        return Ok(());
+    };
+    match &user_data.password_hash {
+        None => Err(DisconnectReason::PasswordRequired),
+        Some(hash) if verify_password(password, *client_id, hash) => Ok(()),
+        Some(_) => Err(DisconnectReason::WrongPassword),
+    }
+}
+
+/// Construit les `PlayerInfo` d'un client à partir des `user_data` de sa connexion.
+///
+/// Le nom est nettoyé ; un nom vide donne le nom par défaut `Player_<client_id>`.
+/// Sans couleur choisie, le serveur en attribue une de sa palette.
+fn player_info_from_user_data(client_id: &ClientId, user_data: &ConnectUserData) -> PlayerInfo {
+    PlayerInfo {
+        id: *client_id,
+        name: sanitize_player_name(&user_data.name, client_id),
+        color: user_data
+            .color
+            .unwrap_or_else(|| default_player_color(client_id)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::AI_CLIENT_ID_BASE;
+    use bevy::prelude::{App, Entity, Messages, Mut, Update};
+    use bevy_renet::netcode::{
+        ClientAuthentication, NetcodeClientTransport, ServerAuthentication, ServerConfig,
+    };
+    use bevy_renet::renet::RenetClient;
+    use game_core::client::{hash_password, SessionToken};
+    use game_core::network::{connection_config, get_current_time, get_socket, PROTOCOL_ID};
+    use std::time::Duration;
+
+    fn lobby_app() -> App {
+        let socket = get_socket("127.0.0.1:0".parse().unwrap());
+        let public_addr = socket.local_addr().unwrap();
+        let transport = NetcodeServerTransport::new(
+            ServerConfig {
+                current_time: get_current_time(),
+                max_clients: 4,
+                protocol_id: PROTOCOL_ID,
+                public_addresses: vec![public_addr],
+                authentication: ServerAuthentication::Unsecure,
+            },
+            socket,
+        )
+        .unwrap();
+
+        let mut app = App::new();
+        app.add_message::<ServerEvent>();
+        app.add_message::<GameEvent>();
+        app.add_message::<DisconnectClient>();
+        app.insert_resource(RenetServer::new(connection_config()));
+        app.insert_resource(transport);
+        app.init_resource::<Assets<Mesh>>();
+        app.init_resource::<Assets<ColorMaterial>>();
+        app.init_resource::<ServerLobby>();
+        app.init_resource::<WaitingQueue>();
+        app.init_resource::<AccessList>();
+        app.init_resource::<ServerSettings>();
+        app.init_resource::<Time>();
+        app.add_systems(Update, (on_game_event, expire_disconnected_players));
+        app
+    }
+
+    /// Joueur `client_id` déconnecté à l'instant zéro ; retourne son entité et son
+    /// jeton de session.
+    fn disconnected_player(app: &mut App, client_id: ClientId) -> (Entity, SessionToken) {
+        let entity = app
+            .world_mut()
+            .spawn(Transform::from_xyz(50.0, 20.0, 0.0))
+            .id();
+        let mut lobby = app.world_mut().resource_mut::<ServerLobby>();
+        lobby.add_player(&client_id, entity);
+        let token = lobby.open_session(&client_id).unwrap();
+        lobby.mark_disconnected(&client_id, Duration::ZERO);
+        (entity, token)
+    }
+
+    /// Connecte un client netcode au transport du serveur, puis émet le
+    /// `ServerEvent::ClientConnected` correspondant.
+    fn connect(
+        app: &mut App,
+        client_id: ClientId,
+        user_data: &ConnectUserData,
+    ) -> (RenetClient, NetcodeClientTransport) {
+        let server_addr = app.world().resource::<NetcodeServerTransport>().addresses()[0];
+        let mut client = RenetClient::new(connection_config());
+        let mut client_transport = NetcodeClientTransport::new(
+            get_current_time(),
+            ClientAuthentication::Unsecure {
+                client_id,
+                protocol_id: PROTOCOL_ID,
+                server_addr,
+                user_data: Some(user_data.to_user_data()),
+            },
+            get_socket("127.0.0.1:0".parse().unwrap()),
+        )
+        .unwrap();
+
+        let step = Duration::from_millis(10);
+        for _ in 0..200 {
+            client.update(step);
+            client_transport.update(step, &mut client).unwrap();
+            client_transport.send_packets(&mut client).unwrap();
+            std::thread::sleep(Duration::from_millis(1));
+
+            let world = app.world_mut();
+            world.resource_scope(|world, mut transport: Mut<NetcodeServerTransport>| {
+                let mut server = world.resource_mut::<RenetServer>();
+                server.update(step);
+                transport.update(step, &mut server).unwrap();
+                transport.send_packets(&mut server);
+            });
+            if app
+                .world()
+                .resource::<NetcodeServerTransport>()
+                .user_data(client_id)
+                .is_some()
+            {
+                app.world_mut()
+                    .write_message(ServerEvent::ClientConnected { client_id });
+                return (client, client_transport);
+            }
+        }
+        panic!("Client {client_id} failed to connect");
+    }
+
+    fn game_events(app: &mut App) -> Vec<GameEvent> {
+        app.world_mut()
+            .resource_mut::<Messages<GameEvent>>()
+            .drain()
+            .collect()
+    }
+
+    #[test]
+    fn reconnect_within_grace_period_reclaims_the_entity() {
+        let mut app = lobby_app();
+        let (entity, token) = disconnected_player(&mut app, 7);
+
+        let user_data = ConnectUserData {
+            session_token: Some(token),
+            ..ConnectUserData::default()
+        };
+        let _client = connect(&mut app, 7, &user_data);
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs(5));
+        app.update();
+
+        let events = game_events(&mut app);
+        assert!(matches!(
+            events.as_slice(),
+            [GameEvent::PlayerReconnected { client_id: 7, entity: reclaimed, position }]
+                if *reclaimed == entity && *position == Vec3::new(50.0, 20.0, 0.0)
+        ));
+        let lobby = app.world().resource::<ServerLobby>();
+        assert_eq!(lobby.get_player(&7), Some(&entity));
+        assert!(!lobby.is_disconnected(&7));
+        assert!(app.world().get_entity(entity).is_ok());
+    }
+
+    #[test]
+    fn reconnect_without_session_token_is_rejected() {
+        let mut app = lobby_app();
+        let (entity, _) = disconnected_player(&mut app, 7);
+
+        let _client = connect(&mut app, 7, &ConnectUserData::default());
+        app.update();
+
+        assert!(game_events(&mut app).is_empty());
+        let rejections: Vec<_> = app
+            .world_mut()
+            .resource_mut::<Messages<DisconnectClient>>()
+            .drain()
+            .map(|disconnect| (disconnect.client_id, disconnect.reason))
+            .collect();
+        assert_eq!(rejections, vec![(7, DisconnectReason::SessionInUse)]);
+        let lobby = app.world().resource::<ServerLobby>();
+        assert_eq!(lobby.get_player(&7), Some(&entity));
+        assert!(lobby.is_disconnected(&7));
+    }
+
+    #[test]
+    fn expired_players_are_despawned() {
+        let mut app = lobby_app();
+        let (entity, _) = disconnected_player(&mut app, 7);
+        let grace_period = app
+            .world()
+            .resource::<ServerSettings>()
+            .reconnect_grace_period;
+
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(grace_period - Duration::from_millis(1));
+        app.update();
+        assert!(game_events(&mut app).is_empty());
+        assert!(app.world().get_entity(entity).is_ok());
+
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_millis(1));
+        app.update();
+
+        assert!(matches!(
+            game_events(&mut app).as_slice(),
+            [GameEvent::PlayerRemoved { client_id: 7 }]
+        ));
+        assert!(app.world().get_entity(entity).is_err());
+        let lobby = app.world().resource::<ServerLobby>();
+        assert_eq!(lobby.get_player(&7), None);
+        assert_eq!(lobby.session_token(&7), None);
+    }
+
+    #[test]
+    fn admission_rejects_ai_client_ids() {
+        let settings = ServerSettings::default();
+        let access_list = AccessList::default();
+        let user_data = ConnectUserData::default();
+
+        assert_eq!(
+            check_admission(&42, &user_data, None, &access_list, &settings),
+            Ok(())
+        );
+        assert_eq!(
+            check_admission(
+                &AI_CLIENT_ID_BASE,
+                &user_data,
+                None,
+                &access_list,
+                &settings
+            ),
+            Err(DisconnectReason::ReservedClientId)
+        );
+        assert_eq!(
+            check_admission(&u64::MAX, &user_data, None, &access_list, &settings),
+            Err(DisconnectReason::ReservedClientId)
+        );
+    }
+
+    #[test]
+    fn admission_checks_the_password() {
+        let settings = ServerSettings {
+            password: Some("secret".to_string()),
+            ..ServerSettings::default()
+        };
+        let access_list = AccessList::default();
+        let with_password = |password: &str| ConnectUserData {
+            password_hash: Some(hash_password(password, 42)),
+            ..ConnectUserData::default()
+        };
+
+        assert_eq!(
+            check_admission(&42, &with_password("secret"), None, &access_list, &settings),
+            Ok(())
+        );
+        assert_eq!(
+            check_admission(&42, &with_password("guess"), None, &access_list, &settings),
+            Err(DisconnectReason::WrongPassword)
+        );
+        assert_eq!(
+            check_admission(
+                &42,
+                &ConnectUserData::default(),
+                None,
+                &access_list,
+                &settings
+            ),
+            Err(DisconnectReason::PasswordRequired)
+        );
+    }
+}
@@ -0,0 +1,37 @@
+use crate::resource::ServerSettings;
+use bevy::asset::Assets;
+use bevy::log::{info, warn};
+use bevy::mesh::Mesh;
+use bevy::prelude::{ColorMaterial, Commands, Res, ResMut};
+use game_core::map::wall_bundle;
+
+/// Crée les murs de la carte `ServerSettings::map`.
+///
+/// Ses caisses sont créées par `setup_physics`, et ignorées sans la feature `physics`.
+pub fn spawn_map(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    settings: Res<ServerSettings>,
+) {
+    let map = &settings.map;
+    info!(
+        "Loading a {}x{} map with {} walls",
+        map.size[0],
+        map.size[1],
+        map.walls.len()
+    );
+    for wall in &map.walls {
+        let Some(bundle) = wall_bundle(wall, &mut meshes, &mut materials) else {
+            warn!("Ignoring non-convex wall {wall:?}");
+            continue;
+        };
+        commands.spawn(bundle);
+    }
+    if !cfg!(feature = "physics") && !map.crates.is_empty() {
+        info!(
+            "Ignoring {} crates: the server is built without the physics feature",
+            map.crates.len()
+        );
+    }
+}
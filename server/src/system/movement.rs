@@ -0,0 +1,369 @@
+use crate::anti_cheat::{CheatViolation, InputTracker};
+use crate::resource::{
+    AiPlayers, CheatScores, InputTrackers, PlayerActivity, ServerLobby, ServerSettings,
+};
+use bevy::log::{debug, warn};
+use bevy::prelude::{
+    EulerRot, MessageReader, MessageWriter, Query, Res, ResMut, Time, Transform, Vec2, Without,
+};
+use bevy_renet::renet::{ClientId, RenetServer};
+use game_core::collision::{
+    push_out_of_bodies, resolve_static_collisions, separate_circles, ColliderShape,
+    PLAYER_COLLISION_PASSES,
+};
+use game_core::event::server_event::{PlayerInputReceived, ShotFired};
+use game_core::map::StaticCollider;
+use game_core::physics::{BodyKind, PhysicsBody};
+use game_core::player::{
+    clamp_move_direction, move_player, MoveDirection, PlayerInfo, PLAYER_RADIUS,
+};
+use game_core::projectile::fire_direction;
+
+/// Marge tolérée sur la norme d'une direction, pour les erreurs d'arrondi du client.
+const DIRECTION_TOLERANCE: f32 = 1e-3;
+
+/// Accorde à chaque client l'entrée d'un tick fixe écoulé.
+///
+/// S'exécute dans `FixedUpdate` : le crédit d'entrées d'un client suit ainsi
+/// l'horloge du serveur, et non celle, falsifiable, du client. Les joueurs IA
+/// sont soumis au même crédit.
+pub fn grant_input_ticks(
+    server: Res<RenetServer>,
+    settings: Res<ServerSettings>,
+    ai_players: Res<AiPlayers>,
+    mut trackers: ResMut<InputTrackers>,
+) {
+    trackers.clients.retain(|client_id, _| {
+        server.is_connected(*client_id) || ai_players.players.contains_key(client_id)
+    });
+
+    for tracker in trackers.clients.values_mut() {
+        tracker.tick(settings.input_tick_burst);
+    }
+}
+
+/// Valide les entrées reçues, met à jour la `MoveDirection` des joueurs et émet
+/// leurs tirs en `ShotFired`.
+///
+/// Une entrée est ignorée si elle est rejouée, si le client a envoyé plus d'entrées
+/// que de ticks écoulés (speedhack), ou si sa direction n'est pas finie. Une
+/// direction de norme supérieure à 1 est ramenée à 1. Un tir est ignoré si sa
+/// direction est nulle ou non finie, ou s'il ne respecte pas la cadence de tir.
+/// Chaque violation augmente le score de triche du client, journalisé lorsqu'il
+/// franchit `ServerSettings::cheat_score_threshold`. Un déplacement valide ou un tir
+/// accepté compte comme une activité du joueur (voir `PlayerActivity`) : des tirs
+/// refusés ne retardent pas l'expulsion d'un joueur inactif.
+#[allow(clippy::too_many_arguments)]
+pub fn validate_player_inputs(
+    mut input_reader: MessageReader<PlayerInputReceived>,
+    mut shot_writer: MessageWriter<ShotFired>,
+    mut trackers: ResMut<InputTrackers>,
+    mut cheat_scores: ResMut<CheatScores>,
+    settings: Res<ServerSettings>,
+    lobby: Res<ServerLobby>,
+    time: Res<Time>,
+    mut activity: ResMut<PlayerActivity>,
+    mut players: Query<&mut MoveDirection>,
+) {
+    for PlayerInputReceived { client_id, input } in input_reader.read() {
+        let Some(mut move_direction) = lobby
+            .get_player(client_id)
+            .and_then(|entity| players.get_mut(*entity).ok())
+        else {
+            continue;
+        };
+
+        let tracker = trackers
+            .clients
+            .entry(*client_id)
+            .or_insert_with(|| InputTracker::new(settings.input_tick_burst));
+        if let Err(violation) = tracker.accept(input.sequence) {
+            flag_client(client_id, violation, &mut cheat_scores, &settings);
+            continue;
+        }
+
+        let Some(direction) = clamp_move_direction(input.direction) else {
+            flag_client(
+                client_id,
+                CheatViolation::MalformedInput,
+                &mut cheat_scores,
+                &settings,
+            );
+            continue;
+        };
+        if Vec2::from_array(input.direction).length() > 1.0 + DIRECTION_TOLERANCE {
+            flag_client(
+                client_id,
+                CheatViolation::MalformedInput,
+                &mut cheat_scores,
+                &settings,
+            );
+        }
+
+        move_direction.0 = direction;
+        if direction != Vec2::ZERO {
+            activity.last_active.insert(*client_id, time.elapsed());
+        }
+
+        let Some(fire) = input.fire else {
+            continue;
+        };
+        let Some(direction) = fire_direction(fire) else {
+            flag_client(
+                client_id,
+                CheatViolation::MalformedInput,
+                &mut cheat_scores,
+                &settings,
+            );
+            continue;
+        };
+        if let Err(violation) = tracker.accept_fire(input.sequence) {
+            flag_client(client_id, violation, &mut cheat_scores, &settings);
+            continue;
+        }
+        activity.last_active.insert(*client_id, time.elapsed());
+        shot_writer.write(ShotFired {
+            client_id: *client_id,
+            shot_id: input.sequence,
+            direction,
+        });
+    }
+}
+
+/// Déplace les joueurs connectés selon leur `MoveDirection`.
+///
+/// S'exécute dans `FixedUpdate` : la vitesse d'un joueur ne dépend que de
+/// l'horloge du serveur. Un joueur déconnecté reste immobile.
+pub fn move_players(
+    time: Res<Time>,
+    lobby: Res<ServerLobby>,
+    mut players: Query<(&PlayerInfo, &MoveDirection, &mut Transform)>,
+) {
+    for (info, direction, mut transform) in players.iter_mut() {
+        if direction.0 == Vec2::ZERO || lobby.is_disconnected(&info.id) {
+            continue;
+        }
+        move_player(&mut transform, direction.0, time.delta_secs());
+    }
+}
+
+/// Les corps dynamiques sont simulés avec la feature `physics` : les joueurs les
+/// poussent pendant le pas de simulation, et en sont séparés ensuite (voir
+/// `separate_players_from_bodies`). Sans simulation, ils restent immobiles.
+const SIMULATED_BODIES: bool = cfg!(feature = "physics");
+
+/// Résout les collisions des joueurs après leur déplacement.
+///
+/// S'exécute dans `FixedUpdate`, après `move_players`. Les joueurs qui se
+/// chevauchent sont écartés l'un de l'autre (en `PLAYER_COLLISION_PASSES` passes au
+/// plus), puis chacun est repoussé hors des corps physiques immobiles
+/// (`PhysicsBody` fixes, ou tous sans simulation), hors des murs et ramené dans
+/// l'arène (voir `resolve_static_collisions`). Un joueur déconnecté reste immobile :
+/// il repousse les autres sans être poussé.
+#[allow(clippy::type_complexity)]
+pub fn resolve_collisions(
+    settings: Res<ServerSettings>,
+    lobby: Res<ServerLobby>,
+    walls: Query<(&Transform, &StaticCollider), Without<PlayerInfo>>,
+    bodies: Query<(&Transform, &PhysicsBody), (Without<PlayerInfo>, Without<StaticCollider>)>,
+    mut players: Query<
+        (&PlayerInfo, &mut Transform),
+        (Without<StaticCollider>, Without<PhysicsBody>),
+    >,
+) {
+    for _ in 0..PLAYER_COLLISION_PASSES {
+        let mut separated = false;
+        let mut pairs = players.iter_combinations_mut();
+        while let Some([(info_a, mut transform_a), (info_b, mut transform_b)]) = pairs.fetch_next()
+        {
+            let share_a = match (
+                lobby.is_disconnected(&info_a.id),
+                lobby.is_disconnected(&info_b.id),
+            ) {
+                (false, false) => 0.5,
+                (false, true) => 1.0,
+                (true, false) => 0.0,
+                (true, true) => continue,
+            };
+
+            let mut a = transform_a.translation.truncate();
+            let mut b = transform_b.translation.truncate();
+            if separate_circles(&mut a, PLAYER_RADIUS, &mut b, PLAYER_RADIUS, share_a) {
+                transform_a.translation = a.extend(transform_a.translation.z);
+                transform_b.translation = b.extend(transform_b.translation.z);
+                separated = true;
+            }
+        }
+        if !separated {
+            break;
+        }
+    }
+
+    let bodies: Vec<_> = bodies
+        .iter()
+        .filter(|(_, body)| body.kind == BodyKind::Fixed || !SIMULATED_BODIES)
+        .map(body_collider)
+        .collect();
+    resolve_players(&settings, &lobby, &walls, &bodies, &mut players);
+}
+
+/// Sépare les joueurs des corps physiques après le pas de simulation.
+///
+/// Un joueur pousse les corps dynamiques sans être poussé par eux : contre un
+/// corps bloqué (par un mur ou un autre corps), il le traverserait. Il en est
+/// repoussé ici, depuis la position du corps à l'issue du pas, puis à nouveau
+/// hors des murs et dans l'arène.
+#[allow(clippy::type_complexity)]
+pub fn separate_players_from_bodies(
+    settings: Res<ServerSettings>,
+    lobby: Res<ServerLobby>,
+    walls: Query<(&Transform, &StaticCollider), Without<PlayerInfo>>,
+    bodies: Query<(&Transform, &PhysicsBody), (Without<PlayerInfo>, Without<StaticCollider>)>,
+    mut players: Query<
+        (&PlayerInfo, &mut Transform),
+        (Without<StaticCollider>, Without<PhysicsBody>),
+    >,
+) {
+    let bodies: Vec<_> = bodies.iter().map(body_collider).collect();
+    resolve_players(&settings, &lobby, &walls, &bodies, &mut players);
+}
+
+/// Centre, angle et forme d'un corps physique (voir `push_out_of_bodies`).
+fn body_collider<'a>(
+    (transform, body): (&Transform, &'a PhysicsBody),
+) -> (Vec2, f32, &'a ColliderShape) {
+    (
+        transform.translation.truncate(),
+        transform.rotation.to_euler(EulerRot::ZYX).0,
+        &body.shape,
+    )
+}
+
+/// Repousse chaque joueur connecté hors des corps `bodies`, puis hors des murs et
+/// dans l'arène.
+#[allow(clippy::type_complexity)]
+fn resolve_players(
+    settings: &ServerSettings,
+    lobby: &ServerLobby,
+    walls: &Query<(&Transform, &StaticCollider), Without<PlayerInfo>>,
+    bodies: &[(Vec2, f32, &ColliderShape)],
+    players: &mut Query<
+        (&PlayerInfo, &mut Transform),
+        (Without<StaticCollider>, Without<PhysicsBody>),
+    >,
+) {
+    let statics: Vec<_> = walls
+        .iter()
+        .map(|(transform, collider)| (transform.translation.truncate(), &collider.shape))
+        .collect();
+    for (info, mut transform) in players.iter_mut() {
+        if lobby.is_disconnected(&info.id) {
+            continue;
+        }
+        let position = transform.translation.truncate();
+        let resolved = push_out_of_bodies(position, PLAYER_RADIUS, bodies);
+        let resolved =
+            resolve_static_collisions(resolved, PLAYER_RADIUS, &statics, settings.map.half_size());
+        if resolved != position {
+            transform.translation = resolved.extend(transform.translation.z);
+        }
+    }
+}
+
+/// Fait décroître les scores de triche avec le temps.
+pub fn decay_cheat_scores(
+    time: Res<Time>,
+    settings: Res<ServerSettings>,
+    mut cheat_scores: ResMut<CheatScores>,
+) {
+    cheat_scores.decay(settings.cheat_score_decay * time.delta_secs());
+}
+
+/// Ajoute une violation au score de triche d'un client et la journalise.
+fn flag_client(
+    client_id: &ClientId,
+    violation: CheatViolation,
+    cheat_scores: &mut CheatScores,
+    settings: &ServerSettings,
+) {
+    let (score, crossed) = cheat_scores.flag(
+        client_id,
+        violation.weight(),
+        settings.cheat_score_threshold,
+    );
+    debug!("Client {client_id}: {violation} (cheat score {score:.1})");
+    if crossed {
+        warn!("Client {client_id} is suspected of cheating: {violation} (cheat score {score:.1})");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::prelude::{App, Messages, Update};
+    use game_core::client::PlayerInput;
+    use std::time::Duration;
+
+    const CLIENT: ClientId = 1;
+
+    fn input_app() -> App {
+        let mut app = App::new();
+        app.add_message::<PlayerInputReceived>();
+        app.add_message::<ShotFired>();
+        app.init_resource::<InputTrackers>();
+        app.init_resource::<CheatScores>();
+        app.init_resource::<ServerSettings>();
+        app.init_resource::<ServerLobby>();
+        app.init_resource::<PlayerActivity>();
+        app.init_resource::<Time>();
+        app.add_systems(Update, validate_player_inputs);
+
+        let entity = app.world_mut().spawn(MoveDirection::default()).id();
+        app.world_mut()
+            .resource_mut::<ServerLobby>()
+            .add_player(&CLIENT, entity);
+        app
+    }
+
+    /// Envoie un tir immobile à l'instant `secs` et retourne la dernière activité du client.
+    fn fire(app: &mut App, secs: u64, sequence: u32, fire: [f32; 2]) -> Option<Duration> {
+        let elapsed = app.world().resource::<Time>().elapsed();
+        app.world_mut()
+            .resource_mut::<Time>()
+            .advance_by(Duration::from_secs(secs) - elapsed);
+        app.world_mut().write_message(PlayerInputReceived {
+            client_id: CLIENT,
+            input: PlayerInput {
+                sequence,
+                direction: [0.0, 0.0],
+                fire: Some(fire),
+            },
+        });
+        app.update();
+        app.world()
+            .resource::<PlayerActivity>()
+            .last_active
+            .get(&CLIENT)
+            .copied()
+    }
+
+    #[test]
+    fn only_accepted_shots_count_as_activity() {
+        let mut app = input_app();
+
+        // Tir de direction nulle : refusé.
+        assert_eq!(fire(&mut app, 1, 1, [0.0, 0.0]), None);
+        assert_eq!(
+            fire(&mut app, 2, 2, [1.0, 0.0]),
+            Some(Duration::from_secs(2))
+        );
+        // Trop rapproché du précédent : refusé, l'activité n'est pas renouvelée.
+        assert_eq!(
+            fire(&mut app, 3, 3, [1.0, 0.0]),
+            Some(Duration::from_secs(2))
+        );
+
+        let shots = app.world().resource::<Messages<ShotFired>>();
+        assert_eq!(shots.len(), 1);
+    }
+}
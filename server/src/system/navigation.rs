@@ -0,0 +1,34 @@
+use crate::navigation::{NavGrid, NAV_CELL_SIZE};
+use crate::resource::{Navigation, ServerSettings};
+use bevy::log::debug;
+use bevy::prelude::{Changed, Query, RemovedComponents, Res, ResMut, Transform};
+use game_core::map::StaticCollider;
+use game_core::player::PLAYER_RADIUS;
+
+/// Construit la `NavGrid` de l'arène, puis la reconstruit lorsqu'un `StaticCollider`
+/// est ajouté, modifié ou retiré.
+pub fn update_navigation_grid(
+    settings: Res<ServerSettings>,
+    mut navigation: ResMut<Navigation>,
+    colliders: Query<(&Transform, &StaticCollider)>,
+    changed: Query<(), Changed<StaticCollider>>,
+    mut removed: RemovedComponents<StaticCollider>,
+) {
+    let removed = removed.read().count() > 0;
+    if !navigation.grid.is_empty() && changed.is_empty() && !removed {
+        return;
+    }
+
+    navigation.grid = NavGrid::new(
+        settings.map.half_size(),
+        NAV_CELL_SIZE,
+        PLAYER_RADIUS,
+        colliders
+            .iter()
+            .map(|(transform, collider)| (transform.translation.truncate(), &collider.shape)),
+    );
+    debug!(
+        "Navigation grid rebuilt from {} static colliders",
+        colliders.iter().count()
+    );
+}
@@ -0,0 +1,113 @@
+use crate::physics::{Knockback, PhysicsSimulation};
+use crate::resource::{Physics, ServerSettings};
+use bevy::asset::Assets;
+use bevy::log::{info, warn};
+use bevy::mesh::Mesh;
+use bevy::prelude::{
+    Changed, ColorMaterial, Commands, DetectChangesMut, Entity, EulerRot, MessageReader, Quat,
+    Query, RemovedComponents, Res, ResMut, Time, Transform, Vec2, With,
+};
+use game_core::map::StaticCollider;
+use game_core::physics::{body_visual_bundle, BodyKind, PhysicsBody, Velocity};
+use game_core::player::{PlayerInfo, PLAYER_RADIUS};
+use game_core::replication::Replicated;
+
+/// Crée la simulation de l'arène de `ServerSettings::map`, puis les caisses de la
+/// carte, corps dynamiques répliqués.
+pub fn setup_physics(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    settings: Res<ServerSettings>,
+    mut physics: ResMut<Physics>,
+) {
+    let map = &settings.map;
+    physics.simulation = PhysicsSimulation::new(map.half_size());
+    info!("Physics enabled with {} crates", map.crates.len());
+    for map_crate in &map.crates {
+        let body = map_crate.body();
+        commands.spawn((
+            body_visual_bundle(&body, &mut meshes, &mut materials),
+            body,
+            Velocity::default(),
+            Transform::from_translation(Vec2::from_array(map_crate.center).extend(-0.5)),
+            Replicated,
+        ));
+    }
+}
+
+/// Reporte les entités du monde dans la simulation avant chaque pas.
+///
+/// Les `PhysicsBody` et les murs (`StaticCollider`) sont (re)créés lorsqu'ils sont
+/// ajoutés ou modifiés, et les joueurs suivent leur `Transform`, déjà résolue par
+/// `resolve_collisions`. Les entités qui perdent ces composants sont retirées.
+pub fn sync_physics_bodies(
+    mut physics: ResMut<Physics>,
+    bodies: Query<(Entity, &PhysicsBody, &Transform), Changed<PhysicsBody>>,
+    walls: Query<(Entity, &StaticCollider, &Transform), Changed<StaticCollider>>,
+    players: Query<(Entity, &Transform), With<PlayerInfo>>,
+    mut removed_bodies: RemovedComponents<PhysicsBody>,
+    mut removed_walls: RemovedComponents<StaticCollider>,
+    mut removed_players: RemovedComponents<PlayerInfo>,
+) {
+    let simulation = &mut physics.simulation;
+    for entity in removed_bodies
+        .read()
+        .chain(removed_walls.read())
+        .chain(removed_players.read())
+    {
+        simulation.remove(entity);
+    }
+
+    let bodies = bodies
+        .iter()
+        .map(|(entity, body, transform)| (entity, body.kind, &body.shape, transform));
+    let walls = walls
+        .iter()
+        .map(|(entity, collider, transform)| (entity, BodyKind::Fixed, &collider.shape, transform));
+    for (entity, kind, shape, transform) in bodies.chain(walls) {
+        let position = transform.translation.truncate();
+        let rotation = transform.rotation.to_euler(EulerRot::ZYX).0;
+        if !simulation.insert_body(entity, kind, shape, position, rotation) {
+            warn!("Physics ignores {entity}: invalid shape {shape:?}");
+        }
+    }
+
+    for (entity, transform) in players.iter() {
+        let position = transform.translation.truncate();
+        if simulation.contains(entity) {
+            simulation.move_player(entity, position);
+        } else {
+            simulation.insert_player(entity, position, PLAYER_RADIUS);
+        }
+    }
+}
+
+/// Applique les `Knockback`, avance la simulation d'un pas de `FixedUpdate`, puis
+/// reporte la position, la rotation et la vitesse des corps simulés sur leur entité,
+/// d'où elles sont répliquées.
+pub fn step_physics(
+    time: Res<Time>,
+    mut physics: ResMut<Physics>,
+    mut knockbacks: MessageReader<Knockback>,
+    mut bodies: Query<(Entity, &mut Transform, &mut Velocity), With<PhysicsBody>>,
+) {
+    let simulation = &mut physics.simulation;
+    for knockback in knockbacks.read() {
+        simulation.knockback(knockback.entity, knockback.velocity);
+    }
+    simulation.step(time.delta_secs());
+
+    for (entity, mut transform, mut velocity) in bodies.iter_mut() {
+        let Some(state) = simulation.body_state(entity) else {
+            continue;
+        };
+        let translation = state.position.extend(transform.translation.z);
+        let rotation = Quat::from_rotation_z(state.rotation);
+        if transform.translation != translation || transform.rotation != rotation {
+            transform.translation = translation;
+            transform.rotation = rotation;
+        }
+        velocity.set_if_neq(state.velocity);
+    }
+}
@@ -0,0 +1,326 @@
+#[cfg(feature = "physics")]
+use crate::physics::Knockback;
+use crate::resource::{ServerLobby, ServerSettings};
+use crate::system::game_event::spawn_position;
+use bevy::asset::Assets;
+use bevy::mesh::Mesh;
+use bevy::prelude::{
+    ColorMaterial, Commands, Entity, MessageReader, MessageWriter, Query, Res, ResMut, Time,
+    Transform, Vec2, With, Without,
+};
+use bevy_renet::renet::ClientId;
+use game_core::event::game_event::GameEvent;
+use game_core::event::server_event::ShotFired;
+use game_core::map::StaticCollider;
+use game_core::physics::{PhysicsBody, Velocity};
+use game_core::player::{Health, PlayerInfo, PLAYER_RADIUS};
+#[cfg(feature = "physics")]
+use game_core::projectile::PROJECTILE_KNOCKBACK;
+use game_core::projectile::{
+    muzzle_position, projectile_bundle, projectile_hits_shape, sweep_projectile, Projectile,
+    ProjectileLifetime, PROJECTILE_DAMAGE, PROJECTILE_RADIUS,
+};
+use game_core::replication::Replicated;
+
+/// Ce qu'un projectile touche au cours d'un tick.
+enum ProjectileHit {
+    /// Un joueur autre que le tireur.
+    Player(Entity),
+    /// Un corps physique, projeté uniquement avec la feature `physics`.
+    #[cfg_attr(not(feature = "physics"), allow(dead_code))]
+    Body(Entity),
+    /// Un mur ou le bord de l'arène.
+    Obstacle,
+}
+
+/// Crée un projectile répliqué pour chaque tir validé (`ShotFired`), devant le
+/// joueur qui l'a tiré.
+pub fn spawn_projectiles(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut shot_reader: MessageReader<ShotFired>,
+    lobby: Res<ServerLobby>,
+    players: Query<&Transform, With<PlayerInfo>>,
+) {
+    for shot in shot_reader.read() {
+        let Some(transform) = lobby
+            .get_player(&shot.client_id)
+            .and_then(|entity| players.get(*entity).ok())
+        else {
+            continue;
+        };
+
+        let position = muzzle_position(transform.translation.truncate(), shot.direction);
+        commands.spawn((
+            projectile_bundle(
+                Projectile {
+                    owner: shot.client_id,
+                    shot_id: shot.shot_id,
+                },
+                position,
+                shot.direction,
+                &mut meshes,
+                &mut materials,
+            ),
+            Replicated,
+        ));
+    }
+}
+
+/// Avance les projectiles d'un tick fixe et résout leurs impacts.
+///
+/// Le trajet du tick est parcouru par `sweep_projectile` : le projectile disparaît
+/// au premier joueur (hors tireur), mur, corps physique ou bord de l'arène touché,
+/// ou à la fin de sa durée de vie. Les joueurs en attente de reconnexion ne sont
+/// pas des cibles. Un joueur touché perd `PROJECTILE_DAMAGE` points de vie ; à
+/// zéro, il réapparaît avec toute sa vie et un `GameEvent::PlayerKilled` est émis.
+/// Un corps dynamique touché est projeté lorsque la feature `physics` est active.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn move_projectiles(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<ServerSettings>,
+    lobby: Res<ServerLobby>,
+    mut game_event_writer: MessageWriter<GameEvent>,
+    #[cfg(feature = "physics")] mut knockback_writer: MessageWriter<Knockback>,
+    mut projectiles: Query<(
+        Entity,
+        &Projectile,
+        &Velocity,
+        &mut ProjectileLifetime,
+        &mut Transform,
+    )>,
+    mut players: Query<(Entity, &PlayerInfo, &mut Health, &mut Transform), Without<Projectile>>,
+    walls: Query<(&StaticCollider, &Transform), (Without<Projectile>, Without<PlayerInfo>)>,
+    bodies: Query<(Entity, &PhysicsBody, &Transform), (Without<Projectile>, Without<PlayerInfo>)>,
+) {
+    let delta_secs = time.delta_secs();
+    let arena_half_size = settings.map.half_size();
+    let targets: Vec<(Entity, ClientId, Vec2)> = players
+        .iter()
+        .filter(|(_, info, ..)| !lobby.is_disconnected(&info.id))
+        .map(|(entity, info, _, transform)| (entity, info.id, transform.translation.truncate()))
+        .collect();
+
+    for (entity, projectile, velocity, mut lifetime, mut transform) in projectiles.iter_mut() {
+        lifetime.0 -= delta_secs;
+        if lifetime.0 <= 0.0 {
+            commands.entity(entity).despawn();
+            continue;
+        }
+
+        let start = transform.translation.truncate();
+        let end = start + velocity.linear * delta_secs;
+        let hit = sweep_projectile(start, end, |position| {
+            if position.abs().cmpgt(arena_half_size).any() {
+                return Some(ProjectileHit::Obstacle);
+            }
+            if let Some((player, ..)) = targets.iter().find(|(_, client_id, center)| {
+                *client_id != projectile.owner
+                    && position.distance(*center) < PLAYER_RADIUS + PROJECTILE_RADIUS
+            }) {
+                return Some(ProjectileHit::Player(*player));
+            }
+            if walls.iter().any(|(collider, wall)| {
+                projectile_hits_shape(
+                    position,
+                    wall.translation.truncate(),
+                    wall.rotation,
+                    &collider.shape,
+                )
+            }) {
+                return Some(ProjectileHit::Obstacle);
+            }
+            bodies
+                .iter()
+                .find_map(|(body_entity, body, body_transform)| {
+                    projectile_hits_shape(
+                        position,
+                        body_transform.translation.truncate(),
+                        body_transform.rotation,
+                        &body.shape,
+                    )
+                    .then_some(ProjectileHit::Body(body_entity))
+                })
+        });
+
+        let Some((_, hit)) = hit else {
+            transform.translation = end.extend(transform.translation.z);
+            continue;
+        };
+        commands.entity(entity).despawn();
+
+        match hit {
+            ProjectileHit::Player(player) => {
+                let Ok((_, info, mut health, mut player_transform)) = players.get_mut(player)
+                else {
+                    continue;
+                };
+                if health.damage(PROJECTILE_DAMAGE) {
+                    *health = Health::default();
+                    player_transform.translation = spawn_position();
+                    game_event_writer.write(GameEvent::PlayerKilled {
+                        victim: info.id,
+                        killer: projectile.owner,
+                    });
+                }
+            }
+            #[cfg(feature = "physics")]
+            ProjectileHit::Body(body) => {
+                knockback_writer.write(Knockback {
+                    entity: body,
+                    velocity: velocity.linear.normalize_or_zero() * PROJECTILE_KNOCKBACK,
+                });
+            }
+            #[cfg(not(feature = "physics"))]
+            ProjectileHit::Body(_) | ProjectileHit::Obstacle => {}
+            #[cfg(feature = "physics")]
+            ProjectileHit::Obstacle => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::prelude::{App, Messages, Update, Vec3};
+    use game_core::player::PLAYER_MAX_HEALTH;
+    use game_core::projectile::PROJECTILE_SPEED;
+    use std::time::Duration;
+
+    const SHOOTER: ClientId = 1;
+    const TARGET: ClientId = 2;
+
+    fn projectile_app() -> App {
+        let mut app = App::new();
+        app.add_message::<GameEvent>();
+        #[cfg(feature = "physics")]
+        app.add_message::<Knockback>();
+        app.insert_resource(ServerSettings::default());
+        app.insert_resource(ServerLobby::default());
+        let mut time = Time::<()>::default();
+        time.advance_by(Duration::from_secs_f32(1.0 / 60.0));
+        app.insert_resource(time);
+        app.add_systems(Update, move_projectiles);
+        app
+    }
+
+    fn spawn_player(app: &mut App, id: ClientId, position: Vec3, health: u32) -> Entity {
+        let entity = app
+            .world_mut()
+            .spawn((
+                PlayerInfo {
+                    id,
+                    name: format!("Player {id}"),
+                    color: [255, 255, 255],
+                },
+                Health(health),
+                Transform::from_translation(position),
+            ))
+            .id();
+        app.world_mut()
+            .resource_mut::<ServerLobby>()
+            .add_player(&id, entity);
+        entity
+    }
+
+    /// Projectile du tireur qui se dirige vers la droite depuis `position`.
+    fn spawn_projectile(app: &mut App, position: Vec3) -> Entity {
+        app.world_mut()
+            .spawn((
+                Projectile {
+                    owner: SHOOTER,
+                    shot_id: 0,
+                },
+                Velocity {
+                    linear: Vec2::X * PROJECTILE_SPEED,
+                    angular: 0.0,
+                },
+                ProjectileLifetime(1.0),
+                Transform::from_translation(position),
+            ))
+            .id()
+    }
+
+    fn kills(app: &App) -> Vec<(ClientId, ClientId)> {
+        app.world()
+            .resource::<Messages<GameEvent>>()
+            .iter_current_update_messages()
+            .filter_map(|event| match event {
+                GameEvent::PlayerKilled { victim, killer } => Some((*victim, *killer)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn projectile_damages_the_player_it_hits() {
+        let mut app = projectile_app();
+        spawn_player(&mut app, SHOOTER, Vec3::ZERO, PLAYER_MAX_HEALTH);
+        let target = spawn_player(
+            &mut app,
+            TARGET,
+            Vec3::new(100.0, 0.0, 0.0),
+            PLAYER_MAX_HEALTH,
+        );
+        let projectile = spawn_projectile(&mut app, Vec3::new(40.0, 0.0, 0.0));
+
+        app.update();
+
+        assert_eq!(
+            app.world().get::<Health>(target),
+            Some(&Health(PLAYER_MAX_HEALTH - PROJECTILE_DAMAGE))
+        );
+        assert!(app.world().get_entity(projectile).is_err());
+        assert!(kills(&app).is_empty());
+    }
+
+    #[test]
+    fn killed_player_respawns_with_full_health() {
+        let mut app = projectile_app();
+        let target = spawn_player(
+            &mut app,
+            TARGET,
+            Vec3::new(100.0, 50.0, 0.0),
+            PROJECTILE_DAMAGE,
+        );
+        spawn_projectile(&mut app, Vec3::new(40.0, 50.0, 0.0));
+
+        app.update();
+
+        assert_eq!(app.world().get::<Health>(target), Some(&Health::default()));
+        let position = app.world().get::<Transform>(target).unwrap().translation;
+        assert_eq!(position.y, 0.0);
+        assert_eq!(kills(&app), [(TARGET, SHOOTER)]);
+    }
+
+    #[test]
+    fn shooter_and_disconnected_players_are_not_hit() {
+        let mut app = projectile_app();
+        let shooter = spawn_player(&mut app, SHOOTER, Vec3::ZERO, PLAYER_MAX_HEALTH);
+        let target = spawn_player(
+            &mut app,
+            TARGET,
+            Vec3::new(60.0, 0.0, 0.0),
+            PLAYER_MAX_HEALTH,
+        );
+        app.world_mut()
+            .resource_mut::<ServerLobby>()
+            .mark_disconnected(&TARGET, Duration::ZERO);
+        let projectile = spawn_projectile(&mut app, Vec3::new(30.0, 0.0, 0.0));
+
+        for _ in 0..3 {
+            app.update();
+        }
+
+        assert_eq!(app.world().get::<Health>(shooter), Some(&Health::default()));
+        assert_eq!(app.world().get::<Health>(target), Some(&Health::default()));
+        let position = app
+            .world()
+            .get::<Transform>(projectile)
+            .unwrap()
+            .translation;
+        assert!(position.x > 60.0);
+    }
+}
@@ -0,0 +1,80 @@
+use crate::resource::{ReplayRecorder, ServerSettings, ServerTick};
+use bevy::log::{error, info};
+use bevy::prelude::{Fixed, Res, ResMut, Time};
+use game_core::replay::{
+    protocol_fingerprint, ReplayHeader, ReplayRecord, ReplayWriter, REPLAY_EXTENSION,
+};
+use game_core::replication::ReplicationRegistry;
+
+/// Avance le tick du serveur ; s'exécute dans `FixedUpdate`.
+pub fn advance_server_tick(mut tick: ResMut<ServerTick>) {
+    tick.0 += 1;
+}
+
+/// Ouvre le fichier de replay de la partie dans `ServerSettings::replay_dir`.
+///
+/// Le fichier est nommé d'après l'heure de début de l'enregistrement. En cas
+/// d'échec, l'erreur est journalisée et la partie n'est pas enregistrée.
+pub fn start_replay_recording(
+    settings: Res<ServerSettings>,
+    registry: Res<ReplicationRegistry>,
+    time: Res<Time<Fixed>>,
+    mut recorder: ResMut<ReplayRecorder>,
+) {
+    let Some(dir) = &settings.replay_dir else {
+        return;
+    };
+
+    let tick_rate = 1.0 / time.timestep().as_secs_f64();
+    let header = ReplayHeader::new(protocol_fingerprint(&registry), tick_rate);
+    let path = dir.join(format!("match_{}.{REPLAY_EXTENSION}", header.started_at));
+
+    let writer = std::fs::create_dir_all(dir)
+        .map_err(|err| format!("Failed to create {}: {err}", dir.display()))
+        .and_then(|_| ReplayWriter::create(&path, &header));
+    match writer {
+        Ok(writer) => {
+            info!("Recording replay to {}", path.display());
+            recorder.writer = Some(writer);
+            recorder.keyframe_interval =
+                ((settings.replay_keyframe_interval.as_secs_f64() * tick_rate).ceil() as u64)
+                    .max(1);
+        }
+        Err(err) => error!("Replay recording disabled: {err}"),
+    }
+}
+
+/// Écrit dans le replay les messages de la frame et, s'il est dû, l'état complet.
+///
+/// S'exécute après `replicate_components`, qui enregistre la réplication et
+/// construit les états complets. Une erreur d'écriture arrête l'enregistrement.
+pub fn write_replay(tick: Res<ServerTick>, mut recorder: ResMut<ReplayRecorder>) {
+    let recorder = &mut *recorder;
+    let Some(writer) = &mut recorder.writer else {
+        return;
+    };
+
+    let mut records = Vec::new();
+    if !recorder.pending.is_empty() {
+        records.push(ReplayRecord::Messages {
+            tick: tick.0,
+            messages: std::mem::take(&mut recorder.pending),
+        });
+    }
+    if let Some(messages) = recorder.keyframe.take() {
+        records.push(ReplayRecord::Keyframe {
+            tick: tick.0,
+            messages,
+        });
+        recorder.next_keyframe = tick.0 + recorder.keyframe_interval;
+    }
+
+    for record in &records {
+        if let Err(err) = writer.write(record) {
+            error!("Replay recording stopped: {err}");
+            recorder.writer = None;
+            recorder.pending.clear();
+            return;
+        }
+    }
+}
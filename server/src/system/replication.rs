@@ -0,0 +1,281 @@
+use crate::resource::{
+    ReplayRecorder, ReplicatedComponents, ReplicationState, ServerLobby, ServerSettings, ServerTick,
+};
+use crate::system::server_event::{
+    broadcast_networked_entities, broadcast_server_message, send_server_message_to_client,
+};
+use bevy::prelude::{
+    AppTypeRegistry, ChildOf, Children, Entity, EntityRef, Mut, Transform, With, World,
+};
+use bevy_renet::renet::RenetServer;
+use game_core::network::serialize_server_message;
+use game_core::player::PlayerInfo;
+use game_core::replication::{Replicated, ReplicationId, ReplicationRegistry};
+use game_core::server::ServerMessages;
+use std::collections::{HashMap, HashSet};
+
+/// Réplique les composants enregistrés des entités `Replicated` vers les clients.
+///
+/// Les composants modifiés depuis la dernière exécution (via `serde` ou par
+/// réflexion) sont sérialisés ; les autres reprennent les octets déjà envoyés.
+/// L'état obtenu est comparé au dernier état envoyé : seules les différences
+/// (créations, modifications, retraits, suppressions) sont diffusées, sur le canal
+/// fiable. Les modifications des composants non fiables (positions, vitesses) sont
+/// diffusées à part sur le canal `NetworkedEntities` (voir `split_unreliable`).
+/// Un client nouvellement connecté reçoit d'abord l'état complet.
+///
+/// La hiérarchie est répliquée via `ChildOf` : les entités sont envoyées parents
+/// d'abord, et le `ChildOf` d'une entité dont le parent n'est pas répliqué est ignoré.
+///
+/// La différence est aussi confiée au `ReplayRecorder`, avec un état complet
+/// (joueurs du lobby et composants répliqués) lorsqu'il est dû.
+pub fn replicate_components(world: &mut World) {
+    let registry = world.get_resource_or_init::<ReplicationRegistry>().clone();
+    let type_registry = world.get_resource_or_init::<AppTypeRegistry>().clone();
+    let type_registry = type_registry.read();
+    let child_of = registry.id_of::<ChildOf>();
+
+    let last_run = world.last_change_tick();
+    let this_run = world.read_change_tick();
+    let mut current: HashMap<Entity, ReplicatedComponents> = HashMap::new();
+    let mut query = world.query_filtered::<EntityRef, With<Replicated>>();
+    let state = world.resource::<ReplicationState>();
+    for entity in query.iter(world) {
+        let sent = entity
+            .get_change_ticks::<Replicated>()
+            .is_some_and(|ticks| !ticks.is_added(last_run, this_run))
+            .then(|| state.sent.get(&entity.id()))
+            .flatten();
+        let mut components: ReplicatedComponents = registry
+            .iter()
+            .filter_map(|(id, rule)| {
+                let changed = (rule.changed)(&entity, last_run, this_run)?;
+                let cached = sent.and_then(|sent| sent.get(&id)).filter(|_| !changed);
+                match cached {
+                    Some(data) => Some((id, data.clone())),
+                    None => (rule.serialize)(&entity, &type_registry).map(|data| (id, data)),
+                }
+            })
+            .collect();
+        if let Some(child_of) = child_of
+            && entity
+                .get::<ChildOf>()
+                .is_some_and(|parent| !world.entity(parent.parent()).contains::<Replicated>())
+        {
+            components.remove(&child_of);
+        }
+        current.insert(entity.id(), components);
+    }
+    let order = hierarchy_order(world, &current);
+    record_keyframe(world, &current, &order);
+
+    let unreliable: HashSet<ReplicationId> = registry
+        .iter()
+        .filter(|(_, rule)| rule.unreliable)
+        .map(|(id, _)| id)
+        .collect();
+
+    world.resource_scope(|world, mut state: Mut<ReplicationState>| {
+        let mut diff = state.diff(&current, &order);
+        let (frame, changes) = state.split_unreliable(&mut diff, &current, &unreliable);
+        let frame =
+            (!changes.is_empty()).then_some(ServerMessages::ReplicationFrame { frame, changes });
+        world.resource_scope(|world, mut recorder: Mut<ReplayRecorder>| {
+            let mut server = world.resource_mut::<RenetServer>();
+
+            // Un nouveau client reçoit d'abord l'état déjà envoyé aux autres, puis la
+            // même différence qu'eux.
+            state
+                .clients
+                .retain(|client_id| server.is_connected(*client_id));
+            for client_id in server.clients_id() {
+                if !state.clients.insert(client_id) {
+                    continue;
+                }
+                let update = ReplicationState::full(&state.sent, &order);
+                if !update.is_empty() {
+                    send_server_message_to_client(
+                        &client_id,
+                        &ServerMessages::Replication { update },
+                        &mut server,
+                        &mut recorder,
+                    );
+                }
+            }
+            if !diff.is_empty() {
+                broadcast_server_message(
+                    &mut server,
+                    &mut recorder,
+                    &ServerMessages::Replication { update: diff },
+                );
+            }
+            if let Some(frame) = &frame {
+                broadcast_networked_entities(&mut server, &mut recorder, frame);
+            }
+        });
+
+        state.sent = current;
+    });
+}
+
+/// Construit l'état complet du replay s'il est dû : la carte, la création des
+/// joueurs du lobby, puis l'état complet des composants répliqués.
+fn record_keyframe(
+    world: &mut World,
+    current: &HashMap<Entity, ReplicatedComponents>,
+    order: &[Entity],
+) {
+    let tick = world.resource::<ServerTick>().0;
+    if !world.resource::<ReplayRecorder>().keyframe_due(tick) {
+        return;
+    }
+
+    let map = world.resource::<ServerSettings>().map.clone();
+    let mut messages = vec![serialize_server_message(&ServerMessages::Map { map })];
+    messages.extend(world.resource::<ServerLobby>().players.iter().filter_map(
+        |(client_id, entity)| {
+            let transform = world.get::<Transform>(*entity)?;
+            let info = world.get::<PlayerInfo>(*entity)?;
+            Some(serialize_server_message(&ServerMessages::PlayerCreate {
+                client_id: *client_id,
+                position: transform.translation,
+                entity: *entity,
+                info: info.clone(),
+            }))
+        },
+    ));
+    messages.push(serialize_server_message(&ServerMessages::Replication {
+        update: ReplicationState::full(current, order),
+    }));
+
+    world.resource_mut::<ReplayRecorder>().keyframe = Some(messages);
+}
+
+/// Ordonne les entités répliquées en profondeur : chaque parent précède ses enfants,
+/// rangés dans l'ordre de ses `Children`.
+fn hierarchy_order(world: &World, current: &HashMap<Entity, ReplicatedComponents>) -> Vec<Entity> {
+    let is_root = |entity: &&Entity| {
+        world
+            .get::<ChildOf>(**entity)
+            .is_none_or(|child_of| !current.contains_key(&child_of.parent()))
+    };
+    let mut stack: Vec<Entity> = current.keys().filter(is_root).copied().collect();
+    stack.sort_unstable_by_key(|entity| std::cmp::Reverse(*entity));
+
+    let mut order = Vec::with_capacity(current.len());
+    while let Some(entity) = stack.pop() {
+        order.push(entity);
+        if let Some(children) = world.get::<Children>(entity) {
+            stack.extend(
+                children
+                    .iter()
+                    .rev()
+                    .filter(|child| current.contains_key(child)),
+            );
+        }
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::prelude::{App, Update};
+    use game_core::network::connection_config;
+    use game_core::player::Health;
+    use game_core::replication::ReplicationPlugin;
+
+    fn replication_app() -> App {
+        let mut app = App::new();
+        app.add_plugins(ReplicationPlugin)
+            .insert_resource(RenetServer::new(connection_config()))
+            .init_resource::<ReplicationState>()
+            .init_resource::<ReplayRecorder>()
+            .init_resource::<ServerTick>()
+            .init_resource::<ServerSettings>()
+            .init_resource::<ServerLobby>()
+            .add_systems(Update, replicate_components);
+        app
+    }
+
+    fn sent(app: &App, entity: Entity) -> Option<Vec<u8>> {
+        let health = app
+            .world()
+            .resource::<ReplicationRegistry>()
+            .id_of::<Health>()?;
+        app.world()
+            .resource::<ReplicationState>()
+            .sent
+            .get(&entity)?
+            .get(&health)
+            .cloned()
+    }
+
+    #[test]
+    fn only_changed_components_are_serialized() {
+        let mut app = replication_app();
+        let entity = app.world_mut().spawn((Replicated, Health(50))).id();
+        app.update();
+        let serialized = sent(&app, entity).unwrap();
+
+        // Une valeur envoyée factice n'est pas écrasée tant que le composant ne change pas.
+        let health = app
+            .world()
+            .resource::<ReplicationRegistry>()
+            .id_of::<Health>()
+            .unwrap();
+        app.world_mut()
+            .resource_mut::<ReplicationState>()
+            .sent
+            .get_mut(&entity)
+            .unwrap()
+            .insert(health, vec![0xaa]);
+        app.update();
+        assert_eq!(sent(&app, entity), Some(vec![0xaa]));
+
+        app.world_mut().get_mut::<Health>(entity).unwrap().0 = 50;
+        app.update();
+        assert_eq!(sent(&app, entity), Some(serialized));
+    }
+
+    #[test]
+    fn removed_and_despawned_entities_leave_the_state() {
+        let mut app = replication_app();
+        let removed = app.world_mut().spawn((Replicated, Health(50))).id();
+        let despawned = app.world_mut().spawn((Replicated, Health(50))).id();
+        app.update();
+
+        app.world_mut().entity_mut(removed).remove::<Health>();
+        app.world_mut().despawn(despawned);
+        app.update();
+
+        let state = app.world().resource::<ReplicationState>();
+        assert_eq!(sent(&app, removed), None);
+        assert!(state.sent.contains_key(&removed));
+        assert!(!state.sent.contains_key(&despawned));
+    }
+
+    #[test]
+    fn hierarchy_order_puts_parents_first() {
+        let mut world = World::new();
+        let root = world.spawn_empty().id();
+        let first = world.spawn(ChildOf(root)).id();
+        let second = world.spawn(ChildOf(root)).id();
+        let grandchild = world.spawn(ChildOf(first)).id();
+        // Le parent d'un orphelin n'est pas répliqué : il est traité comme une racine.
+        let hidden = world.spawn_empty().id();
+        let orphan = world.spawn(ChildOf(hidden)).id();
+        let current: HashMap<Entity, ReplicatedComponents> =
+            [grandchild, second, orphan, first, root]
+                .into_iter()
+                .map(|entity| (entity, ReplicatedComponents::new()))
+                .collect();
+
+        let order = hierarchy_order(&world, &current);
+
+        assert_eq!(order.len(), current.len());
+        let tree = order.iter().position(|entity| *entity == root).unwrap();
+        assert_eq!(order[tree..tree + 4], [root, first, grandchild, second]);
+        assert!(order.contains(&orphan));
+    }
+}
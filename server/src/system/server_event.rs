@@ -1,14 +1,21 @@
-use crate::resource::ServerLobby;
-use bevy::prelude::{info, MessageReader, ResMut};
-use bevy_renet::renet::RenetServer;
+use crate::resource::{ReplayRecorder, ServerLobby, ServerSettings};
+use crate::system::admin::SERVER_CHAT_NAME;
+use bevy::prelude::{info, MessageReader, Query, Res, ResMut, Transform};
+use bevy_renet::renet::{ClientId, RenetServer};
 use game_core::event::game_event::GameEvent;
+use game_core::map::GameMap;
 use game_core::network::serialize_server_message;
+use game_core::player::PlayerInfo;
+use game_core::replay::ReplayRecipient;
 use game_core::server::{ServerChannel, ServerMessages};
 
 pub fn on_server_event(
     mut server: ResMut<RenetServer>,
     mut lobby: ResMut<ServerLobby>,
+    mut recorder: ResMut<ReplayRecorder>,
+    settings: Res<ServerSettings>,
     mut game_event_reader: MessageReader<GameEvent>,
+    players: Query<(&Transform, &PlayerInfo)>,
 ) {
     for event in game_event_reader.read() {
         match event {
@@ -16,57 +23,227 @@ pub fn on_server_event(
                 client_id,
                 entity,
                 position,
+                info,
             } => {
                 info!(
-                    "PlayerCreated {:?} {:?} at position : {:?}",
-                    client_id, entity, position
+                    "PlayerCreated {:?} {:?} ({}) at position : {:?}",
+                    client_id, entity, info.name, position
                 );
-                lobby.add_player(client_id, *entity);
+                // Le joueur est déjà dans le lobby : le client le recevra par la
+                // diffusion ci-dessous. Un joueur IA n'a pas de connexion, et un
+                // spectateur qui rejoint la partie connaît déjà le lobby.
+                if server.is_connected(*client_id) && lobby.mark_synced(client_id) {
+                    send_lobby_to_client(
+                        client_id,
+                        Some(client_id),
+                        &settings.map,
+                        &lobby,
+                        &players,
+                        &mut server,
+                        &mut recorder,
+                    );
+                }
+
+                let message = ServerMessages::PlayerCreate {
+                    client_id: *client_id,
+                    position: *position,
+                    entity: *entity,
+                    info: info.clone(),
+                };
+                broadcast_server_message(&mut server, &mut recorder, &message);
 
-                send_server_message_to_client(
+                // Le jeton n'est pas enregistré dans le replay : il suffit, pendant
+                // le délai de grâce, pour se faire passer pour le joueur.
+                if let Some(token) = lobby.session_token(client_id)
+                    && server.is_connected(*client_id)
+                {
+                    let message = serialize_server_message(&ServerMessages::Session { token });
+                    server.send_message(*client_id, ServerChannel::ServerMessages, message);
+                }
+            }
+            GameEvent::PlayerReconnected {
+                client_id,
+                entity,
+                position,
+            } => {
+                info!(
+                    "PlayerReconnected {:?} {:?} at position : {:?}",
+                    client_id, entity, position
+                );
+                lobby.mark_synced(client_id);
+                send_lobby_to_client(
                     client_id,
-                    &ServerMessages::PlayerCreate {
-                        client_id: *client_id,
-                        position: *position,
-                        entity: *entity,
-                    },
+                    None,
+                    &settings.map,
+                    &lobby,
+                    &players,
                     &mut server,
+                    &mut recorder,
                 );
+            }
+            GameEvent::PlayerQueued {
+                client_id,
+                position,
+                total,
+            } => {
+                let message = ServerMessages::QueuePosition {
+                    position: *position as u32,
+                    total: *total as u32,
+                };
+                send_server_message_to_client(client_id, &message, &mut server, &mut recorder);
+            }
+            GameEvent::SpectatorJoined { client_id } => {
+                info!("SpectatorJoined {:?}", client_id);
+                // Un joueur qui passe spectateur connaît déjà le lobby : le lui
+                // renvoyer recréerait les joueurs de son monde local.
+                if lobby.mark_synced(client_id) {
+                    send_lobby_to_client(
+                        client_id,
+                        None,
+                        &settings.map,
+                        &lobby,
+                        &players,
+                        &mut server,
+                        &mut recorder,
+                    );
+                }
 
-                broadcast_server_message(
-                    &mut server,
-                    &ServerMessages::PlayerCreate {
-                        client_id: *client_id,
-                        position: *position,
-                        entity: *entity,
-                    },
-                );
+                let message = ServerMessages::Spectating;
+                send_server_message_to_client(client_id, &message, &mut server, &mut recorder);
             }
             GameEvent::PlayerRemoved { client_id } => {
                 info!("PlayerRemoved {:?}", client_id);
                 lobby.remove_player(client_id);
 
-                broadcast_server_message(
-                    &mut server,
-                    &ServerMessages::PlayerRemove {
-                        client_id: *client_id,
-                    },
-                );
+                let message = ServerMessages::PlayerRemove {
+                    client_id: *client_id,
+                };
+                broadcast_server_message(&mut server, &mut recorder, &message);
+            }
+            GameEvent::PlayerKilled { victim, killer } => {
+                let victim = player_name(victim, &lobby, &players);
+                let killer = player_name(killer, &lobby, &players);
+                info!("PlayerKilled {victim} by {killer}");
+
+                let message = ServerMessages::Chat {
+                    client_id: None,
+                    name: SERVER_CHAT_NAME.to_string(),
+                    text: format!("{killer} eliminated {victim}"),
+                    team_only: false,
+                };
+                broadcast_server_message(&mut server, &mut recorder, &message);
             }
         }
     }
 }
 
-fn broadcast_server_message(server: &mut ResMut<RenetServer>, server_message: &ServerMessages) {
+/// Nom du joueur d'un client, ou son identifiant s'il n'a plus de joueur.
+fn player_name(
+    client_id: &ClientId,
+    lobby: &ServerLobby,
+    players: &Query<(&Transform, &PlayerInfo)>,
+) -> String {
+    lobby
+        .get_player(client_id)
+        .and_then(|entity| players.get(*entity).ok())
+        .map_or_else(|| client_id.to_string(), |(_, info)| info.name.clone())
+}
+
+/// Indique si tous les messages fiables envoyés à un client ont été acquittés.
+///
+/// Un canal fiable libère sa mémoire à l'acquittement : il est vide lorsque sa
+/// mémoire disponible est égale à sa capacité configurée.
+pub fn is_server_messages_flushed(server: &RenetServer, client_id: ClientId) -> bool {
+    let capacity = ServerChannel::channel_config()
+        .into_iter()
+        .find(|config| config.channel_id == u8::from(ServerChannel::ServerMessages))
+        .map(|config| config.max_memory_usage_bytes)
+        .unwrap_or_default();
+
+    server.channel_available_memory(client_id, ServerChannel::ServerMessages) >= capacity
+}
+
+/// Diffuse un message à tous les clients sur le canal fiable et l'enregistre dans
+/// le replay, adressé à tous.
+pub fn broadcast_server_message(
+    server: &mut RenetServer,
+    recorder: &mut ReplayRecorder,
+    server_message: &ServerMessages,
+) {
+    recorder.record(ReplayRecipient::All, server_message);
     let message = serialize_server_message(server_message);
     server.broadcast_message(ServerChannel::ServerMessages, message);
 }
 
-fn send_server_message_to_client(
+/// Diffuse un message sur le canal non fiable `ServerChannel::NetworkedEntities`,
+/// réservé à l'état modifié à chaque frame (voir `ServerMessages::ReplicationFrame`),
+/// et l'enregistre dans le replay, adressé à tous.
+pub fn broadcast_networked_entities(
+    server: &mut RenetServer,
+    recorder: &mut ReplayRecorder,
+    server_message: &ServerMessages,
+) {
+    recorder.record(ReplayRecipient::All, server_message);
+    let message = serialize_server_message(server_message);
+    server.broadcast_message(ServerChannel::NetworkedEntities, message);
+}
+
+/// Envoie un message à un client sur le canal fiable et l'enregistre dans le
+/// replay, adressé à ce client.
+pub fn send_server_message_to_client(
     client_id: &u64,
     server_message: &ServerMessages,
-    server: &mut ResMut<RenetServer>,
+    server: &mut RenetServer,
+    recorder: &mut ReplayRecorder,
 ) {
+    recorder.record(ReplayRecipient::Client(*client_id), server_message);
     let message = serialize_server_message(server_message);
     server.send_message(*client_id, ServerChannel::ServerMessages, message);
 }
+
+/// Envoie à un client la carte, puis la création de tous les joueurs présents dans
+/// le lobby.
+///
+/// Utilisé une seule fois par connexion (voir `ServerLobby::mark_synced`) : à
+/// l'arrivée pour que le nouveau client connaisse les joueurs déjà présents, et
+/// lors d'une reconnexion : le client a perdu son état local et doit
+/// recréer chaque joueur, y compris le sien, à sa position actuelle.
+///
+/// - `except` : joueur à ne pas envoyer, par exemple celui qui vient d'être créé.
+fn send_lobby_to_client(
+    client_id: &u64,
+    except: Option<&ClientId>,
+    map: &GameMap,
+    lobby: &ServerLobby,
+    players: &Query<(&Transform, &PlayerInfo)>,
+    server: &mut RenetServer,
+    recorder: &mut ReplayRecorder,
+) {
+    send_server_message_to_client(
+        client_id,
+        &ServerMessages::Map { map: map.clone() },
+        server,
+        recorder,
+    );
+
+    for (player_id, entity) in lobby.players.iter() {
+        if except == Some(player_id) {
+            continue;
+        }
+        let Ok((transform, info)) = players.get(*entity) else {
+            continue;
+        };
+
+        send_server_message_to_client(
+            client_id,
+            &ServerMessages::PlayerCreate {
+                client_id: *player_id,
+                position: transform.translation,
+                entity: *entity,
+                info: info.clone(),
+            },
+            server,
+            recorder,
+        );
+    }
+}
@@ -0,0 +1,211 @@
+use crate::resource::{
+    ReplayRecorder, ServerSettings, ShutdownPhase, ShutdownSignal, ShutdownState,
+};
+use crate::system::server_event::{broadcast_server_message, is_server_messages_flushed};
+use bevy::app::AppExit;
+use bevy::log::{error, info, warn};
+use bevy::prelude::{MessageReader, MessageWriter, Res, ResMut, Time};
+use bevy_renet::netcode::NetcodeServerTransport;
+use bevy_renet::renet::RenetServer;
+use game_core::event::server_event::{PersistState, ShutdownRequest};
+use game_core::server::{DisconnectReason, ServerMessages};
+
+/// Installe le gestionnaire de Ctrl+C / SIGTERM qui lève le `ShutdownSignal`.
+///
+/// Le `TerminalCtrlCHandlerPlugin` de Bevy doit être désactivé : il installe sinon le
+/// gestionnaire du processus en premier et quitte sans séquence d'arrêt.
+/// En cas d'échec (gestionnaire déjà installé), l'erreur est journalisée et le
+/// serveur reste arrêtable par une `ShutdownRequest` émise autrement (commande `shutdown`).
+pub fn install_signal_handler(signal: &ShutdownSignal) {
+    let signal = signal.clone();
+    if let Err(e) = ctrlc::set_handler(move || signal.raise()) {
+        error!("Impossible d'installer le gestionnaire de signaux: {e}");
+    }
+}
+
+/// Transforme un signal système reçu en `ShutdownRequest`.
+pub fn on_shutdown_signal(
+    signal: Res<ShutdownSignal>,
+    mut shutdown_writer: MessageWriter<ShutdownRequest>,
+) {
+    if signal.take() {
+        shutdown_writer.write(ShutdownRequest {
+            reason: "Server stopped by operator".to_string(),
+            restart_eta: None,
+        });
+    }
+}
+
+/// Démarre la séquence d'arrêt : prévient tous les clients et laisse aux canaux
+/// fiables le temps de se vider.
+pub fn on_shutdown_request(
+    mut shutdown_reader: MessageReader<ShutdownRequest>,
+    mut state: ResMut<ShutdownState>,
+    mut server: ResMut<RenetServer>,
+    mut recorder: ResMut<ReplayRecorder>,
+    settings: Res<ServerSettings>,
+    time: Res<Time>,
+) {
+    for request in shutdown_reader.read() {
+        if state.phase != ShutdownPhase::Running {
+            continue;
+        }
+
+        info!("Shutting down: {}", request.reason);
+        let messages = [
+            ServerMessages::ServerShutdown {
+                reason: request.reason.clone(),
+                restart_eta: request.restart_eta,
+            },
+            ServerMessages::Disconnect {
+                reason: DisconnectReason::Shutdown {
+                    message: request.reason.clone(),
+                },
+            },
+        ];
+        for message in &messages {
+            broadcast_server_message(&mut server, &mut recorder, message);
+        }
+
+        state.phase = ShutdownPhase::Draining {
+            deadline: time.elapsed() + settings.shutdown_flush_timeout,
+        };
+    }
+}
+
+/// Termine la séquence d'arrêt une fois les canaux fiables vidés (ou le délai écoulé) :
+/// déconnecte tous les clients, demande la persistance de l'état puis quitte l'application.
+pub fn finish_shutdown(
+    mut state: ResMut<ShutdownState>,
+    mut server: ResMut<RenetServer>,
+    mut transport: ResMut<NetcodeServerTransport>,
+    mut persist_writer: MessageWriter<PersistState>,
+    mut exit_writer: MessageWriter<AppExit>,
+    settings: Res<ServerSettings>,
+    time: Res<Time>,
+) {
+    let ShutdownPhase::Draining { deadline } = state.phase else {
+        return;
+    };
+
+    let flushed = server
+        .clients_id_iter()
+        .all(|client_id| is_server_messages_flushed(&server, client_id));
+    if !flushed && time.elapsed() < deadline {
+        return;
+    }
+    if !flushed {
+        warn!("Shutdown flush timed out, disconnecting remaining clients");
+    }
+
+    transport.disconnect_all(&mut server);
+
+    if settings.persist_on_shutdown {
+        persist_writer.write(PersistState);
+    }
+
+    exit_writer.write(AppExit::Success);
+    state.phase = ShutdownPhase::Stopped;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::prelude::{App, IntoScheduleConfigs, Messages, Update};
+    use bevy_renet::netcode::{ServerAuthentication, ServerConfig};
+    use game_core::network::{connection_config, get_current_time, get_socket, PROTOCOL_ID};
+    use std::time::Duration;
+
+    fn shutdown_app() -> App {
+        let socket = get_socket("127.0.0.1:0".parse().unwrap());
+        let public_addr = socket.local_addr().unwrap();
+        let transport = NetcodeServerTransport::new(
+            ServerConfig {
+                current_time: get_current_time(),
+                max_clients: 4,
+                protocol_id: PROTOCOL_ID,
+                public_addresses: vec![public_addr],
+                authentication: ServerAuthentication::Unsecure,
+            },
+            socket,
+        )
+        .unwrap();
+
+        let mut app = App::new();
+        app.add_message::<ShutdownRequest>();
+        app.add_message::<PersistState>();
+        app.add_message::<AppExit>();
+        app.insert_resource(RenetServer::new(connection_config()));
+        app.insert_resource(transport);
+        app.init_resource::<ReplayRecorder>();
+        app.init_resource::<ServerSettings>();
+        app.init_resource::<ShutdownState>();
+        app.init_resource::<Time>();
+        app.add_systems(Update, (on_shutdown_request, finish_shutdown).chain());
+        app
+    }
+
+    fn advance(app: &mut App, duration: Duration) {
+        app.world_mut().resource_mut::<Time>().advance_by(duration);
+        app.update();
+    }
+
+    fn phase(app: &App) -> ShutdownPhase {
+        app.world().resource::<ShutdownState>().phase
+    }
+
+    #[test]
+    fn shutdown_drains_clients_then_exits() {
+        let mut app = shutdown_app();
+        // Un client dont les messages fiables ne partent jamais retient le serveur
+        // jusqu'au délai de vidage.
+        app.world_mut()
+            .resource_mut::<RenetServer>()
+            .add_connection(7);
+        app.update();
+        assert_eq!(phase(&app), ShutdownPhase::Running);
+
+        app.world_mut().write_message(ShutdownRequest {
+            reason: "maintenance".to_string(),
+            restart_eta: Some(Duration::from_secs(60)),
+        });
+        advance(&mut app, Duration::from_millis(100));
+        let timeout = app
+            .world()
+            .resource::<ServerSettings>()
+            .shutdown_flush_timeout;
+        assert_eq!(
+            phase(&app),
+            ShutdownPhase::Draining {
+                deadline: Duration::from_millis(100) + timeout,
+            }
+        );
+        assert!(app.world().resource::<Messages<AppExit>>().is_empty());
+
+        advance(&mut app, timeout / 2);
+        assert!(matches!(phase(&app), ShutdownPhase::Draining { .. }));
+
+        advance(&mut app, timeout);
+        assert_eq!(phase(&app), ShutdownPhase::Stopped);
+        assert!(!app.world().resource::<Messages<PersistState>>().is_empty());
+        let exits: Vec<_> = app
+            .world_mut()
+            .resource_mut::<Messages<AppExit>>()
+            .drain()
+            .collect();
+        assert_eq!(exits, vec![AppExit::Success]);
+    }
+
+    #[test]
+    fn shutdown_without_clients_exits_immediately() {
+        let mut app = shutdown_app();
+        app.world_mut().write_message(ShutdownRequest {
+            reason: "maintenance".to_string(),
+            restart_eta: None,
+        });
+        advance(&mut app, Duration::from_millis(100));
+
+        assert_eq!(phase(&app), ShutdownPhase::Stopped);
+        assert!(!app.world().resource::<Messages<AppExit>>().is_empty());
+    }
+}
@@ -0,0 +1,63 @@
+use crate::resource::{ServerLobby, ServerSettings, WaitingQueue};
+use bevy::log::info;
+use bevy::prelude::{Commands, MessageReader, MessageWriter, Res, ResMut};
+use game_core::client::ClientMessages;
+use game_core::event::game_event::GameEvent;
+use game_core::event::server_event::ClientMessageReceived;
+
+/// Traite les demandes de passage entre joueur et spectateur.
+///
+/// - `Spectate` : le joueur est retiré de la partie (ou le client de la file
+///   d'attente) et devient spectateur.
+/// - `JoinGame` : le spectateur est placé dans la `WaitingQueue`, qui l'admet dès
+///   qu'une place est libre ; il reste spectateur en attendant.
+pub fn on_client_spectate(
+    mut message_reader: MessageReader<ClientMessageReceived>,
+    mut game_event_writer: MessageWriter<GameEvent>,
+    mut commands: Commands,
+    mut lobby: ResMut<ServerLobby>,
+    mut queue: ResMut<WaitingQueue>,
+    settings: Res<ServerSettings>,
+) {
+    for ClientMessageReceived { client_id, message } in message_reader.read() {
+        match message {
+            ClientMessages::Spectate => {
+                if lobby.is_spectator(client_id) {
+                    continue;
+                }
+
+                if let Some(entity) = lobby.get_player(client_id).copied() {
+                    info!("Client {client_id} left the game to spectate");
+                    commands.entity(entity).despawn();
+                    lobby.remove_player(client_id);
+                    game_event_writer.write(GameEvent::PlayerRemoved {
+                        client_id: *client_id,
+                    });
+                } else if !queue.remove(client_id) {
+                    continue;
+                }
+
+                lobby.add_spectator(client_id);
+                game_event_writer.write(GameEvent::SpectatorJoined {
+                    client_id: *client_id,
+                });
+            }
+            ClientMessages::JoinGame => {
+                if !lobby.is_spectator(client_id) || queue.position(client_id).is_some() {
+                    continue;
+                }
+
+                let position = queue.push(client_id);
+                info!("Spectator {client_id} asked to play, queued at position {position}");
+                if lobby.human_count() >= settings.max_players {
+                    game_event_writer.write(GameEvent::PlayerQueued {
+                        client_id: *client_id,
+                        position,
+                        total: queue.clients.len(),
+                    });
+                }
+            }
+            ClientMessages::Chat { .. } | ClientMessages::Leave => {}
+        }
+    }
+}